@@ -0,0 +1,325 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use crate::finite_automata::FiniteAutomata;
+use crate::grammar::{format_symbols, Grammar, Symbol, MAX_SENTENTIAL_FORMS};
+use crate::pushdown_automata::MAX_CONFIGURATIONS;
+use crate::state::{Input, StateID};
+use crate::state_machine::StateMachine;
+
+// A move of a finite automaton (from, input, to), the ε-transitions read the empty string.
+type Move = (StateID, Input, StateID);
+
+// A configuration (state, input read) with the index of the previous one and the move taken.
+type Configuration = ((StateID, String), Option<(usize, usize)>);
+
+/* The result of a bounded search of ambiguity: the evidence found, no
+ * evidence up to the bound, or Truncated if the search gave up before
+ * trying every word up to the bound. */
+#[derive(Debug, Clone)]
+pub enum AmbiguitySearch<T> {
+    Found(T),
+    NotFound,
+    Truncated,
+}
+
+impl<T> AmbiguitySearch<T> {
+    // Returns the evidence found, if any.
+    pub fn found(self) -> Option<T> {
+        match self {
+            AmbiguitySearch::Found(evidence) => Some(evidence),
+            _ => None,
+        }
+    }
+}
+
+/* Structure that represents the evidence that a grammar is ambiguous,
+ * a word with two different leftmost derivations. */
+#[derive(Debug, Clone)]
+pub struct GrammarAmbiguity {
+    pub word: Vec<Symbol>,
+    pub derivations: [Vec<Vec<Symbol>>; 2],
+}
+
+impl GrammarAmbiguity {
+    /* Function that searches a word of at most max_length terminals with two
+     * different leftmost derivations. The leftmost derivations are explored
+     * breadth first and every sentential form is expanded only once, so when
+     * a form is reached again by another derivation, both derivations are
+     * completed in the same way to a word, replacing every nonterminal by the
+     * body that derives its shortest string. The forms that can't derive a
+     * word short enough are discarded, and the search gives up after
+     * MAX_SENTENTIAL_FORMS forms, returning Truncated. */
+    pub fn find(grammar: &Grammar, max_length: usize) -> AmbiguitySearch<Self> {
+        let shortest = shortest_productions(grammar);
+        let fits = |form: &[Symbol]| form.iter()
+            .map(|symbol| if grammar.is_nonterminal(symbol) { shortest.get(symbol).map(|(length, _)| *length) } else { Some(1) })
+            .sum::<Option<usize>>()
+            .is_some_and(|length| length <= max_length);
+        let start = vec![grammar.get_start_symbol().clone()];
+        if !fits(&start) {
+            return AmbiguitySearch::NotFound;
+        }
+        // Every form stores the index of the form it was derived from.
+        let mut forms: Vec<(Vec<Symbol>, Option<usize>)> = vec![(start.clone(), None)];
+        let mut indices: HashMap<Vec<Symbol>, usize> = HashMap::new();
+        indices.insert(start, 0);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(0);
+        let mut truncated = false;
+        while let Some(index) = queue.pop_front() {
+            let form = forms[index].0.clone();
+            let position = match form.iter().position(|symbol| grammar.is_nonterminal(symbol)) {
+                Some(position) => position,
+                None => continue,
+            };
+            for production in grammar.productions_of(&form[position]) {
+                let mut next = form[..position].to_vec();
+                next.extend(production.body.iter().cloned());
+                next.extend(form[position + 1..].iter().cloned());
+                if !fits(&next) {
+                    continue;
+                }
+                match indices.get(&next) {
+                    // The same body written twice is not a different derivation.
+                    Some(&other) if forms[other].1 == Some(index) => {}
+                    Some(&other) => {
+                        let mut first = derivation_to(&forms, other);
+                        let mut second = derivation_to(&forms, index);
+                        second.push(next);
+                        complete_derivation(grammar, &shortest, &mut first);
+                        complete_derivation(grammar, &shortest, &mut second);
+                        return AmbiguitySearch::Found(GrammarAmbiguity {
+                            word: first.last().cloned().unwrap_or_default(),
+                            derivations: [first, second],
+                        });
+                    }
+                    None if forms.len() < MAX_SENTENTIAL_FORMS => {
+                        indices.insert(next.clone(), forms.len());
+                        forms.push((next, Some(index)));
+                        queue.push_back(forms.len() - 1);
+                    }
+                    None => truncated = true,
+                }
+            }
+        }
+        if truncated { AmbiguitySearch::Truncated } else { AmbiguitySearch::NotFound }
+    }
+}
+
+/* The derivations are printed as S => aS => ab, one in every line. */
+impl fmt::Display for GrammarAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "The word {} has two leftmost derivations:", format_symbols(&self.word))?;
+        for derivation in self.derivations.iter() {
+            let forms: Vec<String> = derivation.iter().map(|form| format_symbols(form)).collect();
+            writeln!(f, "{}", forms.join(" => "))?;
+        }
+        Ok(())
+    }
+}
+
+/* A run of a finite automaton, the states it goes through and the input
+ * read in every move, so there is one more state than inputs. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutomatonRun {
+    pub states: Vec<StateID>,
+    pub inputs: Vec<Input>,
+}
+
+/* Structure that represents the evidence that a finite automaton is
+ * ambiguous, a word with two different accepting runs. */
+#[derive(Debug, Clone)]
+pub struct AutomatonAmbiguity {
+    pub word: String,
+    pub runs: [AutomatonRun; 2],
+    state_names: HashMap<StateID, String>,
+}
+
+impl AutomatonAmbiguity {
+    /* Function that searches a word of at most max_length characters with
+     * two different accepting runs. The configurations, a state and the
+     * input read, are explored breadth first and every one is expanded only
+     * once, so when a configuration is reached again by another run, both
+     * runs are completed with the same shortest path to a final state. The
+     * ε-transitions count as different moves, so a cycle of ε-transitions
+     * in an accepting run makes the automaton ambiguous. The search gives
+     * up after MAX_CONFIGURATIONS configurations, returning Truncated. */
+    pub fn find(automaton: &FiniteAutomata, max_length: usize) -> AmbiguitySearch<Self> {
+        let initial_id = match automaton.get_initial_state_id() {
+            Some(id) => *id,
+            None => return AmbiguitySearch::NotFound,
+        };
+        let mut moves: Vec<Move> = Vec::new();
+        for (from_id, state) in automaton.get_states_by_id_ref() {
+            for (to_id, labels) in state.iter_by_transition() {
+                for label in labels {
+                    let input = if label == "ε" { String::new() } else { label.clone() };
+                    moves.push((*from_id, input, *to_id));
+                }
+            }
+        }
+        let shortest = shortest_completions(automaton, &moves);
+        let fits = |state_id: &StateID, read: &str| shortest.get(state_id)
+            .is_some_and(|(length, _)| read.chars().count() + length <= max_length);
+        if !fits(&initial_id, "") {
+            return AmbiguitySearch::NotFound;
+        }
+        let mut configurations: Vec<Configuration> = vec![((initial_id, String::new()), None)];
+        let mut indices: HashMap<(StateID, String), usize> = HashMap::new();
+        indices.insert((initial_id, String::new()), 0);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(0);
+        let mut truncated = false;
+        while let Some(index) = queue.pop_front() {
+            let (state_id, read) = configurations[index].0.clone();
+            for (move_index, (_, input, to_id)) in moves.iter().enumerate().filter(|(_, m)| m.0 == state_id) {
+                let next = (*to_id, format!("{}{}", read, input));
+                if !fits(&next.0, &next.1) {
+                    continue;
+                }
+                match indices.get(&next) {
+                    Some(&other) => {
+                        let mut first = run_to(&configurations, &moves, other);
+                        let mut second = run_to(&configurations, &moves, index);
+                        second.states.push(*to_id);
+                        second.inputs.push(input.clone());
+                        complete_run(&shortest, &moves, &mut first);
+                        complete_run(&shortest, &moves, &mut second);
+                        let state_names = automaton.get_states_by_id_ref().iter()
+                            .map(|(id, state)| (*id, state.name.clone()))
+                            .collect();
+                        return AmbiguitySearch::Found(AutomatonAmbiguity {
+                            word: first.inputs.concat(),
+                            runs: [first, second],
+                            state_names,
+                        });
+                    }
+                    None if configurations.len() < MAX_CONFIGURATIONS => {
+                        indices.insert(next.clone(), configurations.len());
+                        configurations.push((next, Some((index, move_index))));
+                        queue.push_back(configurations.len() - 1);
+                    }
+                    None => truncated = true,
+                }
+            }
+        }
+        if truncated { AmbiguitySearch::Truncated } else { AmbiguitySearch::NotFound }
+    }
+}
+
+/* The runs are printed as q0 -a-> q1 -ε-> q2, one in every line. */
+impl fmt::Display for AutomatonAmbiguity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let word = if self.word.is_empty() { "ε" } else { &self.word };
+        writeln!(f, "The word {} has two accepting runs:", word)?;
+        for run in self.runs.iter() {
+            let name_of = |id: &StateID| self.state_names.get(id).cloned().unwrap_or(id.to_string());
+            let mut line = run.states.first().map_or(String::new(), name_of);
+            for (input, state_id) in run.inputs.iter().zip(run.states.iter().skip(1)) {
+                let input = if input.is_empty() { "ε" } else { input };
+                line.push_str(&format!(" -{}-> {}", input, name_of(state_id)));
+            }
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// Returns the length of the shortest word derived from every generating nonterminal,
+// with the index of the production used to derive it. A production is only chosen
+// when it improves the length, so following them always ends in a word.
+fn shortest_productions(grammar: &Grammar) -> HashMap<Symbol, (usize, usize)> {
+    let mut shortest: HashMap<Symbol, (usize, usize)> = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, production) in grammar.get_productions().iter().enumerate() {
+            let length = production.body.iter()
+                .map(|symbol| if grammar.is_nonterminal(symbol) { shortest.get(symbol).map(|(length, _)| *length) } else { Some(1) })
+                .sum::<Option<usize>>();
+            if let Some(length) = length {
+                if shortest.get(&production.head).is_none_or(|(current, _)| length < *current) {
+                    shortest.insert(production.head.clone(), (length, index));
+                    changed = true;
+                }
+            }
+        }
+    }
+    shortest
+}
+
+// Returns the derivation that ends in the form with the index.
+fn derivation_to(forms: &[(Vec<Symbol>, Option<usize>)], index: usize) -> Vec<Vec<Symbol>> {
+    let mut derivation = Vec::new();
+    let mut current = Some(index);
+    while let Some(current_index) = current {
+        derivation.push(forms[current_index].0.clone());
+        current = forms[current_index].1;
+    }
+    derivation.reverse();
+    derivation
+}
+
+// Continues the leftmost derivation until it ends in a word, using the shortest productions.
+fn complete_derivation(grammar: &Grammar, shortest: &HashMap<Symbol, (usize, usize)>, derivation: &mut Vec<Vec<Symbol>>) {
+    while let Some(form) = derivation.last() {
+        let position = match form.iter().position(|symbol| grammar.is_nonterminal(symbol)) {
+            Some(position) => position,
+            None => return,
+        };
+        let production = &grammar.get_productions()[shortest[&form[position]].1];
+        let mut next = form[..position].to_vec();
+        next.extend(production.body.iter().cloned());
+        next.extend(form[position + 1..].iter().cloned());
+        derivation.push(next);
+    }
+}
+
+// Returns the length of the shortest input that takes every state to a final state,
+// with the index of the first move, which is None for the final states.
+fn shortest_completions(automaton: &FiniteAutomata, moves: &[Move]) -> HashMap<StateID, (usize, Option<usize>)> {
+    let mut shortest: HashMap<StateID, (usize, Option<usize>)> = automaton.get_final_states().iter()
+        .map(|id| (*id, (0, None)))
+        .collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (index, (from_id, input, to_id)) in moves.iter().enumerate() {
+            if let Some((length, _)) = shortest.get(to_id) {
+                let length = length + input.chars().count();
+                if shortest.get(from_id).is_none_or(|(current, _)| length < *current) {
+                    shortest.insert(*from_id, (length, Some(index)));
+                    changed = true;
+                }
+            }
+        }
+    }
+    shortest
+}
+
+// Returns the run that ends in the configuration with the index.
+fn run_to(configurations: &[Configuration], moves: &[Move], index: usize) -> AutomatonRun {
+    let mut states = vec![configurations[index].0.0];
+    let mut inputs = Vec::new();
+    let mut current = configurations[index].1;
+    while let Some((previous, move_index)) = current {
+        states.push(configurations[previous].0.0);
+        inputs.push(moves[move_index].1.clone());
+        current = configurations[previous].1;
+    }
+    states.reverse();
+    inputs.reverse();
+    AutomatonRun { states, inputs }
+}
+
+// Continues the run until it ends in a final state, using the shortest completions.
+fn complete_run(shortest: &HashMap<StateID, (usize, Option<usize>)>, moves: &[Move], run: &mut AutomatonRun) {
+    while let Some(state_id) = run.states.last() {
+        let move_index = match shortest.get(state_id) {
+            Some((_, Some(move_index))) => *move_index,
+            _ => return,
+        };
+        run.states.push(moves[move_index].2);
+        run.inputs.push(moves[move_index].1.clone());
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::machine_file::{MachineFile, MachineFileKind, StateEntry, TransitionEntry, FORMAT_VERSION};
+use crate::mealy_machine;
+use crate::pushdown_automata;
+use crate::state::StateID;
+use crate::turing_machine;
+
+/* Words that start a statement, the states with these names are quoted. */
+const KEYWORDS: [&str; 8] = ["type", "stack", "blank", "accept", "initial", "final", "state", "output"];
+
+/* Error returned when the text of an automaton can't be read. The line and
+ * the column start at 1. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutomatonTextError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AutomatonTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+// Reads the characters of the text keeping the line and column of the next one.
+struct Scanner {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Scanner {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_is(&self, string: &str) -> bool {
+        string.chars().enumerate().all(|(offset, c)| self.chars.get(self.index + offset) == Some(&c))
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, AutomatonTextError> {
+        Err(AutomatonTextError { line: self.line, column: self.column, message })
+    }
+
+    // Skips the spaces and the comments until the end of the line.
+    fn skip_blanks(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.bump();
+                }
+            } else if c.is_whitespace() && c != '\n' {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Reads a string between double quotes, where \" is a quote and \\ a backslash.
+    fn quoted(&mut self) -> Result<String, AutomatonTextError> {
+        let (line, column) = self.position();
+        self.bump();
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(string),
+                Some('\\') if matches!(self.peek(), Some('"') | Some('\\')) => string.push(self.bump().unwrap_or('\\')),
+                Some('\n') | None => return Err(AutomatonTextError { line, column, message: "unterminated string".to_string() }),
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    // Reads a name, quoted or made of letters, digits, _, ' and dots. Returns the name
+    // and whether it was quoted.
+    fn name(&mut self) -> Result<(String, bool), AutomatonTextError> {
+        self.skip_blanks();
+        if self.peek() == Some('"') {
+            return Ok((self.quoted()?, true));
+        }
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(|c| is_name_char(*c)) {
+            name.push(c);
+            self.bump();
+        }
+        if name.is_empty() {
+            return match self.peek() {
+                Some('\n') | None => self.error("expected a name".to_string()),
+                Some(c) => self.error(format!("expected a name, found {}", c)),
+            };
+        }
+        Ok((name, false))
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\'' || c == '.'
+}
+
+// A state of the text, with the position where it was used first.
+struct TextState {
+    entry: StateEntry,
+    line: usize,
+    column: usize,
+}
+
+// Builds the machine file while the statements are read.
+struct TextAutomaton {
+    kind: Option<MachineFileKind>,
+    initial_stack_symbol: Option<String>,
+    empty_stack_acceptance: bool,
+    blank_symbol: Option<String>,
+    initial: Option<(StateID, (usize, usize))>,
+    states: Vec<TextState>,
+    ids: HashMap<String, StateID>,
+    // The transitions with the position of their labels, to report the invalid ones.
+    transitions: Vec<(TransitionEntry, (usize, usize))>,
+}
+
+impl TextAutomaton {
+    // Returns the id of the state with the name, adding it if it is new.
+    fn state(&mut self, name: &str, position: (usize, usize)) -> StateID {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.states.len() as StateID;
+        self.states.push(TextState {
+            entry: StateEntry {
+                id,
+                name: name.to_string(),
+                label: Vec::new(),
+                position: None,
+                initial: false,
+                is_final: false,
+                output: None,
+            },
+            line: position.0,
+            column: position.1,
+        });
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    // Reads the state names separated by commas of a final, state or output statement.
+    fn state_list(&mut self, scanner: &mut Scanner) -> Result<Vec<StateID>, AutomatonTextError> {
+        let mut ids = Vec::new();
+        loop {
+            scanner.skip_blanks();
+            let position = scanner.position();
+            let (name, _) = scanner.name()?;
+            ids.push(self.state(&name, position));
+            scanner.skip_blanks();
+            if scanner.peek() != Some(',') {
+                return Ok(ids);
+            }
+            scanner.bump();
+        }
+    }
+
+    fn statement(&mut self, scanner: &mut Scanner) -> Result<(), AutomatonTextError> {
+        let start = scanner.position();
+        let (word, quoted) = scanner.name()?;
+        scanner.skip_blanks();
+        let is_keyword = !quoted && KEYWORDS.contains(&word.as_str()) && scanner.peek() != Some('-');
+        if !is_keyword {
+            return self.transition(scanner, &word, start);
+        }
+        match word.as_str() {
+            "type" => {
+                let position = scanner.position();
+                let (kind, _) = scanner.name()?;
+                let kind = match kind.as_str() {
+                    "finite" => MachineFileKind::Finite,
+                    "pushdown" => MachineFileKind::Pushdown,
+                    "turing" => MachineFileKind::Turing,
+                    "mealy" => MachineFileKind::Mealy,
+                    "moore" => MachineFileKind::Moore,
+                    _ => return Err(AutomatonTextError {
+                        line: position.0,
+                        column: position.1,
+                        message: format!("unknown type {}, expected finite, pushdown, turing, mealy or moore", kind),
+                    }),
+                };
+                if self.kind.is_some() {
+                    return Err(AutomatonTextError { line: start.0, column: start.1, message: "the type is given twice".to_string() });
+                }
+                self.kind = Some(kind);
+            }
+            "stack" => self.initial_stack_symbol = Some(scanner.name()?.0),
+            "blank" => self.blank_symbol = Some(scanner.name()?.0),
+            "accept" => {
+                let position = scanner.position();
+                match scanner.name()?.0.as_str() {
+                    "empty_stack" => self.empty_stack_acceptance = true,
+                    "final_states" => self.empty_stack_acceptance = false,
+                    other => return Err(AutomatonTextError {
+                        line: position.0,
+                        column: position.1,
+                        message: format!("unknown acceptance {}, expected empty_stack or final_states", other),
+                    }),
+                }
+            }
+            "initial" => {
+                let position = scanner.position();
+                let (name, _) = scanner.name()?;
+                if let Some((_, (line, _))) = self.initial {
+                    return Err(AutomatonTextError {
+                        line: start.0,
+                        column: start.1,
+                        message: format!("the initial state is already given in line {}", line),
+                    });
+                }
+                let id = self.state(&name, position);
+                self.initial = Some((id, start));
+            }
+            "final" => {
+                for id in self.state_list(scanner)? {
+                    self.states[id as usize].entry.is_final = true;
+                }
+            }
+            "state" => {
+                self.state_list(scanner)?;
+            }
+            _ => {
+                // An output statement, the state and its output, e.g. output q1 x.
+                let position = scanner.position();
+                let (name, _) = scanner.name()?;
+                let id = self.state(&name, position);
+                scanner.skip_blanks();
+                let output = match scanner.peek() {
+                    Some(';') | Some('\n') | None => String::new(),
+                    _ => scanner.name()?.0,
+                };
+                self.states[id as usize].entry.output = Some(output);
+            }
+        }
+        Ok(())
+    }
+
+    // Reads the rest of a transition statement, e.g. -a,b-> q1, after its first state.
+    fn transition(&mut self, scanner: &mut Scanner, from: &str, start: (usize, usize)) -> Result<(), AutomatonTextError> {
+        if scanner.peek() != Some('-') {
+            return scanner.error(format!("expected -label-> after the state {}", from));
+        }
+        scanner.bump();
+        let mut labels = Vec::new();
+        loop {
+            scanner.skip_blanks();
+            let position = scanner.position();
+            let label = if scanner.peek() == Some('"') {
+                scanner.quoted()?
+            } else {
+                let mut label = String::new();
+                while let Some(c) = scanner.peek() {
+                    if matches!(c, ',' | ';' | '\n' | '"' | '#') || scanner.peek_is("->") {
+                        break;
+                    }
+                    label.push(c);
+                    scanner.bump();
+                }
+                label.trim().to_string()
+            };
+            let label = if label.is_empty() || label == "λ" { "ε".to_string() } else { label };
+            labels.push((label, position));
+            scanner.skip_blanks();
+            if scanner.peek_is("->") {
+                scanner.bump();
+                scanner.bump();
+                break;
+            }
+            if scanner.peek() != Some(',') {
+                return scanner.error("expected , or -> after the label".to_string());
+            }
+            scanner.bump();
+        }
+        scanner.skip_blanks();
+        let to_position = scanner.position();
+        let (to, _) = scanner.name()?;
+        let from_id = self.state(from, start);
+        let to_id = self.state(&to, to_position);
+        for (label, position) in labels {
+            self.transitions.push((TransitionEntry { from: from_id, to: to_id, label }, position));
+        }
+        Ok(())
+    }
+
+    // Checks that the labels can be read by the machine and builds the machine file.
+    fn into_machine_file(mut self) -> Result<MachineFile, AutomatonTextError> {
+        let kind = self.kind.unwrap_or(MachineFileKind::Finite);
+        for (transition, (line, column)) in self.transitions.iter() {
+            let expected = match kind {
+                MachineFileKind::Pushdown if pushdown_automata::parse_transition_label(&transition.label).is_none() => "input;pop/push, e.g. \"a;Z/AZ\"",
+                MachineFileKind::Turing if turing_machine::parse_transition_label(&transition.label).is_none() => "read/write,move, e.g. \"a/X,R\"",
+                MachineFileKind::Mealy if mealy_machine::parse_transition_label(&transition.label).is_none() => "input/output, e.g. a/01",
+                _ => continue,
+            };
+            return Err(AutomatonTextError {
+                line: *line,
+                column: *column,
+                message: format!("invalid label {}, expected {}", transition.label, expected),
+            });
+        }
+        if kind != MachineFileKind::Moore {
+            if let Some(state) = self.states.iter().find(|state| state.entry.output.is_some()) {
+                return Err(AutomatonTextError {
+                    line: state.line,
+                    column: state.column,
+                    message: "only the states of Moore machines have an output".to_string(),
+                });
+            }
+        }
+        if let Some((id, _)) = self.initial {
+            self.states[id as usize].entry.initial = true;
+        }
+        let initial_stack_symbol = match kind {
+            MachineFileKind::Pushdown => Some(self.initial_stack_symbol.unwrap_or_else(|| "Z".to_string())),
+            _ => None,
+        };
+        let blank_symbol = match kind {
+            MachineFileKind::Turing => Some(self.blank_symbol.unwrap_or_else(|| "_".to_string())),
+            _ => None,
+        };
+        Ok(MachineFile {
+            version: FORMAT_VERSION,
+            kind,
+            initial_stack_symbol,
+            empty_stack_acceptance: kind == MachineFileKind::Pushdown && self.empty_stack_acceptance,
+            blank_symbol,
+            states: self.states.into_iter().map(|state| state.entry).collect(),
+            transitions: self.transitions.into_iter().map(|(transition, _)| transition).collect(),
+        })
+    }
+}
+
+/* Function that reads an automaton written as text, one statement per line
+ * or separated by semicolons, e.g.
+ *     initial q0; final q2
+ *     q0 -a-> q1; q1 -b,c-> q2   # comments start with #
+ * The statements are:
+ *  - p -a,b-> q: transitions from p to q with the labels a and b, an empty
+ *    label or λ is ε.
+ *  - initial p, final p, q and state p, q: the initial state, the final
+ *    states and states that don't appear anywhere else.
+ *  - type finite, pushdown, turing, mealy or moore: the kind of machine,
+ *    finite by default.
+ *  - stack Z, accept empty_stack, blank _ and output p x: the initial stack
+ *    symbol and the acceptance of a pushdown automaton, the blank symbol of a
+ *    Turing machine and the output of a state of a Moore machine.
+ * The states are named after how they are written, and their ids are given
+ * in the order they first appear. Names and labels with other characters,
+ * e.g. the labels a;Z/AZ of pushdown automata or a/X,R of Turing machines,
+ * are written between double quotes. */
+pub fn parse_automaton(text: &str) -> Result<MachineFile, AutomatonTextError> {
+    let mut scanner = Scanner { chars: text.chars().collect(), index: 0, line: 1, column: 1 };
+    let mut automaton = TextAutomaton {
+        kind: None,
+        initial_stack_symbol: None,
+        empty_stack_acceptance: false,
+        blank_symbol: None,
+        initial: None,
+        states: Vec::new(),
+        ids: HashMap::new(),
+        transitions: Vec::new(),
+    };
+    loop {
+        scanner.skip_blanks();
+        match scanner.peek() {
+            None => break,
+            Some(';') | Some('\n') => {
+                scanner.bump();
+                continue;
+            }
+            _ => (),
+        }
+        automaton.statement(&mut scanner)?;
+        scanner.skip_blanks();
+        match scanner.peek() {
+            Some(';') | Some('\n') | None => (),
+            Some(c) => return scanner.error(format!("expected ; or a new line, found {}", c)),
+        }
+    }
+    automaton.into_machine_file()
+}
+
+/* Function that writes a machine file as text, the inverse of
+ * parse_automaton. The settings come first, then the initial and final
+ * states, the states without transitions, the outputs that are not empty
+ * and the transitions grouped by their states, one statement per line.
+ * The positions of the states are not written. If two states have the same
+ * name, apostrophes are added to the name of the second one. */
+pub fn format_automaton(file: &MachineFile) -> String {
+    let mut names: HashMap<StateID, String> = HashMap::new();
+    for state in file.states.iter() {
+        let mut name = state.name.clone();
+        while names.values().any(|used| *used == name) {
+            name.push('\'');
+        }
+        names.insert(state.id, name);
+    }
+    let name = |id: &StateID| quote_name(&names[id]);
+    let mut lines = Vec::new();
+    match file.kind {
+        MachineFileKind::Finite => (),
+        MachineFileKind::Pushdown => lines.push("type pushdown;".to_string()),
+        MachineFileKind::Turing => lines.push("type turing;".to_string()),
+        MachineFileKind::Mealy => lines.push("type mealy;".to_string()),
+        MachineFileKind::Moore => lines.push("type moore;".to_string()),
+    }
+    if let Some(symbol) = &file.initial_stack_symbol {
+        lines.push(format!("stack {};", quote_name(symbol)));
+    }
+    if file.empty_stack_acceptance {
+        lines.push("accept empty_stack;".to_string());
+    }
+    if let Some(symbol) = &file.blank_symbol {
+        lines.push(format!("blank {};", quote_name(symbol)));
+    }
+    if let Some(state) = file.states.iter().find(|state| state.initial) {
+        lines.push(format!("initial {};", name(&state.id)));
+    }
+    let final_states: Vec<String> = file.states.iter().filter(|state| state.is_final).map(|state| name(&state.id)).collect();
+    if !final_states.is_empty() {
+        lines.push(format!("final {};", final_states.join(", ")));
+    }
+    let isolated_states: Vec<String> = file.states.iter()
+        .filter(|state| !state.initial && !state.is_final)
+        .filter(|state| !file.transitions.iter().any(|t| t.from == state.id || t.to == state.id))
+        .map(|state| name(&state.id))
+        .collect();
+    if !isolated_states.is_empty() {
+        lines.push(format!("state {};", isolated_states.join(", ")));
+    }
+    for state in file.states.iter() {
+        if let Some(output) = state.output.as_ref().filter(|output| !output.is_empty()) {
+            lines.push(format!("output {} {};", name(&state.id), quote_name(output)));
+        }
+    }
+    // The labels of the transitions between the same states are written together.
+    let mut groups: Vec<((StateID, StateID), Vec<&str>)> = Vec::new();
+    for transition in file.transitions.iter() {
+        let key = (transition.from, transition.to);
+        match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+            Some((_, labels)) => labels.push(&transition.label),
+            None => groups.push((key, vec![&transition.label])),
+        }
+    }
+    for ((from, to), labels) in groups {
+        let labels: Vec<String> = labels.iter().map(|label| quote_label(label)).collect();
+        lines.push(format!("{} -{}-> {};", name(&from), labels.join(","), name(&to)));
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+// Writes a name between quotes if it can't be read as a name.
+fn quote_name(name: &str) -> String {
+    if !name.is_empty() && name.chars().all(is_name_char) && !KEYWORDS.contains(&name) {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+// Writes a label between quotes if it can't be read as a label.
+fn quote_label(label: &str) -> String {
+    let special = label.contains([',', ';', '\n', '"', '#']) || label.contains("->");
+    if special || label.trim() != label || label.is_empty() {
+        quote(label)
+    } else {
+        label.to_string()
+    }
+}
+
+fn quote(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,349 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io::BufRead;
+use crate::file_format::FileFormat;
+use crate::finite_automata::FiniteAutomata;
+use crate::machine_file::{FileMachine, MachineFile, MachineFileKind};
+use crate::pushdown_automata;
+use crate::regular_expression::RegularExpression;
+use crate::state_machine::StateMachine;
+use crate::test_suite::TestSuite;
+use crate::tikz;
+use crate::turing_machine;
+
+/* Text shown by the help command. */
+const USAGE: &str = "Usage: moca-data <command> [arguments] [-o <output>]
+
+Commands:
+  check <machine> [word...]       Runs the machine on the words, or on the lines of the
+                                  standard input if no word is given.
+  determinize <machine>           Converts a finite automaton to a deterministic one.
+  minimize <machine>              Minimizes a finite automaton or a Mealy machine.
+  equiv <machine> <machine>       Checks if two finite automata accept the same language.
+  to-regex <machine>              Writes a regular expression of a finite automaton.
+  from-regex <expression>         Builds a finite automaton from a regular expression.
+  export --tikz|--dot <machine>   Writes the machine as TikZ code or as a DOT graph.
+  info <machine>                  Describes the machine.
+  test <suite> <machine>...       Runs a test suite on every machine and reports the
+                                  cases where it didn't give the expected answer.
+  help                            Shows this text.
+
+The machines are read and written in the format given by the extension of the
+path: .jff for JFLAP, .dot or .gv for Graphviz, .txt for the text format and
+JSON machine files otherwise. The machines built by a command are written to
+the -o path, or to the standard output in the text format.
+
+A test suite has a case per line, accept or reject followed by the word, e.g.
+accept abb, and ε or nothing for the empty word. The lines that start with #
+are comments.
+
+The exit status is 0 on success, 1 if check rejects some word, equiv finds the
+automata different or test finds a mismatch, and 2 on errors.
+";
+
+/* Error of a command, with a message that can be shown to the user. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError {
+    pub message: String,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/* The result of a command: the text written to the standard output and if
+ * the answer was positive, i.e. every word was accepted by check, the
+ * automata were equivalent for equiv and every case passed for test. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliOutput {
+    pub text: String,
+    pub success: bool,
+}
+
+impl CliOutput {
+    fn new(text: String) -> Self {
+        CliOutput { text, success: true }
+    }
+}
+
+// The arguments of a command split in positional arguments, the output path and the flags.
+#[derive(Debug, Default)]
+struct Arguments {
+    positional: Vec<String>,
+    output: Option<String>,
+    flags: BTreeSet<String>,
+}
+
+impl Arguments {
+    // Reads the arguments after the command, the ones after -- are always positional.
+    fn parse(args: &[String]) -> Result<Arguments, CliError> {
+        let mut arguments = Arguments::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--" => {
+                    arguments.positional.extend(iter.by_ref().cloned());
+                }
+                "-o" | "--output" => match iter.next() {
+                    Some(path) => arguments.output = Some(path.clone()),
+                    None => return Err(error(&format!("{} needs a path", arg))),
+                },
+                "--tikz" | "--dot" => {
+                    arguments.flags.insert(arg.clone());
+                }
+                _ if arg.starts_with('-') && arg.len() > 1 => return Err(error(&format!("unknown option {}", arg))),
+                _ => arguments.positional.push(arg.clone()),
+            }
+        }
+        Ok(arguments)
+    }
+
+    // Checks the number of positional arguments of a command.
+    fn expect_positional(&self, command: &str, min: usize, max: Option<usize>) -> Result<(), CliError> {
+        let count = self.positional.len();
+        if count < min || max.is_some_and(|max| count > max) {
+            return Err(error(&format!("wrong number of arguments for {}, see moca-data help", command)));
+        }
+        Ok(())
+    }
+}
+
+/* Function that runs the command of the arguments, without the name of the
+ * program, e.g. ["check", "automaton.json", "ab"]. The words of check are
+ * read from input when none is given. */
+pub fn run(args: &[String], input: &mut dyn BufRead) -> Result<CliOutput, CliError> {
+    let command = match args.first() {
+        Some(command) => command.as_str(),
+        None => return Err(error(&format!("missing command\n\n{}", USAGE))),
+    };
+    let arguments = Arguments::parse(&args[1..])?;
+    match command {
+        "check" => {
+            arguments.expect_positional(command, 1, None)?;
+            let words = if arguments.positional.len() > 1 {
+                arguments.positional[1..].to_vec()
+            } else {
+                read_words(input)?
+            };
+            check(&read_machine(&arguments.positional[0])?, &words)
+        }
+        "determinize" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            let automaton = read_finite_automaton(&arguments.positional[0])?;
+            let determinized = determinize(&automaton)?;
+            write_machine(&FileMachine::Finite(determinized), &arguments.output)
+        }
+        "minimize" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            let minimized = match read_machine(&arguments.positional[0])?.to_machine() {
+                FileMachine::Finite(automaton) => FileMachine::Finite(determinize(&automaton)?.minimize()),
+                FileMachine::Mealy(machine) => FileMachine::Mealy(machine.minimize()),
+                other => return Err(error(&format!("only finite automata and Mealy machines can be minimized, not {} machines", kind_name(other.kind())))),
+            };
+            write_machine(&minimized, &arguments.output)
+        }
+        "equiv" => {
+            arguments.expect_positional(command, 2, Some(2))?;
+            let first = read_finite_automaton(&arguments.positional[0])?;
+            let second = read_finite_automaton(&arguments.positional[1])?;
+            match first.distinguishing_word(&second) {
+                None => Ok(CliOutput::new("equivalent\n".to_string())),
+                Some(word) => {
+                    let (accepting, rejecting) = if FileMachine::Finite(first).accepts(&word) {
+                        (&arguments.positional[0], &arguments.positional[1])
+                    } else {
+                        (&arguments.positional[1], &arguments.positional[0])
+                    };
+                    Ok(CliOutput {
+                        text: format!("not equivalent: {} is accepted by {} and not by {}\n", display_word(&word), accepting, rejecting),
+                        success: false,
+                    })
+                }
+            }
+        }
+        "to-regex" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            let automaton = read_finite_automaton(&arguments.positional[0])?;
+            Ok(CliOutput::new(format!("{}\n", RegularExpression::from_finite_automata(&automaton))))
+        }
+        "from-regex" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            let expression = RegularExpression::parse(&arguments.positional[0])
+                .map_err(|parse_error| error(&format!("invalid regular expression, {}", parse_error)))?;
+            write_machine(&FileMachine::Finite(expression.to_finite_automata()), &arguments.output)
+        }
+        "export" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            let file = read_machine(&arguments.positional[0])?;
+            let text = match (arguments.flags.contains("--tikz"), arguments.flags.contains("--dot")) {
+                (true, false) => tikz::to_tikz(&file),
+                (false, true) => FileFormat::Dot.write(&file).map_err(|write_error| error(&write_error.message))?,
+                _ => return Err(error("export needs either --tikz or --dot")),
+            };
+            write_text(text, &arguments.output)
+        }
+        "info" => {
+            arguments.expect_positional(command, 1, Some(1))?;
+            Ok(CliOutput::new(info(&read_machine(&arguments.positional[0])?)))
+        }
+        "test" => {
+            arguments.expect_positional(command, 2, None)?;
+            let suite_path = &arguments.positional[0];
+            let text = std::fs::read_to_string(suite_path)
+                .map_err(|io_error| error(&format!("cannot read {}: {}", suite_path, io_error)))?;
+            let suite = TestSuite::parse(&text).map_err(|parse_error| error(&format!("{}: {}", suite_path, parse_error)))?;
+            let mut output = CliOutput::new(String::new());
+            for path in arguments.positional[1..].iter() {
+                let report = suite.run(&read_machine(path)?.to_machine());
+                output.success = output.success && report.all_passed();
+                output.text.push_str(&format!("{}: {} of {} cases passed\n", path, report.passed_count(), report.results.len()));
+                for result in report.mismatches() {
+                    output.text.push_str(&format!("  {}\n", result));
+                }
+            }
+            Ok(output)
+        }
+        "help" | "--help" | "-h" => Ok(CliOutput::new(USAGE.to_string())),
+        _ => Err(error(&format!("unknown command {}, see moca-data help", command))),
+    }
+}
+
+fn error(message: &str) -> CliError {
+    CliError { message: message.to_string() }
+}
+
+// Reads a machine in the format given by the extension of the path.
+fn read_machine(path: &str) -> Result<MachineFile, CliError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|io_error| error(&format!("cannot read {}: {}", path, io_error)))?;
+    FileFormat::of_path(path).read(&text)
+        .map_err(|file_error| error(&format!("{}: {}", path, file_error)))
+}
+
+fn read_finite_automaton(path: &str) -> Result<FiniteAutomata, CliError> {
+    match read_machine(path)?.to_machine() {
+        FileMachine::Finite(automaton) => Ok(automaton),
+        other => Err(error(&format!("{} is a {} machine, not a finite automaton", path, kind_name(other.kind())))),
+    }
+}
+
+// Writes a machine to the output path in its format, or in the text format if there is none.
+fn write_machine(machine: &FileMachine, output: &Option<String>) -> Result<CliOutput, CliError> {
+    let file = MachineFile::new(machine, &HashMap::new());
+    let format = match output {
+        Some(path) => FileFormat::of_path(path),
+        None => FileFormat::Text,
+    };
+    let text = format.write(&file).map_err(|write_error| error(&write_error.message))?;
+    write_text(text, output)
+}
+
+// Writes the text to the output path, or returns it to be written to the standard output.
+fn write_text(text: String, output: &Option<String>) -> Result<CliOutput, CliError> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, text).map_err(|io_error| error(&format!("cannot write {}: {}", path, io_error)))?;
+            Ok(CliOutput::new(String::new()))
+        }
+        None => Ok(CliOutput::new(text)),
+    }
+}
+
+// Reads the words of check from the input, one per line, an empty line is the empty word.
+fn read_words(input: &mut dyn BufRead) -> Result<Vec<String>, CliError> {
+    input.lines()
+        .map(|line| line.map(|line| line.trim_end_matches('\r').to_string()))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|io_error| error(&format!("cannot read the words: {}", io_error)))
+}
+
+// The automaton itself if it is deterministic, otherwise the result of the subset construction.
+fn determinize(automaton: &FiniteAutomata) -> Result<FiniteAutomata, CliError> {
+    if automaton.get_initial_state_id().is_none() {
+        return Err(error("the automaton has no initial state"));
+    }
+    if automaton.is_deterministic() {
+        Ok(automaton.clone())
+    } else {
+        Ok(automaton.to_dfa())
+    }
+}
+
+/* Function that runs the machine on every word and writes a line for each
+ * one, whether it was accepted or, for Mealy and Moore machines, the output.
+ * The answer is negative if some word is rejected or has no output. */
+fn check(file: &MachineFile, words: &[String]) -> Result<CliOutput, CliError> {
+    let machine = file.to_machine();
+    if machine.as_state_machine().get_initial_state_id().is_none() {
+        return Err(error("the machine has no initial state"));
+    }
+    let mut text = String::new();
+    let mut success = true;
+    for word in words {
+        let result = match &machine {
+            FileMachine::Mealy(mealy_machine) => mealy_machine.run(word).map(|output| format!("output {}", display_word(&output))),
+            FileMachine::Moore(moore_machine) => moore_machine.run(word).map(|output| format!("output {}", display_word(&output))),
+            _ => machine.accepts(word).then(|| "accepted".to_string()),
+        };
+        match result {
+            Some(result) => text.push_str(&format!("{}: {}\n", display_word(word), result)),
+            None => {
+                success = false;
+                text.push_str(&format!("{}: rejected\n", display_word(word)));
+            }
+        }
+    }
+    Ok(CliOutput { text, success })
+}
+
+/* Function that describes a machine: its kind, the number of states and
+ * transitions, the initial and final states, whether it is deterministic
+ * and the symbols it reads. */
+fn info(file: &MachineFile) -> String {
+    let machine = file.to_machine();
+    let names = |filter: &dyn Fn(&crate::machine_file::StateEntry) -> bool| {
+        file.states.iter().filter(|state| filter(state)).map(|state| state.name.clone()).collect::<Vec<String>>().join(", ")
+    };
+    let alphabet: BTreeSet<String> = file.transitions.iter()
+        .filter_map(|transition| match file.kind {
+            MachineFileKind::Finite | MachineFileKind::Moore => Some(transition.label.clone()),
+            MachineFileKind::Pushdown => pushdown_automata::parse_transition_label(&transition.label).map(|(symbol, _, _)| symbol),
+            MachineFileKind::Turing => turing_machine::parse_transition_label(&transition.label).map(|(symbol, _, _)| symbol),
+            MachineFileKind::Mealy => crate::mealy_machine::parse_transition_label(&transition.label).map(|(symbol, _)| symbol),
+        })
+        .filter(|symbol| symbol != "ε" && !symbol.is_empty())
+        .collect();
+    let mut text = String::new();
+    text.push_str(&format!("kind: {}\n", kind_name(file.kind)));
+    text.push_str(&format!("states: {}\n", file.states.len()));
+    text.push_str(&format!("transitions: {}\n", file.transitions.len()));
+    text.push_str(&format!("initial state: {}\n", names(&|state| state.initial)));
+    text.push_str(&format!("final states: {}\n", names(&|state| state.is_final)));
+    text.push_str(&format!("deterministic: {}\n", if machine.as_state_machine().is_deterministic() { "yes" } else { "no" }));
+    text.push_str(&format!("alphabet: {}\n", alphabet.into_iter().collect::<Vec<String>>().join(", ")));
+    if let Some(symbol) = &file.initial_stack_symbol {
+        text.push_str(&format!("initial stack symbol: {}\n", symbol));
+        text.push_str(&format!("acceptance: {}\n", if file.empty_stack_acceptance { "empty stack" } else { "final states" }));
+    }
+    if let Some(symbol) = &file.blank_symbol {
+        text.push_str(&format!("blank symbol: {}\n", symbol));
+    }
+    text
+}
+
+fn kind_name(kind: MachineFileKind) -> &'static str {
+    match kind {
+        MachineFileKind::Finite => "finite",
+        MachineFileKind::Pushdown => "pushdown",
+        MachineFileKind::Turing => "turing",
+        MachineFileKind::Mealy => "mealy",
+        MachineFileKind::Moore => "moore",
+    }
+}
+
+// Writes the empty word as ε.
+fn display_word(word: &str) -> &str {
+    if word.is_empty() { "ε" } else { word }
+}
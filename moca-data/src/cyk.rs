@@ -0,0 +1,110 @@
+use std::collections::BTreeSet;
+use crate::grammar::{Grammar, ParseTree, Symbol};
+
+/* Structure that represents the table of the CYK algorithm for a word.
+ * The cell (start, length) has the nonterminals that derive the subword
+ * of the given length that begins in the position start, so the table is
+ * triangular: the row of length 1 has one cell per symbol of the word, and
+ * the row of length n only has the cell of the whole word. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CykTable {
+    word: Vec<Symbol>,
+    cells: Vec<Vec<BTreeSet<Symbol>>>,
+    start_symbol: Symbol,
+    parse_tree: Option<ParseTree>,
+}
+
+impl CykTable {
+    /* Function that fills the table of the CYK algorithm for a grammar in
+     * Chomsky normal form, returning None if the grammar is not in that
+     * form. If the word is generated, a parse tree is rebuilt from the
+     * table, choosing the first split and production that work. */
+    pub fn new(grammar: &Grammar, word: &[Symbol]) -> Option<CykTable> {
+        if !grammar.is_chomsky_normal_form() {
+            return None;
+        }
+        let length = word.len();
+        let mut cells: Vec<Vec<BTreeSet<Symbol>>> = Vec::new();
+        cells.push(word.iter()
+            .map(|symbol| grammar.get_productions().iter()
+                .filter(|production| production.body.len() == 1 && production.body[0] == *symbol)
+                .map(|production| production.head.clone())
+                .collect())
+            .collect());
+        for subword_length in 2..=length {
+            let mut row = Vec::new();
+            for start in 0..=length - subword_length {
+                let mut cell = BTreeSet::new();
+                for split in 1..subword_length {
+                    let left = &cells[split - 1][start];
+                    let right = &cells[subword_length - split - 1][start + split];
+                    for production in grammar.get_productions().iter().filter(|p| p.body.len() == 2) {
+                        if left.contains(&production.body[0]) && right.contains(&production.body[1]) {
+                            cell.insert(production.head.clone());
+                        }
+                    }
+                }
+                row.push(cell);
+            }
+            cells.push(row);
+        }
+        let mut table = CykTable {
+            word: word.to_vec(),
+            cells,
+            start_symbol: grammar.get_start_symbol().clone(),
+            parse_tree: None,
+        };
+        table.parse_tree = if length == 0 {
+            grammar.productions_of(grammar.get_start_symbol())
+                .find(|production| production.is_epsilon())
+                .map(|_| ParseTree::new(grammar.get_start_symbol(), vec![ParseTree::leaf("ε")]))
+        } else if table.is_accepted() {
+            table.build_tree(grammar, grammar.get_start_symbol(), 0, length)
+        } else {
+            None
+        };
+        Some(table)
+    }
+
+    // Getter for the word of the table.
+    pub fn get_word(&self) -> &Vec<Symbol> {
+        &self.word
+    }
+
+    /* Returns the nonterminals of the cell of the subword of the given
+     * length that begins in the position start, both starting at 0 and 1. */
+    pub fn get_cell(&self, start: usize, length: usize) -> &BTreeSet<Symbol> {
+        &self.cells[length - 1][start]
+    }
+
+    // Returns true if the start symbol derives the word.
+    pub fn is_accepted(&self) -> bool {
+        match self.word.len() {
+            0 => self.parse_tree.is_some(),
+            length => self.get_cell(0, length).contains(&self.start_symbol),
+        }
+    }
+
+    // Getter for the parse tree of the word, it is None if the word is rejected.
+    pub fn get_parse_tree(&self) -> Option<&ParseTree> {
+        self.parse_tree.as_ref()
+    }
+
+    // Auxiliar function that builds the tree of a nonterminal in a cell of the table.
+    fn build_tree(&self, grammar: &Grammar, symbol: &str, start: usize, length: usize) -> Option<ParseTree> {
+        if length == 1 {
+            return Some(ParseTree::new(symbol, vec![ParseTree::leaf(&self.word[start])]));
+        }
+        for split in 1..length {
+            for production in grammar.productions_of(symbol).filter(|p| p.body.len() == 2) {
+                if self.get_cell(start, split).contains(&production.body[0])
+                    && self.get_cell(start + split, length - split).contains(&production.body[1]) {
+                    let left = self.build_tree(grammar, &production.body[0], start, split)?;
+                    let right = self.build_tree(grammar, &production.body[1], start + split, length - split)?;
+                    return Some(ParseTree::new(symbol, vec![left, right]));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,502 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use crate::state::StateID;
+use crate::state_machine::StateMachine;
+
+/* Name of the invisible node the start arrow comes from. */
+const START_NODE: &str = "__start";
+
+/* Error returned when a DOT graph can't be read. The line starts at 1. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/* A node of a DOT graph with its attributes, the defaults of the node
+ * statements before it included. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotNode {
+    pub id: String,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/* An edge of a DOT graph, with the line where it was written. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotEdge {
+    pub from: String,
+    pub to: String,
+    pub attributes: BTreeMap<String, String>,
+    pub line: usize,
+}
+
+/* Structure that represents a directed graph read from the DOT language.
+ * The nodes are in the order they first appear, in a node statement or in
+ * an edge, and the subgraphs are flattened into the graph. */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DotGraph {
+    pub nodes: Vec<DotNode>,
+    pub edges: Vec<DotEdge>,
+}
+
+impl DotGraph {
+    pub fn node(&self, id: &str) -> Option<&DotNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /* Returns true if the node is the source of a start arrow instead of a
+     * state, i.e. it is invisible, a point or a node without a name. */
+    pub fn is_start_node(&self, id: &str) -> bool {
+        let node = match self.node(id) {
+            Some(node) => node,
+            None => return false,
+        };
+        let attribute = |name: &str| node.attributes.get(name).map(|value| value.as_str());
+        node.id.is_empty()
+            || matches!(attribute("shape"), Some("point") | Some("none") | Some("plaintext"))
+            || attribute("style") == Some("invis")
+            || attribute("label") == Some("")
+    }
+
+    /* Function that builds a state machine from the graph. Every node that
+     * is not a start node is a state with the id given by its order, named
+     * after its label or its id, and it is final if it is a doublecircle. The
+     * target of the edge from a start node is the initial state. The labels of
+     * an edge are its lines, and also its comma separated parts if
+     * split_commas is set, an edge without label or with λ is an ε-transition. */
+    pub fn build_machine(&self, machine: &mut dyn StateMachine, split_commas: bool) -> Result<(), DotParseError> {
+        let mut ids: HashMap<&str, StateID> = HashMap::new();
+        for node in self.nodes.iter().filter(|node| !self.is_start_node(&node.id)) {
+            let id = ids.len() as StateID;
+            let name = match node.attributes.get("label") {
+                Some(label) if !label.trim().is_empty() => label.trim(),
+                _ => node.id.as_str(),
+            };
+            machine.add_state_with_id_label(id, name);
+            let peripheries = node.attributes.get("peripheries").and_then(|value| value.parse::<usize>().ok());
+            if node.attributes.get("shape").map(|shape| shape.as_str()) == Some("doublecircle") || peripheries.is_some_and(|value| value > 1) {
+                machine.make_final(id);
+            }
+            ids.insert(&node.id, id);
+        }
+        let mut initial_line: Option<usize> = None;
+        for edge in self.edges.iter() {
+            if self.is_start_node(&edge.from) {
+                if let Some(line) = initial_line {
+                    return Err(DotParseError {
+                        line: edge.line,
+                        message: format!("there is already a start arrow in line {}", line),
+                    });
+                }
+                match ids.get(edge.to.as_str()) {
+                    Some(id) => machine.make_initial(*id),
+                    None => return Err(DotParseError {
+                        line: edge.line,
+                        message: format!("the start arrow goes to {}, which is not a state", edge.to),
+                    }),
+                }
+                initial_line = Some(edge.line);
+                continue;
+            }
+            let (from_id, to_id) = match (ids.get(edge.from.as_str()), ids.get(edge.to.as_str())) {
+                (Some(from_id), Some(to_id)) => (*from_id, *to_id),
+                _ => return Err(DotParseError {
+                    line: edge.line,
+                    message: format!("the edge {} -> {} goes to a start node", edge.from, edge.to),
+                }),
+            };
+            for label in edge_labels(edge.attributes.get("label").map_or("", |label| label.as_str()), split_commas) {
+                machine.add_transition(from_id, to_id, label);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Auxiliar function that splits the label of an edge in the labels of its transitions.
+fn edge_labels(label: &str, split_commas: bool) -> Vec<String> {
+    let label = label.replace("\\l", "\\n").replace("\\r", "\\n");
+    let lines = label.split("\\n").flat_map(|line| line.lines());
+    let parts: Vec<&str> = if split_commas {
+        lines.flat_map(|line| line.split(',')).collect()
+    } else {
+        lines.collect()
+    };
+    let mut labels: Vec<String> = parts.into_iter()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.replace('λ', "ε"))
+        .collect();
+    if labels.is_empty() {
+        labels.push("ε".to_string());
+    }
+    labels
+}
+
+/* Function that writes a state machine in the DOT language of Graphviz.
+ * The states are circles named after their ids and labeled with their
+ * names, the final ones are doublecircles, and an invisible node draws the
+ * arrow to the initial state. The labels of the transitions between two
+ * states are grouped in one edge, one label per line. The states and the
+ * labels are sorted, so the same machine always gives the same graph. */
+pub fn to_dot(machine: &dyn StateMachine) -> String {
+    let mut dot = String::from("digraph {\n    rankdir = LR;\n    node [shape = circle];\n");
+    let states = machine.get_states_by_id_ref();
+    let mut ids: Vec<&StateID> = states.keys().collect();
+    ids.sort();
+    if machine.get_initial_state_id().is_some() {
+        dot.push_str(&format!("    {} [shape = point, style = invis];\n", START_NODE));
+    }
+    for id in ids.iter() {
+        let shape = if machine.get_final_states().contains(id) { ", shape = doublecircle" } else { "" };
+        dot.push_str(&format!("    {} [label = {}{}];\n", id, quote(&states[id].name), shape));
+    }
+    if let Some(initial_id) = machine.get_initial_state_id() {
+        dot.push_str(&format!("    {} -> {};\n", START_NODE, initial_id));
+    }
+    for id in ids.iter() {
+        let mut transition_groups: Vec<(&StateID, Vec<&String>)> = states[id].iter_by_transition()
+            .map(|(to_id, labels)| {
+                let mut labels: Vec<&String> = labels.iter().collect();
+                labels.sort();
+                (to_id, labels)
+            })
+            .collect();
+        transition_groups.sort();
+        for (to_id, labels) in transition_groups {
+            let label = labels.iter().map(|label| label.as_str()).collect::<Vec<&str>>().join("\\n");
+            dot.push_str(&format!("    {} -> {} [label = {}];\n", id, to_id, quote(&label)));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Writes a string as a quoted DOT identifier, the line breaks written as \n are kept.
+fn quote(string: &str) -> String {
+    format!("\"{}\"", string.replace('"', "\\\""))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Id(String),
+    Punctuation(&'static str),
+}
+
+// Auxiliar function that splits a DOT text in tokens, with the line of each one.
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, DotParseError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        if c == '\n' {
+            line += 1;
+            index += 1;
+        } else if c.is_whitespace() {
+            index += 1;
+        } else if c == '/' && next == Some('/') || c == '#' {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            index += 2;
+            while index < chars.len() && !(chars[index] == '*' && chars.get(index + 1) == Some(&'/')) {
+                if chars[index] == '\n' {
+                    line += 1;
+                }
+                index += 1;
+            }
+            index += 2;
+        } else if c == '-' && next == Some('>') {
+            tokens.push((Token::Punctuation("->"), line));
+            index += 2;
+        } else if c == '-' && next == Some('-') {
+            return Err(DotParseError { line, message: "undirected edges are not supported, use ->".to_string() });
+        } else if let Some(punctuation) = ["{", "}", "[", "]", "=", ";", ",", ":"].iter().find(|p| p.starts_with(c)) {
+            tokens.push((Token::Punctuation(punctuation), line));
+            index += 1;
+        } else if c == '"' {
+            let start_line = line;
+            let mut string = String::new();
+            index += 1;
+            loop {
+                match chars.get(index) {
+                    None => return Err(DotParseError { line: start_line, message: "unterminated string".to_string() }),
+                    Some('"') => break,
+                    Some('\\') if chars.get(index + 1) == Some(&'"') => {
+                        string.push('"');
+                        index += 1;
+                    }
+                    Some('\\') if chars.get(index + 1) == Some(&'\n') => {
+                        line += 1;
+                        index += 1;
+                    }
+                    Some(c) => {
+                        if *c == '\n' {
+                            line += 1;
+                        }
+                        string.push(*c);
+                    }
+                }
+                index += 1;
+            }
+            index += 1;
+            tokens.push((Token::Id(string), start_line));
+        } else if c == '<' {
+            // HTML strings are kept as they are, without the outer brackets.
+            let start_line = line;
+            let mut depth = 0;
+            let mut string = String::new();
+            loop {
+                match chars.get(index) {
+                    None => return Err(DotParseError { line: start_line, message: "unterminated HTML string".to_string() }),
+                    Some('<') => depth += 1,
+                    Some('>') => depth -= 1,
+                    Some('\n') => line += 1,
+                    _ => (),
+                }
+                if depth == 0 {
+                    break;
+                }
+                if depth > 1 || chars[index] != '<' {
+                    string.push(chars[index]);
+                }
+                index += 1;
+            }
+            index += 1;
+            tokens.push((Token::Id(string), start_line));
+        } else if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' {
+            // A minus sign can only start a numeral, e.g. -1.5.
+            let start = index;
+            index += 1;
+            while index < chars.len() && (chars[index].is_alphanumeric() || chars[index] == '_' || chars[index] == '.') {
+                index += 1;
+            }
+            tokens.push((Token::Id(chars[start..index].iter().collect()), line));
+        } else {
+            return Err(DotParseError { line, message: format!("unexpected character {}", c) });
+        }
+    }
+    Ok(tokens)
+}
+
+// Reads the tokens of a DOT graph, keeping the defaults of the node statements.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    node_defaults: BTreeMap<String, String>,
+    edge_defaults: BTreeMap<String, String>,
+    graph: DotGraph,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens.get(self.position)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, DotParseError> {
+        Err(DotParseError { line: self.line(), message })
+    }
+
+    fn next_is(&self, punctuation: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punctuation(next)) if *next == punctuation)
+    }
+
+    fn expect(&mut self, punctuation: &str) -> Result<(), DotParseError> {
+        if self.next_is(punctuation) {
+            self.position += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected {}", punctuation))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, DotParseError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Ok(id)
+            }
+            Some(Token::Punctuation(punctuation)) => self.error(format!("expected an identifier, found {}", punctuation)),
+            None => self.error("expected an identifier, found the end of the text".to_string()),
+        }
+    }
+
+    fn keyword_is(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    // Reads the attribute lists [a = b, c = d][e = f] of a statement.
+    fn attributes(&mut self) -> Result<BTreeMap<String, String>, DotParseError> {
+        let mut attributes = BTreeMap::new();
+        while self.next_is("[") {
+            self.position += 1;
+            while !self.next_is("]") {
+                let name = self.id()?;
+                let value = if self.next_is("=") {
+                    self.position += 1;
+                    self.id()?
+                } else {
+                    "true".to_string()
+                };
+                attributes.insert(name, value);
+                if self.next_is(",") || self.next_is(";") {
+                    self.position += 1;
+                }
+            }
+            self.position += 1;
+        }
+        Ok(attributes)
+    }
+
+    // Reads a node id with its optional port, and adds the node to the graph if it is new.
+    fn node_id(&mut self) -> Result<String, DotParseError> {
+        let id = self.id()?;
+        self.add_node(id)
+    }
+
+    // Skips the port of a node id that was already read, and adds the node to the graph if it is new.
+    fn add_node(&mut self, id: String) -> Result<String, DotParseError> {
+        while self.next_is(":") {
+            self.position += 1;
+            self.id()?;
+        }
+        if self.graph.node(&id).is_none() {
+            self.graph.nodes.push(DotNode { id: id.clone(), attributes: self.node_defaults.clone() });
+        }
+        Ok(id)
+    }
+
+    // Reads the statements until the closing brace of the current graph or subgraph.
+    fn statements(&mut self) -> Result<(), DotParseError> {
+        loop {
+            if self.next_is("}") {
+                self.position += 1;
+                return Ok(());
+            }
+            if self.peek().is_none() {
+                return self.error("expected } at the end of the graph".to_string());
+            }
+            if self.next_is(";") {
+                self.position += 1;
+            } else if self.next_is("{") || self.keyword_is("subgraph") {
+                if self.keyword_is("subgraph") {
+                    self.position += 1;
+                    if !self.next_is("{") {
+                        self.id()?;
+                    }
+                }
+                self.expect("{")?;
+                // The defaults set inside a subgraph are only used inside it.
+                let node_defaults = self.node_defaults.clone();
+                let edge_defaults = self.edge_defaults.clone();
+                self.statements()?;
+                self.node_defaults = node_defaults;
+                self.edge_defaults = edge_defaults;
+                if self.next_is("->") {
+                    return self.error("edges between subgraphs are not supported".to_string());
+                }
+            } else if self.keyword_is("graph") || self.keyword_is("node") || self.keyword_is("edge") {
+                let keyword = self.id()?.to_lowercase();
+                let attributes = self.attributes()?;
+                match keyword.as_str() {
+                    "node" => self.node_defaults.extend(attributes),
+                    "edge" => self.edge_defaults.extend(attributes),
+                    _ => (),
+                }
+            } else {
+                let line = self.line();
+                let id = self.id()?;
+                if self.next_is("=") {
+                    // An attribute of the graph, e.g. rankdir = LR.
+                    self.position += 1;
+                    self.id()?;
+                    continue;
+                }
+                let mut chain = vec![self.add_node(id)?];
+                while self.next_is("->") {
+                    self.position += 1;
+                    if self.next_is("{") || self.keyword_is("subgraph") {
+                        return self.error("edges between subgraphs are not supported".to_string());
+                    }
+                    chain.push(self.node_id()?);
+                }
+                let attributes = self.attributes()?;
+                if chain.len() == 1 {
+                    if let Some(node) = self.graph.nodes.iter_mut().find(|node| node.id == chain[0]) {
+                        node.attributes.extend(attributes);
+                    }
+                } else {
+                    let mut edge_attributes = self.edge_defaults.clone();
+                    edge_attributes.extend(attributes);
+                    for pair in chain.windows(2) {
+                        self.graph.edges.push(DotEdge {
+                            from: pair[0].clone(),
+                            to: pair[1].clone(),
+                            attributes: edge_attributes.clone(),
+                            line,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/* Function that reads a directed graph written in the DOT language, e.g.
+ *     digraph {
+ *         node [shape = circle];
+ *         start [shape = point];
+ *         q2 [shape = doublecircle];
+ *         start -> q0;
+ *         q0 -> q1 [label = "a"];
+ *         q1 -> q2 [label = "b\nc"];
+ *     }
+ * The attributes of the graph, the ports and the subgraphs are read but
+ * ignored, the statements of a subgraph are part of the graph. Undirected
+ * graphs are rejected. */
+pub fn parse_dot(text: &str) -> Result<DotGraph, DotParseError> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        node_defaults: BTreeMap::new(),
+        edge_defaults: BTreeMap::new(),
+        graph: DotGraph::default(),
+    };
+    if parser.keyword_is("strict") {
+        parser.position += 1;
+    }
+    if parser.keyword_is("graph") {
+        return parser.error("undirected graphs are not supported, use digraph".to_string());
+    }
+    if !parser.keyword_is("digraph") {
+        return parser.error("expected digraph".to_string());
+    }
+    parser.position += 1;
+    if !parser.next_is("{") {
+        parser.id()?;
+    }
+    parser.expect("{")?;
+    parser.statements()?;
+    if parser.peek().is_some() {
+        return parser.error("unexpected text after the graph".to_string());
+    }
+    Ok(parser.graph)
+}
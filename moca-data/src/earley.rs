@@ -0,0 +1,229 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use crate::grammar::{format_symbols, Grammar, ParseTree, Production, Symbol};
+
+/* Maximum number of parse trees returned for an ambiguous word. */
+pub const MAX_PARSE_TREES: usize = 100;
+
+// One more tree than the ones returned is searched, to know if some were left out.
+const TREE_LIMIT: usize = MAX_PARSE_TREES + 1;
+
+/* The parse trees returned by EarleyChart::parse_trees. If truncated is true
+ * the word has more than MAX_PARSE_TREES trees and only the first ones are kept. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTreeList {
+    pub trees: Vec<ParseTree>,
+    pub truncated: bool,
+}
+
+/* Struct that represents an item of the Earley algorithm, a production with
+ * a dot that separates the part of the body that was already read, and the
+ * position of the word where the production started to be read. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EarleyItem {
+    pub production: Production,
+    pub dot: usize,
+    pub origin: usize,
+}
+
+impl EarleyItem {
+    fn new(production: &Production, dot: usize, origin: usize) -> Self {
+        EarleyItem {
+            production: production.clone(),
+            dot,
+            origin,
+        }
+    }
+
+    // Returns the symbol after the dot, or None if the item is completed.
+    pub fn next_symbol(&self) -> Option<&Symbol> {
+        self.production.body.get(self.dot)
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.dot == self.production.body.len()
+    }
+}
+
+/* The item is printed as A -> a·Sb, 0 where 0 is the origin. */
+impl fmt::Display for EarleyItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (read, unread) = self.production.body.split_at(self.dot);
+        let write = |symbols: &[Symbol]| if symbols.is_empty() { String::new() } else { format_symbols(symbols) };
+        write!(f, "{} -> {}·{}, {}", self.production.head, write(read), write(unread), self.origin)
+    }
+}
+
+/* Structure that represents the chart of the Earley algorithm for a word.
+ * The set i has the items that are valid after reading the first i
+ * symbols of the word, so there is one more set than symbols. The grammar
+ * doesn't need to be in any normal form, the ε-productions are handled
+ * advancing the dot over the nullable nonterminals when they are predicted,
+ * as proposed by Aycock and Horspool. */
+#[derive(Debug, Clone)]
+pub struct EarleyChart {
+    grammar: Grammar,
+    word: Vec<Symbol>,
+    sets: Vec<Vec<EarleyItem>>,
+}
+
+impl EarleyChart {
+    pub fn new(grammar: &Grammar, word: &[Symbol]) -> Self {
+        let nullable = grammar.nullable_nonterminals();
+        let mut sets: Vec<Vec<EarleyItem>> = vec![Vec::new(); word.len() + 1];
+        let mut seen: Vec<HashSet<EarleyItem>> = vec![HashSet::new(); word.len() + 1];
+        for production in grammar.productions_of(grammar.get_start_symbol()) {
+            let item = EarleyItem::new(production, 0, 0);
+            seen[0].insert(item.clone());
+            sets[0].push(item);
+        }
+        for position in 0..=word.len() {
+            let mut index = 0;
+            while index < sets[position].len() {
+                let item = sets[position][index].clone();
+                index += 1;
+                let mut new_items: Vec<(usize, EarleyItem)> = Vec::new();
+                match item.next_symbol() {
+                    // Prediction
+                    Some(symbol) if grammar.is_nonterminal(symbol) => {
+                        for production in grammar.productions_of(symbol) {
+                            new_items.push((position, EarleyItem::new(production, 0, position)));
+                        }
+                        if nullable.contains(symbol) {
+                            new_items.push((position, EarleyItem::new(&item.production, item.dot + 1, item.origin)));
+                        }
+                    }
+                    // Scan
+                    Some(symbol) => {
+                        if position < word.len() && word[position] == *symbol {
+                            new_items.push((position + 1, EarleyItem::new(&item.production, item.dot + 1, item.origin)));
+                        }
+                    }
+                    // Completion
+                    None => {
+                        for waiting in sets[item.origin].iter() {
+                            if waiting.next_symbol() == Some(&item.production.head) {
+                                new_items.push((position, EarleyItem::new(&waiting.production, waiting.dot + 1, waiting.origin)));
+                            }
+                        }
+                    }
+                }
+                for (set, new_item) in new_items {
+                    if seen[set].insert(new_item.clone()) {
+                        sets[set].push(new_item);
+                    }
+                }
+            }
+        }
+        EarleyChart {
+            grammar: grammar.clone(),
+            word: word.to_vec(),
+            sets,
+        }
+    }
+
+    // Getter for the word of the chart.
+    pub fn get_word(&self) -> &Vec<Symbol> {
+        &self.word
+    }
+
+    // Getter for the sets of items, the set i is the one after reading i symbols.
+    pub fn get_sets(&self) -> &Vec<Vec<EarleyItem>> {
+        &self.sets
+    }
+
+    /* Returns true if the word is generated by the grammar, i.e. the last
+     * set has a completed item of the start symbol that started at 0. */
+    pub fn is_accepted(&self) -> bool {
+        self.sets[self.word.len()].iter().any(|item| item.is_completed()
+            && item.origin == 0
+            && item.production.head == *self.grammar.get_start_symbol())
+    }
+
+    /* Function that returns the parse trees of the word, there is more
+     * than one if the grammar is ambiguous. The trees are built from the
+     * completed items of the chart. A grammar with cycles A =>+ A has
+     * infinitely many trees for some words, so a nonterminal is not expanded
+     * again over the same subword inside itself, and at most MAX_PARSE_TREES
+     * trees are returned, marking the list as truncated if there are more. */
+    pub fn parse_trees(&self) -> ParseTreeList {
+        // The productions that derive every subword, from the completed items.
+        let mut completed: HashMap<(&Symbol, usize, usize), BTreeSet<&Production>> = HashMap::new();
+        for (end, set) in self.sets.iter().enumerate() {
+            for item in set.iter().filter(|item| item.is_completed()) {
+                completed.entry((&item.production.head, item.origin, end))
+                    .or_default()
+                    .insert(&item.production);
+            }
+        }
+        let mut expanding = Vec::new();
+        let mut trees = self.trees_of(self.grammar.get_start_symbol(), 0, self.word.len(), &completed, &mut expanding);
+        let truncated = trees.len() > MAX_PARSE_TREES;
+        trees.truncate(MAX_PARSE_TREES);
+        ParseTreeList { trees, truncated }
+    }
+
+    // Auxiliar function that returns the trees of a nonterminal that derives the subword.
+    fn trees_of<'a>(&'a self, symbol: &'a Symbol, start: usize, end: usize,
+                    completed: &HashMap<(&'a Symbol, usize, usize), BTreeSet<&'a Production>>,
+                    expanding: &mut Vec<(&'a Symbol, usize, usize)>) -> Vec<ParseTree> {
+        let productions = match completed.get(&(symbol, start, end)) {
+            Some(productions) if !expanding.contains(&(symbol, start, end)) => productions,
+            _ => return Vec::new(),
+        };
+        expanding.push((symbol, start, end));
+        let mut trees = Vec::new();
+        for production in productions.iter() {
+            for children in self.children_of(&production.body, start, end, completed, expanding) {
+                let children = if children.is_empty() { vec![ParseTree::leaf("ε")] } else { children };
+                trees.push(ParseTree::new(symbol, children));
+                if trees.len() == TREE_LIMIT {
+                    expanding.pop();
+                    return trees;
+                }
+            }
+        }
+        expanding.pop();
+        trees
+    }
+
+    // Auxiliar function that returns the ways the symbols of a body derive the subword.
+    fn children_of<'a>(&'a self, body: &'a [Symbol], start: usize, end: usize,
+                       completed: &HashMap<(&'a Symbol, usize, usize), BTreeSet<&'a Production>>,
+                       expanding: &mut Vec<(&'a Symbol, usize, usize)>) -> Vec<Vec<ParseTree>> {
+        let (symbol, rest) = match body.split_first() {
+            Some(parts) => parts,
+            None => return if start == end { vec![Vec::new()] } else { Vec::new() },
+        };
+        let mut sequences = Vec::new();
+        if self.grammar.is_terminal(symbol) {
+            if start < end && self.word[start] == *symbol {
+                for mut children in self.children_of(rest, start + 1, end, completed, expanding) {
+                    children.insert(0, ParseTree::leaf(symbol));
+                    sequences.push(children);
+                }
+            }
+            return sequences;
+        }
+        for middle in start..=end {
+            if !completed.contains_key(&(symbol, start, middle)) {
+                continue;
+            }
+            let rest_sequences = self.children_of(rest, middle, end, completed, expanding);
+            if rest_sequences.is_empty() {
+                continue;
+            }
+            for tree in self.trees_of(symbol, start, middle, completed, expanding) {
+                for rest_children in rest_sequences.iter() {
+                    let mut children = vec![tree.clone()];
+                    children.extend(rest_children.iter().cloned());
+                    sequences.push(children);
+                    if sequences.len() == TREE_LIMIT {
+                        return sequences;
+                    }
+                }
+            }
+        }
+        sequences
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::automaton_text;
+use crate::dot::{self, DotParseError};
+use crate::finite_automata::FiniteAutomata;
+use crate::jflap;
+use crate::machine_file::{FileMachine, MachineFile, MachineFileError};
+use crate::pushdown_automata::PushdownAutomata;
+
+/* The formats a machine can be read from and written to, given by the
+ * extension of the path: .jff for JFLAP, .dot or .gv for Graphviz, .txt for
+ * the text format of automaton_text and JSON machine files otherwise. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Jflap,
+    Dot,
+    Text,
+}
+
+impl FileFormat {
+    pub fn of_path(path: &str) -> Self {
+        let extension = Path::new(path).extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("jff") => FileFormat::Jflap,
+            Some("dot") | Some("gv") => FileFormat::Dot,
+            Some("txt") => FileFormat::Text,
+            _ => FileFormat::Json,
+        }
+    }
+
+    /* Function that reads a machine written in this format, the errors of
+     * every format are given as a MachineFileError. */
+    pub fn read(&self, text: &str) -> Result<MachineFile, MachineFileError> {
+        match self {
+            FileFormat::Json => MachineFile::from_json(text),
+            FileFormat::Jflap => jflap::read_jff(text),
+            FileFormat::Dot => read_dot_file(text).map_err(|error| MachineFileError { message: error.to_string() }),
+            FileFormat::Text => automaton_text::parse_automaton(text).map_err(|error| MachineFileError { message: error.to_string() }),
+        }
+    }
+
+    /* Function that writes a machine in this format. Only finite and
+     * pushdown automata can be written as DOT graphs. */
+    pub fn write(&self, file: &MachineFile) -> Result<String, MachineFileError> {
+        match self {
+            FileFormat::Json => Ok(file.to_json()),
+            FileFormat::Jflap => Ok(jflap::write_jff(file)),
+            FileFormat::Dot => match file.to_machine() {
+                FileMachine::Finite(automaton) => Ok(automaton.to_dot()),
+                FileMachine::Pushdown(automaton) => Ok(automaton.to_dot()),
+                _ => Err(MachineFileError { message: "only finite and pushdown automata can be written as DOT graphs".to_string() }),
+            },
+            FileFormat::Text => Ok(automaton_text::format_automaton(file)),
+        }
+    }
+}
+
+/* Function that reads a DOT graph as a machine file without positions. It
+ * is a pushdown automaton if some label has the form input;pop/push, and a
+ * finite automaton otherwise. */
+pub fn read_dot_file(text: &str) -> Result<MachineFile, DotParseError> {
+    let graph = dot::parse_dot(text)?;
+    let is_pushdown = graph.edges.iter()
+        .filter_map(|edge| edge.attributes.get("label"))
+        .any(|label| label.contains(';'));
+    let machine = if is_pushdown {
+        let mut automaton = PushdownAutomata::new("Z".to_string());
+        graph.build_machine(&mut automaton, false)?;
+        FileMachine::Pushdown(automaton)
+    } else {
+        let mut automaton = FiniteAutomata::new();
+        graph.build_machine(&mut automaton, true)?;
+        FileMachine::Finite(automaton)
+    };
+    Ok(MachineFile::new(&machine, &HashMap::new()))
+}
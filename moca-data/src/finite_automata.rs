@@ -1,7 +1,12 @@
-use std::collections::{HashMap, HashSet, BTreeSet};
+use std::collections::{HashMap, HashSet, BTreeSet, VecDeque};
+use crate::dot::{self, DotParseError};
+use crate::grammar::Grammar;
 use crate::state::{StateID, Input, State};
 use crate::state_machine::StateMachine;
 
+// The sets of states two automata can be in after reading the same word.
+type StateSetPair = (BTreeSet<StateID>, BTreeSet<StateID>);
+
 /* Structure that represent a finite automaton.
  * The initial_state_id represents the initial state
  * of the automaton, if the value in None, then some
@@ -134,53 +139,6 @@ impl FiniteAutomata {
         }
     }
 
-    /*  ε-closure transition function of the DFA given a state and a string. */
-    pub fn lambda_closure(&self, state_id: StateID, input_string: &str) -> BTreeSet<StateID> {
-        let mut closure_set: BTreeSet<StateID> = BTreeSet::new();
-        self.lambda_closure_aux(state_id, input_string,&mut closure_set);
-        closure_set
-    }
-
-
-    /* The auxiliar recursive function of the lambda closure function. */
-    // If the state_id is the initial id of the automaton, then it will not be added in this
-    // function, so it needs to be added outside the function. It also "consumes" the input.
-    fn lambda_closure_aux(&self, state_id: StateID, input_string: &str, closure_set: &mut BTreeSet<StateID>) {
-        match self.states_by_id.get(&state_id) {
-            Some(state) => {
-                let mut valid_transitions = 0;
-                for (id, transitions) in state.iter_by_transition() {
-                    if closure_set.contains(id) {
-                        continue
-                    }
-                    for string in transitions {
-                        if string == "ε" || string == input_string {
-                            // If statement to add the states with loops
-                            if *id == state_id {
-                                closure_set.insert(*id);
-                            }
-                            // To add the states that have a lambda transition
-                            if string == "ε" && input_string.is_empty() {
-                                closure_set.insert(*id);
-                            }
-                            valid_transitions += 1;
-                            // It can have repeated states for the lambdas, but the replace handle
-                            // it.
-                            match input_string.strip_prefix(string) {
-                                Some(new_input) => self.lambda_closure_aux(*id, new_input, closure_set),
-                                None => self.lambda_closure_aux(*id, input_string, closure_set),
-                            }
-                        }
-                    }
-                }
-                    if valid_transitions == 0 && input_string.len() == 0 {
-                        closure_set.replace(state_id);
-                }
-            },
-            None => panic!("This should never occurr"),
-        }
-    }
-
     /* Auxiliar function that takes a hahsmap of btreesets of u64 mapped to 
      * a vector of tuples (btreeset<u64>, &str) that represents the transitions
      * given by the subset construction algorithm. */
@@ -198,13 +156,9 @@ impl FiniteAutomata {
             // what is the id of a subset in a transition.
             for (subset, _) in subsets_and_transitions.iter() {
                 let mut state = State::new(format!("q{}", id));
-                if let Some(initial_id) = self.initial_state_id {
-                    let mut initial_subset = self.lambda_closure(initial_id,"");
-                    initial_subset.insert(initial_id);
-                    if initial_subset == *subset {
-                        new_initial_id = id;
-                        state.initial_flag = true;
-                    }
+                if self.initial_closure() == *subset {
+                    new_initial_id = id;
+                    state.initial_flag = true;
                 }
                 for current_id in subset {
                     if let Some(current_state) = self.states_by_id.get(current_id) {
@@ -275,6 +229,147 @@ impl FiniteAutomata {
         }
         None
     }
+
+    /* Function that builds a right-linear grammar that generates the language
+     * of the automaton. Every state is a nonterminal with the name of the
+     * state, a transition p -a-> q gives the production p -> a q, an
+     * ε-transition gives p -> q, and every final state p has p -> ε. The
+     * start symbol is the initial state. If two states have the same name,
+     * apostrophes are added to the name of the second one. */
+    pub fn to_grammar(&self) -> Grammar {
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return Grammar::new("S"),
+        };
+        let mut ids: Vec<&StateID> = self.states_by_id.keys().collect();
+        ids.sort();
+        let mut names: HashMap<StateID, String> = HashMap::new();
+        for id in ids.iter() {
+            let mut name = self.states_by_id[id].name.clone();
+            while names.values().any(|used| *used == name) {
+                name.push('\'');
+            }
+            names.insert(**id, name);
+        }
+        let mut grammar = Grammar::new(&names[&initial_id]);
+        for id in ids.iter() {
+            grammar.add_nonterminal(&names[id]);
+        }
+        for id in ids {
+            let state = &self.states_by_id[id];
+            let mut transitions: Vec<(&StateID, &String)> = state.iter_by_transition()
+                .flat_map(|(to_id, inputs)| inputs.iter().map(move |input| (to_id, input)))
+                .collect();
+            transitions.sort_by_key(|(to_id, input)| (*input, *to_id));
+            for (to_id, input) in transitions {
+                let body = if input == "ε" || input.is_empty() {
+                    vec![names[to_id].clone()]
+                } else {
+                    vec![input.clone(), names[to_id].clone()]
+                };
+                grammar.add_production(&names[id], body);
+            }
+            if state.final_flag {
+                grammar.add_production(&names[id], Vec::new());
+            }
+        }
+        grammar
+    }
+
+    /* Function that writes the automaton as a Graphviz digraph, see dot::to_dot. */
+    pub fn to_dot(&self) -> String {
+        dot::to_dot(self)
+    }
+
+    /* Function that reads an automaton from a Graphviz digraph. The labels
+     * of an edge are its lines or its comma separated parts, e.g. "a, b". */
+    pub fn from_dot(text: &str) -> Result<FiniteAutomata, DotParseError> {
+        let mut automaton = FiniteAutomata::new();
+        dot::parse_dot(text)?.build_machine(&mut automaton, true)?;
+        Ok(automaton)
+    }
+
+    /* Function that compares the languages of two automata, deterministic or
+     * not. It returns a shortest word accepted by one of them and not by the
+     * other, or None if they are equivalent. It runs both automata at the
+     * same time on the sets of states they can be in, a breadth first search
+     * over the pairs of sets reached by every word, so the first pair where
+     * only one of the sets has a final state gives the word. Every label of a
+     * transition is a symbol and the transitions labeled ε or with the empty
+     * string are ε-transitions. An automaton without initial state accepts no
+     * word. */
+    pub fn distinguishing_word(&self, other: &FiniteAutomata) -> Option<String> {
+        let mut alphabet: BTreeSet<&str> = BTreeSet::new();
+        for automaton in [self, other] {
+            for state in automaton.states_by_id.values() {
+                for (_, labels) in state.iter_by_transition() {
+                    alphabet.extend(labels.iter().map(|label| label.as_str()).filter(|label| *label != "ε" && !label.is_empty()));
+                }
+            }
+        }
+        let start = (self.initial_closure(), other.initial_closure());
+        let mut visited: HashSet<StateSetPair> = HashSet::new();
+        let mut pending: VecDeque<(StateSetPair, String)> = VecDeque::new();
+        visited.insert(start.clone());
+        pending.push_back((start, String::new()));
+        while let Some(((own_states, other_states), word)) = pending.pop_front() {
+            if self.has_final_state(&own_states) != other.has_final_state(&other_states) {
+                return Some(word);
+            }
+            for symbol in alphabet.iter() {
+                let next = (self.step(&own_states, symbol), other.step(&other_states, symbol));
+                if visited.insert(next.clone()) {
+                    pending.push_back((next, format!("{}{}", word, symbol)));
+                }
+            }
+        }
+        None
+    }
+
+    // Auxiliar function that returns the states reached from the given ones by ε-transitions,
+    // including them.
+    fn epsilon_closure(&self, states: BTreeSet<StateID>) -> BTreeSet<StateID> {
+        let mut closure = states.clone();
+        let mut pending: Vec<StateID> = states.into_iter().collect();
+        while let Some(id) = pending.pop() {
+            if let Some(state) = self.states_by_id.get(&id) {
+                for (to_id, labels) in state.iter_by_transition() {
+                    if labels.iter().any(|label| label == "ε" || label.is_empty()) && closure.insert(*to_id) {
+                        pending.push(*to_id);
+                    }
+                }
+            }
+        }
+        closure
+    }
+
+    // Auxiliar function that returns the ε-closure of the initial state, empty if there is none.
+    fn initial_closure(&self) -> BTreeSet<StateID> {
+        match self.initial_state_id {
+            Some(initial_id) => self.epsilon_closure(BTreeSet::from([initial_id])),
+            None => BTreeSet::new(),
+        }
+    }
+
+    // Auxiliar function that returns the ε-closure of the states reached from the given ones
+    // reading a symbol.
+    fn step(&self, states: &BTreeSet<StateID>, symbol: &str) -> BTreeSet<StateID> {
+        let mut reached = BTreeSet::new();
+        for id in states {
+            if let Some(state) = self.states_by_id.get(id) {
+                for (to_id, labels) in state.iter_by_transition() {
+                    if labels.contains(symbol) {
+                        reached.insert(*to_id);
+                    }
+                }
+            }
+        }
+        self.epsilon_closure(reached)
+    }
+
+    fn has_final_state(&self, states: &BTreeSet<StateID>) -> bool {
+        states.iter().any(|id| self.final_states.contains(id))
+    }
 }
 
 impl StateMachine for FiniteAutomata {
@@ -537,13 +632,13 @@ pub fn subset_construction(automata: &FiniteAutomata) -> HashMap<BTreeSet<StateI
     // This is used to store all the subsets and their transitions in a table-like form, this
     // is used to construct the resulting dfa automaton.
     let mut transitions_by_subsets: HashMap<BTreeSet<StateID>, Vec<(BTreeSet<StateID>,&str)>> = HashMap::new();
-    let initial_id = match automata.initial_state_id {
-        Some(id) => id,
-        None => panic!("There is not an initial state.")
-    };
-    let mut current_subset = automata.lambda_closure(initial_id, "");
-    current_subset.insert(initial_id); // This line is required in this implementation.
+    if automata.initial_state_id.is_none() {
+        panic!("There is not an initial state.");
+    }
+    // The subsets are closed under ε-transitions, so the empty labels are not symbols of the dfa.
+    let current_subset = automata.initial_closure();
     sets_to_visit.push(current_subset.clone());
+    visited_sets.insert(current_subset.clone());
     transitions_by_subsets.insert(current_subset, Vec::new());
     while !sets_to_visit.is_empty() {
         let mut vector_transitions: Vec<(BTreeSet<u64>, &str)> = Vec::new();
@@ -554,8 +649,8 @@ pub fn subset_construction(automata: &FiniteAutomata) -> HashMap<BTreeSet<StateI
             None => panic!("There is no subset, this should never occur"),
         };
         
-        for string in automata.get_string_transitions() {
-            let new_subset = lambda_closure_subset(&automata, &current_subset, string);
+        for string in automata.get_string_transitions().iter().filter(|string| !string.is_empty()) {
+            let new_subset = automata.step(&current_subset, string);
             if new_subset.is_empty() || visited_sets.contains(&new_subset) {
                 vector_transitions.push((new_subset, string));
                 continue;
@@ -571,17 +666,5 @@ pub fn subset_construction(automata: &FiniteAutomata) -> HashMap<BTreeSet<StateI
             *vector = vector_transitions;
         }
     }
-    println!("{:?}", transitions_by_subsets);
-    println!("{:?}", visited_sets);
     transitions_by_subsets
 }
-
-//Auxiliar ε-closure function that takes a subset as a parameter and returns a set with all the ids
-//returned by the lambda closure function applied to all the elements of the subset.
-fn lambda_closure_subset(automata: &FiniteAutomata, subset: &BTreeSet<StateID>, input_string: &str) -> BTreeSet<StateID> {
-    let mut subset_result: BTreeSet<StateID> = BTreeSet::new();
-    for id in subset {
-        subset_result = subset_result.union(&automata.lambda_closure(*id, input_string)).cloned().collect();
-    }
-    subset_result
-}
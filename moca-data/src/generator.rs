@@ -0,0 +1,195 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::grammar::{Grammar, Production, Symbol, MAX_SENTENTIAL_FORMS};
+
+/* Default maximum depth of the parse trees of the random sentences. */
+pub const DEFAULT_MAX_DEPTH: usize = 10;
+
+/* Structure that generates random sentences of a grammar. The generator is
+ * seeded, so the same seed always gives the same sentences, which is useful
+ * to repeat the tests that use them. Every production has a weight, 1 by
+ * default, and the productions of a nonterminal are chosen with probability
+ * proportional to their weights. The parse trees of the sentences are not
+ * deeper than the maximum depth. */
+#[derive(Debug, Clone)]
+pub struct SentenceGenerator {
+    grammar: Grammar,
+    weights: HashMap<Production, f64>,
+    heights: HashMap<Symbol, usize>,
+    max_depth: usize,
+    rng: StdRng,
+}
+
+impl SentenceGenerator {
+    pub fn new(grammar: &Grammar, seed: u64) -> Self {
+        SentenceGenerator {
+            grammar: grammar.clone(),
+            weights: HashMap::new(),
+            heights: minimum_heights(grammar),
+            max_depth: DEFAULT_MAX_DEPTH,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn get_max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    // Getter for the weight of a production, 1 if it was not changed.
+    pub fn get_weight(&self, production: &Production) -> f64 {
+        self.weights.get(production).copied().unwrap_or(1.0)
+    }
+
+    /* Function to change the weight of a production, the productions with
+     * weight 0 are only chosen if there is no other way to end the tree
+     * within the maximum depth. */
+    pub fn set_weight(&mut self, production: &Production, weight: f64) {
+        self.weights.insert(production.clone(), weight.max(0.0));
+    }
+
+    /* Function that returns a random sentence of the grammar, or None if the
+     * start symbol can't derive a word with a parse tree within the maximum
+     * depth. At every node only the productions that can still end the tree
+     * within the depth are chosen. */
+    pub fn generate(&mut self) -> Option<Vec<Symbol>> {
+        let start_symbol = self.grammar.get_start_symbol().clone();
+        if self.heights.get(&start_symbol).is_none_or(|height| *height > self.max_depth) {
+            return None;
+        }
+        let mut sentence = Vec::new();
+        self.expand(&start_symbol, self.max_depth, &mut sentence);
+        Some(sentence)
+    }
+
+    // Returns the given number of random sentences, which can be repeated.
+    pub fn generate_many(&mut self, count: usize) -> Vec<Vec<Symbol>> {
+        (0..count).map_while(|_| self.generate()).collect()
+    }
+
+    // Auxiliar function that appends to the sentence a random word derived from the
+    // symbol with a parse tree of at most the given depth.
+    fn expand(&mut self, symbol: &Symbol, depth: usize, sentence: &mut Vec<Symbol>) {
+        if !self.grammar.is_nonterminal(symbol) {
+            sentence.push(symbol.clone());
+            return;
+        }
+        let candidates: Vec<Production> = self.grammar.productions_of(symbol)
+            .filter(|production| self.production_height(production).is_some_and(|height| height <= depth))
+            .cloned()
+            .collect();
+        let total: f64 = candidates.iter().map(|production| self.get_weight(production)).sum();
+        let production = if total > 0.0 {
+            let mut choice = self.rng.gen_range(0.0..total);
+            candidates.iter()
+                .find(|production| {
+                    choice -= self.get_weight(production);
+                    choice < 0.0
+                })
+                .unwrap_or(&candidates[candidates.len() - 1])
+                .clone()
+        } else {
+            candidates[self.rng.gen_range(0..candidates.len())].clone()
+        };
+        for body_symbol in production.body.iter() {
+            self.expand(body_symbol, depth - 1, sentence);
+        }
+    }
+
+    // Returns the minimum height of a parse tree whose root uses the production.
+    fn production_height(&self, production: &Production) -> Option<usize> {
+        production_height(&self.grammar, &self.heights, production)
+    }
+}
+
+/* The sentences found by all_sentences. If truncated is true the search
+ * gave up before visiting every form, so some sentences may be missing. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceList {
+    pub sentences: Vec<Vec<Symbol>>,
+    pub truncated: bool,
+}
+
+/* Function that returns every sentence of the grammar with at most
+ * max_length terminals, ordered by length and then alphabetically. The
+ * ε-productions and unit productions are removed first, so the sentential
+ * forms never get shorter and there are finitely many of them. Then the
+ * leftmost derivations are explored breadth first, discarding the forms
+ * longer than max_length, and the search stops after MAX_SENTENTIAL_FORMS
+ * forms, marking the list as truncated. */
+pub fn all_sentences(grammar: &Grammar, max_length: usize) -> SentenceList {
+    let grammar = grammar.add_new_start_symbol()
+        .remove_epsilon_productions()
+        .remove_unit_productions()
+        .remove_useless_symbols();
+    let fits = |form: &[Symbol]| form.len() <= max_length;
+    let mut sentences: BTreeSet<(usize, Vec<Symbol>)> = BTreeSet::new();
+    let start = vec![grammar.get_start_symbol().clone()];
+    if !fits(&start) {
+        return SentenceList { sentences: Vec::new(), truncated: false };
+    }
+    let mut visited: HashSet<Vec<Symbol>> = HashSet::new();
+    visited.insert(start.clone());
+    let mut queue: VecDeque<Vec<Symbol>> = VecDeque::new();
+    queue.push_back(start);
+    let mut truncated = false;
+    while let Some(form) = queue.pop_front() {
+        let position = match form.iter().position(|symbol| grammar.is_nonterminal(symbol)) {
+            Some(position) => position,
+            None => {
+                sentences.insert((form.len(), form));
+                continue;
+            }
+        };
+        for production in grammar.productions_of(&form[position]) {
+            let mut next = form[..position].to_vec();
+            next.extend(production.body.iter().cloned());
+            next.extend(form[position + 1..].iter().cloned());
+            if !fits(&next) || visited.contains(&next) {
+                continue;
+            }
+            if visited.len() == MAX_SENTENTIAL_FORMS {
+                truncated = true;
+                continue;
+            }
+            visited.insert(next.clone());
+            queue.push_back(next);
+        }
+    }
+    SentenceList {
+        sentences: sentences.into_iter().map(|(_, sentence)| sentence).collect(),
+        truncated,
+    }
+}
+
+// Returns the minimum height of a parse tree of every generating nonterminal,
+// the terminals are leaves and have height 0.
+fn minimum_heights(grammar: &Grammar) -> HashMap<Symbol, usize> {
+    let mut heights: HashMap<Symbol, usize> = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for production in grammar.get_productions() {
+            if let Some(height) = production_height(grammar, &heights, production) {
+                if heights.get(&production.head).is_none_or(|current| height < *current) {
+                    heights.insert(production.head.clone(), height);
+                    changed = true;
+                }
+            }
+        }
+    }
+    heights
+}
+
+// Returns the minimum height of a parse tree whose root uses the production,
+// or None if some nonterminal of the body has no known height.
+fn production_height(grammar: &Grammar, heights: &HashMap<Symbol, usize>, production: &Production) -> Option<usize> {
+    production.body.iter()
+        .map(|symbol| if grammar.is_nonterminal(symbol) { heights.get(symbol).copied() } else { Some(0) })
+        .try_fold(0, |height, symbol_height| symbol_height.map(|symbol_height| height.max(symbol_height)))
+        .map(|height| height + 1)
+}
@@ -0,0 +1,1459 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use crate::finite_automata::FiniteAutomata;
+use crate::pushdown_automata::{split_stack_symbols, PushdownAutomata};
+use crate::state_machine::StateMachine;
+
+/* Type to represent a terminal or a nonterminal of a grammar. */
+pub type Symbol = String;
+
+/* Maximum number of sentential forms visited while searching a derivation. */
+pub const MAX_SENTENTIAL_FORMS: usize = 100_000;
+
+/* Symbol that marks the end of the input in the FOLLOW sets and the parsers,
+ * a grammar with it as a terminal uses the one given by Grammar::end_marker. */
+pub const END_MARKER: &str = "$";
+
+/* The result of the search of a leftmost derivation: the sentential forms
+ * of the derivation, NotDerivable if every form that could derive the word
+ * was explored, or Truncated if the search gave up before. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DerivationSearch {
+    Found(Vec<Vec<Symbol>>),
+    NotDerivable,
+    Truncated,
+}
+
+impl DerivationSearch {
+    // Returns the derivation found, if any.
+    pub fn found(self) -> Option<Vec<Vec<Symbol>>> {
+        match self {
+            DerivationSearch::Found(derivation) => Some(derivation),
+            _ => None,
+        }
+    }
+}
+
+/* A grammar obtained in one step of a transformation, e.g. the conversion
+ * to Chomsky normal form, with the name of the step. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformationStep {
+    pub name: String,
+    pub grammar: Grammar,
+}
+
+impl TransformationStep {
+    fn new(name: &str, grammar: Grammar) -> Self {
+        TransformationStep {
+            name: name.to_string(),
+            grammar,
+        }
+    }
+}
+
+/* Struct that represents a production head -> body of a context-free grammar.
+ * An empty body represents the production head -> ε. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Production {
+    pub head: Symbol,
+    pub body: Vec<Symbol>,
+}
+
+impl Production {
+    pub fn new(head: &str, body: Vec<Symbol>) -> Self {
+        Production {
+            head: head.to_string(),
+            body,
+        }
+    }
+
+    pub fn is_epsilon(&self) -> bool {
+        self.body.is_empty()
+    }
+}
+
+impl fmt::Display for Production {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.head, format_symbols(&self.body))
+    }
+}
+
+/* Struct that represents a parse tree, the leaves are terminals or ε
+ * and every inner node is a nonterminal with the body of one of its
+ * productions as children. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseTree {
+    pub symbol: Symbol,
+    pub children: Vec<ParseTree>,
+}
+
+impl ParseTree {
+    pub fn new(symbol: &str, children: Vec<ParseTree>) -> Self {
+        ParseTree {
+            symbol: symbol.to_string(),
+            children,
+        }
+    }
+
+    pub fn leaf(symbol: &str) -> Self {
+        ParseTree::new(symbol, Vec::new())
+    }
+
+    // Returns the leaves of the tree from left to right, without the ε leaves.
+    pub fn get_yield(&self) -> Vec<Symbol> {
+        if self.children.is_empty() {
+            return if self.symbol == "ε" { Vec::new() } else { vec![self.symbol.clone()] };
+        }
+        self.children.iter().flat_map(|child| child.get_yield()).collect()
+    }
+
+    // Auxiliar function to print the tree with one node per line.
+    fn write_lines(&self, f: &mut fmt::Formatter<'_>, prefix: &str, last: bool, root: bool) -> fmt::Result {
+        if root {
+            writeln!(f, "{}", self.symbol)?;
+        } else {
+            writeln!(f, "{}{} {}", prefix, if last { "└─" } else { "├─" }, self.symbol)?;
+        }
+        let child_prefix = if root {
+            String::new()
+        } else {
+            format!("{}{}", prefix, if last { "   " } else { "│  " })
+        };
+        for (index, child) in self.children.iter().enumerate() {
+            child.write_lines(f, &child_prefix, index + 1 == self.children.len(), false)?;
+        }
+        Ok(())
+    }
+}
+
+/* The tree is printed with one node per line, e.g.
+ *     S
+ *     ├─ a
+ *     └─ b */
+impl fmt::Display for ParseTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_lines(f, "", true, true)
+    }
+}
+
+/* Struct with the analyses of a grammar that are usually done before
+ * building a parser, with the grammars obtained by each transformation. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarReport {
+    pub nullable: BTreeSet<Symbol>,
+    pub generating: BTreeSet<Symbol>,
+    pub reachable: BTreeSet<Symbol>,
+    pub useless: BTreeSet<Symbol>,
+    pub left_recursive: BTreeSet<Symbol>,
+    pub without_useless_symbols: Grammar,
+    pub without_left_recursion: Grammar,
+    pub left_factored: Grammar,
+}
+
+/* The sets only have nonterminals, and the grammars are printed after them. */
+impl fmt::Display for GrammarReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_set = |set: &BTreeSet<Symbol>| format!("{{{}}}", set.iter().cloned().collect::<Vec<_>>().join(", "));
+        writeln!(f, "Nullable: {}", write_set(&self.nullable))?;
+        writeln!(f, "Generating: {}", write_set(&self.generating))?;
+        writeln!(f, "Reachable: {}", write_set(&self.reachable))?;
+        writeln!(f, "Useless: {}", write_set(&self.useless))?;
+        writeln!(f, "Left recursive: {}", write_set(&self.left_recursive))?;
+        writeln!(f, "\nWithout useless symbols:\n{}", self.without_useless_symbols)?;
+        writeln!(f, "Without left recursion:\n{}", self.without_left_recursion)?;
+        write!(f, "Left factored:\n{}", self.left_factored)
+    }
+}
+
+/* Error returned when the text of a grammar can't be read. The line starts at 1. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for GrammarParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/* Structure that represents a context-free grammar.
+ * The nonterminals are stored in the order they were added, which is the
+ * order used to print the grammar, the first one is usually the start symbol.
+ * The terminals are not stored, they are all the symbols of the bodies that
+ * are not nonterminals. */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grammar {
+    nonterminals: Vec<Symbol>,
+    productions: Vec<Production>,
+    start_symbol: Symbol,
+}
+
+impl Grammar {
+    pub fn new(start_symbol: &str) -> Self {
+        Grammar {
+            nonterminals: vec![start_symbol.to_string()],
+            productions: Vec::new(),
+            start_symbol: start_symbol.to_string(),
+        }
+    }
+
+    /* Function to read a grammar written in the usual notation, one
+     * nonterminal per line with its alternatives separated by |, e.g.
+     *     S -> aSb | ε
+     * The arrow can also be written as →, and a line starting with | continues
+     * the alternatives of the previous line. The start symbol is the head of
+     * the first line. If some body has symbols separated by spaces, every
+     * body is split by spaces, otherwise they are split with split_grammar_symbols,
+     * so symbols longer than one character have to be separated by spaces.
+     * The nonterminals are the heads, the symbols that start with an uppercase
+     * letter and the ones between brackets, like [q,A,p] or <a>, every other
+     * symbol is a terminal. Without spaces [ and ] are terminals, e.g. [S]S
+     * has four symbols, so the nonterminals like [q,A,p] need the spaces. */
+    pub fn parse(text: &str) -> Result<Grammar, GrammarParseError> {
+        let mut grammar: Option<Grammar> = None;
+        let mut current_head: Option<Symbol> = None;
+        let mut bodies: Vec<(usize, Symbol, String)> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let alternatives = if let Some(rest) = line.strip_prefix('|') {
+                match &current_head {
+                    Some(_) => rest,
+                    None => return Err(GrammarParseError {
+                        line: line_number,
+                        message: "alternatives without a production head".to_string(),
+                    }),
+                }
+            } else {
+                let (head, rest) = match line.split_once("->").or_else(|| line.split_once('→')) {
+                    Some(parts) => parts,
+                    None => return Err(GrammarParseError {
+                        line: line_number,
+                        message: "expected a production of the form A -> α".to_string(),
+                    }),
+                };
+                let head = head.trim();
+                if head.is_empty() || head.split_whitespace().count() > 1 {
+                    return Err(GrammarParseError {
+                        line: line_number,
+                        message: format!("invalid production head \"{}\"", head),
+                    });
+                }
+                match grammar.as_mut() {
+                    Some(grammar) => grammar.add_nonterminal(head),
+                    None => grammar = Some(Grammar::new(head)),
+                }
+                current_head = Some(head.to_string());
+                rest
+            };
+            if let Some(head) = &current_head {
+                for alternative in alternatives.split('|') {
+                    bodies.push((line_number, head.clone(), alternative.to_string()));
+                }
+            }
+        }
+        let mut grammar = match grammar {
+            Some(grammar) => grammar,
+            None => return Err(GrammarParseError {
+                line: 1,
+                message: "the grammar has no productions".to_string(),
+            }),
+        };
+        let separated = bodies.iter().any(|(_, _, alternative)| alternative.split_whitespace().count() > 1);
+        for (line_number, head, alternative) in bodies {
+            if alternative.contains("->") || alternative.contains('→') {
+                return Err(GrammarParseError {
+                    line: line_number,
+                    message: "a production can only have one arrow".to_string(),
+                });
+            }
+            let body: Vec<Symbol> = if separated {
+                alternative.split_whitespace().map(|symbol| symbol.to_string()).collect()
+            } else {
+                split_grammar_symbols(&alternative)
+            };
+            for symbol in body.iter() {
+                if looks_like_nonterminal(symbol) {
+                    grammar.add_nonterminal(symbol);
+                }
+            }
+            grammar.add_production(&head, body);
+        }
+        Ok(grammar)
+    }
+
+    // Getter for the start symbol of the grammar.
+    pub fn get_start_symbol(&self) -> &Symbol {
+        &self.start_symbol
+    }
+
+    pub fn set_start_symbol(&mut self, start_symbol: &str) {
+        self.add_nonterminal(start_symbol);
+        self.start_symbol = start_symbol.to_string();
+    }
+
+    // Getter for the nonterminals in the order they were added.
+    pub fn get_nonterminals(&self) -> &Vec<Symbol> {
+        &self.nonterminals
+    }
+
+    // Getter for the productions in the order they were added.
+    pub fn get_productions(&self) -> &Vec<Production> {
+        &self.productions
+    }
+
+    /* Returns the terminals of the grammar, i.e. the symbols in the bodies
+     * of the productions that are not nonterminals. */
+    pub fn get_terminals(&self) -> BTreeSet<Symbol> {
+        let mut terminals = BTreeSet::new();
+        for production in self.productions.iter() {
+            for symbol in production.body.iter() {
+                if !self.is_nonterminal(symbol) {
+                    terminals.insert(symbol.clone());
+                }
+            }
+        }
+        terminals
+    }
+
+    pub fn is_nonterminal(&self, symbol: &str) -> bool {
+        self.nonterminals.iter().any(|nonterminal| nonterminal == symbol)
+    }
+
+    pub fn is_terminal(&self, symbol: &str) -> bool {
+        !self.is_nonterminal(symbol)
+    }
+
+    /* Function to add a nonterminal, if the nonterminal already exists
+     * then nothing changes. */
+    pub fn add_nonterminal(&mut self, symbol: &str) {
+        if !self.is_nonterminal(symbol) {
+            self.nonterminals.push(symbol.to_string());
+        }
+    }
+
+    /* Function to add a production, the head is added as a nonterminal.
+     * The ε symbols of the body are omitted, and a production that
+     * already exists is not added twice. */
+    pub fn add_production(&mut self, head: &str, body: Vec<Symbol>) {
+        self.add_nonterminal(head);
+        let body: Vec<Symbol> = body.into_iter().filter(|symbol| symbol != "ε").collect();
+        let production = Production::new(head, body);
+        if !self.productions.contains(&production) {
+            self.productions.push(production);
+        }
+    }
+
+    // Function to remove a production, it doesn't remove the nonterminals.
+    pub fn remove_production(&mut self, production: &Production) {
+        self.productions.retain(|p| p != production);
+    }
+
+    // Iterator over the productions of a given nonterminal.
+    pub fn productions_of<'a>(&'a self, head: &'a str) -> impl Iterator<Item = &'a Production> + 'a {
+        self.productions.iter().filter(move |production| production.head == head)
+    }
+
+    /* Returns the nonterminals that derive ε. A nonterminal is nullable if
+     * it has a production whose body only has nullable nonterminals, so the
+     * set is computed until it doesn't change. */
+    pub fn nullable_nonterminals(&self) -> BTreeSet<Symbol> {
+        let mut nullable: BTreeSet<Symbol> = BTreeSet::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in self.productions.iter() {
+                if !nullable.contains(&production.head)
+                    && production.body.iter().all(|symbol| nullable.contains(symbol)) {
+                    nullable.insert(production.head.clone());
+                    changed = true;
+                }
+            }
+        }
+        nullable
+    }
+
+    /* Returns the symbols that derive a string of terminals. The terminals
+     * generate themselves, and a nonterminal is generating if it has a
+     * production whose body only has generating symbols. */
+    pub fn generating_symbols(&self) -> BTreeSet<Symbol> {
+        let mut generating: BTreeSet<Symbol> = self.get_terminals();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in self.productions.iter() {
+                if !generating.contains(&production.head)
+                    && production.body.iter().all(|symbol| generating.contains(symbol)) {
+                    generating.insert(production.head.clone());
+                    changed = true;
+                }
+            }
+        }
+        generating
+    }
+
+    /* Returns the symbols that appear in some sentential form, i.e. the
+     * symbols reachable from the start symbol through the productions. */
+    pub fn reachable_symbols(&self) -> BTreeSet<Symbol> {
+        let mut reachable: BTreeSet<Symbol> = BTreeSet::new();
+        let mut stack: Vec<&Symbol> = vec![&self.start_symbol];
+        reachable.insert(self.start_symbol.clone());
+        while let Some(symbol) = stack.pop() {
+            for production in self.productions_of(symbol) {
+                for body_symbol in production.body.iter() {
+                    if reachable.insert(body_symbol.clone()) {
+                        stack.push(body_symbol);
+                    }
+                }
+            }
+        }
+        reachable
+    }
+
+    /* Function that returns an equivalent grammar without useless symbols.
+     * First the productions with non generating symbols are removed, and
+     * then the ones with unreachable symbols, the order matters because
+     * removing the non generating symbols can make others unreachable.
+     * The start symbol is always kept. */
+    pub fn remove_useless_symbols(&self) -> Grammar {
+        let generating = self.generating_symbols();
+        let mut grammar = self.clone();
+        grammar.productions.retain(|production| generating.contains(&production.head)
+            && production.body.iter().all(|symbol| generating.contains(symbol)));
+        let reachable = grammar.reachable_symbols();
+        grammar.productions.retain(|production| reachable.contains(&production.head));
+        let start_symbol = grammar.start_symbol.clone();
+        grammar.nonterminals.retain(|nonterminal| *nonterminal == start_symbol
+            || (generating.contains(nonterminal) && reachable.contains(nonterminal)));
+        grammar
+    }
+
+    /* Function that returns an equivalent grammar with a new start symbol S'
+     * and the production S' -> S, so the start symbol never appears in the
+     * body of a production. */
+    pub fn add_new_start_symbol(&self) -> Grammar {
+        let start_symbol = self.fresh_nonterminal(&format!("{}'", self.start_symbol));
+        let mut grammar = Grammar::new(&start_symbol);
+        for nonterminal in self.nonterminals.iter() {
+            grammar.add_nonterminal(nonterminal);
+        }
+        grammar.add_production(&start_symbol, vec![self.start_symbol.clone()]);
+        grammar.productions.extend(self.productions.iter().cloned());
+        grammar
+    }
+
+    /* Function that returns an equivalent grammar without ε-productions,
+     * except the production S -> ε for the start symbol when the grammar
+     * generates ε. Every production is replaced by the ones obtained by
+     * omitting any subset of its nullable symbols, the productions A -> A
+     * obtained this way are useless and are not added. */
+    pub fn remove_epsilon_productions(&self) -> Grammar {
+        let nullable = self.nullable_nonterminals();
+        let mut grammar = self.without_productions();
+        for production in self.productions.iter() {
+            let mut bodies: Vec<Vec<Symbol>> = vec![Vec::new()];
+            for symbol in production.body.iter() {
+                let mut next_bodies = Vec::new();
+                for body in bodies {
+                    if nullable.contains(symbol) {
+                        next_bodies.push(body.clone());
+                    }
+                    let mut body = body;
+                    body.push(symbol.clone());
+                    next_bodies.push(body);
+                }
+                bodies = next_bodies;
+            }
+            for body in bodies.into_iter().filter(|body| !body.is_empty() && *body != [production.head.clone()]) {
+                grammar.add_production(&production.head, body);
+            }
+        }
+        if nullable.contains(&self.start_symbol) {
+            grammar.add_production(&self.start_symbol.clone(), Vec::new());
+        }
+        grammar
+    }
+
+    /* Function that returns an equivalent grammar without unit productions
+     * A -> B. For every pair of nonterminals A, B such that A derives B using
+     * only unit productions, the productions of B that are not unit are
+     * added to A. */
+    pub fn remove_unit_productions(&self) -> Grammar {
+        let mut grammar = self.without_productions();
+        for nonterminal in self.nonterminals.iter() {
+            let mut unit_pairs: Vec<&Symbol> = vec![nonterminal];
+            let mut index = 0;
+            while index < unit_pairs.len() {
+                for production in self.productions_of(unit_pairs[index]) {
+                    if self.is_unit_production(production) && !unit_pairs.contains(&&production.body[0]) {
+                        unit_pairs.push(&production.body[0]);
+                    }
+                }
+                index += 1;
+            }
+            for symbol in unit_pairs {
+                for production in self.productions_of(symbol) {
+                    if !self.is_unit_production(production) {
+                        grammar.add_production(nonterminal, production.body.clone());
+                    }
+                }
+            }
+        }
+        grammar
+    }
+
+    /* Function that returns the equivalent grammar in Chomsky normal form,
+     * where every production is A -> BC, A -> a or S -> ε. */
+    pub fn to_chomsky_normal_form(&self) -> Grammar {
+        match self.chomsky_normal_form_steps().pop() {
+            Some(step) => step.grammar,
+            None => self.clone(),
+        }
+    }
+
+    /* Function that converts the grammar to Chomsky normal form, returning
+     * the grammar obtained in every step:
+     *  1. Add a new start symbol.
+     *  2. Remove the ε-productions.
+     *  3. Remove the unit productions.
+     *  4. Remove the useless symbols.
+     *  5. Replace the terminals of the long bodies by new nonterminals <a> -> a,
+     *     and split the bodies longer than two symbols with new nonterminals. */
+    pub fn chomsky_normal_form_steps(&self) -> Vec<TransformationStep> {
+        let mut steps = Vec::new();
+        let grammar = self.add_new_start_symbol();
+        steps.push(TransformationStep::new("Add a new start symbol", grammar.clone()));
+        let grammar = grammar.remove_epsilon_productions();
+        steps.push(TransformationStep::new("Remove ε-productions", grammar.clone()));
+        let grammar = grammar.remove_unit_productions();
+        steps.push(TransformationStep::new("Remove unit productions", grammar.clone()));
+        let grammar = grammar.remove_useless_symbols();
+        steps.push(TransformationStep::new("Remove useless symbols", grammar.clone()));
+        let grammar = grammar.binarize_productions();
+        steps.push(TransformationStep::new("Convert to Chomsky normal form", grammar));
+        steps
+    }
+
+    /* Returns true if every production is of the form A -> BC or A -> a,
+     * and the only ε-production is S -> ε when S is not in any body. */
+    pub fn is_chomsky_normal_form(&self) -> bool {
+        let start_in_body = self.productions.iter()
+            .any(|production| production.body.contains(&self.start_symbol));
+        self.productions.iter().all(|production| match production.body.len() {
+            0 => production.head == self.start_symbol && !start_in_body,
+            1 => self.is_terminal(&production.body[0]),
+            2 => production.body.iter().all(|symbol| self.is_nonterminal(symbol)),
+            _ => false,
+        })
+    }
+
+    /* Function that returns the equivalent grammar in Greibach normal form,
+     * where every production is A -> aB1...Bk with k >= 0, or S -> ε. */
+    pub fn to_greibach_normal_form(&self) -> Grammar {
+        match self.greibach_normal_form_steps().pop() {
+            Some(step) => step.grammar,
+            None => self.clone(),
+        }
+    }
+
+    /* Function that converts the grammar to Greibach normal form, returning
+     * the grammar obtained in every step:
+     *  1-5. Convert to Chomsky normal form, as in chomsky_normal_form_steps.
+     *  6. Order the nonterminals A1, ..., An and remove the left recursion,
+     *     so every body of Ai begins with a terminal or with Aj, j > i. The
+     *     new nonterminals Ai' have no ε-productions.
+     *  7. Replace the leading nonterminals by their bodies from An to A1,
+     *     and then in the productions of the new nonterminals, and remove
+     *     the useless symbols.
+     * The symbols after the first one are always nonterminals, because the
+     * bodies in Chomsky normal form with two symbols are nonterminals. */
+    pub fn greibach_normal_form_steps(&self) -> Vec<TransformationStep> {
+        let mut steps = self.chomsky_normal_form_steps();
+        let grammar = match steps.last() {
+            Some(step) => step.grammar.clone(),
+            None => self.clone(),
+        };
+        let order = grammar.nonterminals.clone();
+        let mut grammar = grammar.eliminate_left_recursion_with(false);
+        steps.push(TransformationStep::new("Remove left recursion", grammar.clone()));
+        let new_nonterminals: Vec<Symbol> = grammar.nonterminals.iter()
+            .filter(|nonterminal| !order.contains(nonterminal))
+            .cloned()
+            .collect();
+        for nonterminal in order.iter().rev().chain(new_nonterminals.iter()) {
+            let leading: BTreeSet<Symbol> = grammar.productions_of(nonterminal)
+                .filter_map(|production| production.body.first())
+                .filter(|symbol| grammar.is_nonterminal(symbol))
+                .cloned()
+                .collect();
+            for symbol in leading {
+                grammar = grammar.substitute_leading_nonterminal(nonterminal, &symbol);
+            }
+        }
+        steps.push(TransformationStep::new("Convert to Greibach normal form", grammar.remove_useless_symbols()));
+        steps
+    }
+
+    /* Returns true if every production is of the form A -> aB1...Bk with
+     * k >= 0, and the only ε-production is S -> ε when S is not in any body. */
+    pub fn is_greibach_normal_form(&self) -> bool {
+        let start_in_body = self.productions.iter()
+            .any(|production| production.body.contains(&self.start_symbol));
+        self.productions.iter().all(|production| match production.body.split_first() {
+            Some((first, rest)) => self.is_terminal(first) && rest.iter().all(|symbol| self.is_nonterminal(symbol)),
+            None => production.head == self.start_symbol && !start_in_body,
+        })
+    }
+
+    // Auxiliar function for the last step of the conversion to Chomsky normal form.
+    fn binarize_productions(&self) -> Grammar {
+        let terminals = self.get_terminals();
+        let mut grammar = self.without_productions();
+        let mut terminal_nonterminals: HashMap<Symbol, Symbol> = HashMap::new();
+        for terminal in terminals.iter() {
+            let used = self.productions.iter()
+                .any(|production| production.body.len() > 1 && production.body.contains(terminal));
+            if used {
+                let nonterminal = fresh_symbol(&format!("<{}>", terminal),
+                    |symbol| grammar.is_nonterminal(symbol) || terminals.contains(symbol));
+                grammar.add_nonterminal(&nonterminal);
+                terminal_nonterminals.insert(terminal.clone(), nonterminal);
+            }
+        }
+        for production in self.productions.iter() {
+            if production.body.len() < 2 {
+                grammar.add_production(&production.head, production.body.clone());
+                continue;
+            }
+            let body: Vec<Symbol> = production.body.iter()
+                .map(|symbol| terminal_nonterminals.get(symbol).unwrap_or(symbol).clone())
+                .collect();
+            let mut head = production.head.clone();
+            for symbol in body[..body.len() - 2].iter() {
+                let next = fresh_symbol(&format!("<{}1>", production.head),
+                    |symbol| grammar.is_nonterminal(symbol) || terminals.contains(symbol));
+                grammar.add_nonterminal(&next);
+                grammar.add_production(&head, vec![symbol.clone(), next.clone()]);
+                head = next;
+            }
+            grammar.add_production(&head, body[body.len() - 2..].to_vec());
+        }
+        for terminal in terminals {
+            if let Some(nonterminal) = terminal_nonterminals.get(&terminal) {
+                grammar.add_production(nonterminal, vec![terminal]);
+            }
+        }
+        grammar
+    }
+
+    // Returns true if the production is of the form A -> B.
+    fn is_unit_production(&self, production: &Production) -> bool {
+        production.body.len() == 1 && self.is_nonterminal(&production.body[0])
+    }
+
+    // Returns true if some nonterminal derives itself using only unit productions.
+    fn has_unit_cycles(&self) -> bool {
+        self.nonterminals.iter().any(|nonterminal| {
+            let mut reached: Vec<&Symbol> = Vec::new();
+            let mut pending: Vec<&Symbol> = vec![nonterminal];
+            while let Some(symbol) = pending.pop() {
+                for production in self.productions_of(symbol).filter(|p| self.is_unit_production(p)) {
+                    if production.body[0] == *nonterminal {
+                        return true;
+                    }
+                    if !reached.contains(&&production.body[0]) {
+                        reached.push(&production.body[0]);
+                        pending.push(&production.body[0]);
+                    }
+                }
+            }
+            false
+        })
+    }
+
+    // Returns a copy of the grammar with the same nonterminals and no productions.
+    fn without_productions(&self) -> Grammar {
+        Grammar {
+            nonterminals: self.nonterminals.clone(),
+            productions: Vec::new(),
+            start_symbol: self.start_symbol.clone(),
+        }
+    }
+
+    // Returns a nonterminal based on the name that is not a symbol of the grammar.
+    fn fresh_nonterminal(&self, name: &str) -> Symbol {
+        let terminals = self.get_terminals();
+        fresh_symbol(name, |symbol| self.is_nonterminal(symbol) || terminals.contains(symbol))
+    }
+
+    /* Returns the FIRST set of every nonterminal, i.e. the terminals that
+     * begin the strings derived from it, with ε if it is nullable. The sets
+     * are computed until they don't change. */
+    pub fn first_sets(&self) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+        let mut first: BTreeMap<Symbol, BTreeSet<Symbol>> = self.nonterminals.iter()
+            .map(|nonterminal| (nonterminal.clone(), BTreeSet::new()))
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in self.productions.iter() {
+                let body_first = self.first_of_with(&production.body, &first);
+                let head_first = first.entry(production.head.clone()).or_default();
+                let size = head_first.len();
+                head_first.extend(body_first);
+                changed |= head_first.len() != size;
+            }
+        }
+        first
+    }
+
+    /* Returns the FIRST set of a sentential form, it has ε if every symbol
+     * of the form is nullable, in particular if the form is empty. */
+    pub fn first_of(&self, symbols: &[Symbol]) -> BTreeSet<Symbol> {
+        self.first_of_with(symbols, &self.first_sets())
+    }
+
+    /* Returns the FIRST set of a sentential form given the FIRST sets of the
+     * nonterminals, so they are not computed again for every form. */
+    pub fn first_of_with(&self, symbols: &[Symbol], first: &BTreeMap<Symbol, BTreeSet<Symbol>>) -> BTreeSet<Symbol> {
+        let mut result = BTreeSet::new();
+        for symbol in symbols.iter() {
+            if self.is_terminal(symbol) {
+                result.insert(symbol.clone());
+                return result;
+            }
+            let symbol_first = match first.get(symbol) {
+                Some(symbol_first) => symbol_first,
+                None => return result,
+            };
+            result.extend(symbol_first.iter().filter(|terminal| *terminal != "ε").cloned());
+            if !symbol_first.contains("ε") {
+                return result;
+            }
+        }
+        result.insert("ε".to_string());
+        result
+    }
+
+    /* Returns the symbol that marks the end of the input, the END_MARKER
+     * followed by apostrophes until it is not a terminal of the grammar. */
+    pub fn end_marker(&self) -> Symbol {
+        let terminals = self.get_terminals();
+        fresh_symbol(END_MARKER, |symbol| terminals.contains(symbol))
+    }
+
+    /* Returns the FOLLOW set of every nonterminal, i.e. the terminals that
+     * can appear right after it in a sentential form, with the end marker
+     * if it can be the last symbol. */
+    pub fn follow_sets(&self) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+        let first = self.first_sets();
+        let mut follow: BTreeMap<Symbol, BTreeSet<Symbol>> = self.nonterminals.iter()
+            .map(|nonterminal| (nonterminal.clone(), BTreeSet::new()))
+            .collect();
+        follow.entry(self.start_symbol.clone()).or_default().insert(self.end_marker());
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for production in self.productions.iter() {
+                for (position, symbol) in production.body.iter().enumerate() {
+                    if self.is_terminal(symbol) {
+                        continue;
+                    }
+                    let rest_first = self.first_of_with(&production.body[position + 1..], &first);
+                    let mut new_follow: BTreeSet<Symbol> = rest_first.iter()
+                        .filter(|terminal| *terminal != "ε")
+                        .cloned()
+                        .collect();
+                    if rest_first.contains("ε") {
+                        new_follow.extend(follow.get(&production.head).cloned().unwrap_or_default());
+                    }
+                    let symbol_follow = follow.entry(symbol.clone()).or_default();
+                    let size = symbol_follow.len();
+                    symbol_follow.extend(new_follow);
+                    changed |= symbol_follow.len() != size;
+                }
+            }
+        }
+        follow
+    }
+
+    /* Returns the nonterminals A such that A =>+ Aα, directly or through
+     * other nonterminals. A nonterminal B is a left corner of A if there is a
+     * production A -> βBγ with β nullable, so A is left recursive if it is
+     * reachable from itself following the left corners. */
+    pub fn left_recursive_nonterminals(&self) -> BTreeSet<Symbol> {
+        let nullable = self.nullable_nonterminals();
+        let mut left_corners: HashMap<&Symbol, BTreeSet<&Symbol>> = HashMap::new();
+        for production in self.productions.iter() {
+            for symbol in production.body.iter() {
+                if self.is_nonterminal(symbol) {
+                    left_corners.entry(&production.head).or_default().insert(symbol);
+                }
+                if !nullable.contains(symbol) {
+                    break;
+                }
+            }
+        }
+        let mut left_recursive = BTreeSet::new();
+        for nonterminal in self.nonterminals.iter() {
+            let mut visited: BTreeSet<&Symbol> = BTreeSet::new();
+            let mut stack: Vec<&Symbol> = left_corners.get(nonterminal).map_or(Vec::new(), |c| c.iter().copied().collect());
+            while let Some(symbol) = stack.pop() {
+                if symbol == nonterminal {
+                    left_recursive.insert(nonterminal.clone());
+                    break;
+                }
+                if visited.insert(symbol) {
+                    stack.extend(left_corners.get(symbol).into_iter().flatten());
+                }
+            }
+        }
+        left_recursive
+    }
+
+    pub fn has_left_recursion(&self) -> bool {
+        !self.left_recursive_nonterminals().is_empty()
+    }
+
+    /* Function that returns an equivalent grammar without left recursion,
+     * using the usual algorithm: the nonterminals are ordered A1, ..., An and
+     * for every Ai the productions Ai -> Aj γ with j < i are replaced by the
+     * bodies of Aj followed by γ, then the immediate left recursion of Ai is
+     * removed with a new nonterminal Ai'. The algorithm needs a grammar
+     * without ε-productions nor cycles A =>+ A, so if the grammar has
+     * ε-productions they are removed first, together with the useless symbols
+     * left, and if it has cycles the unit productions are removed. */
+    pub fn eliminate_left_recursion(&self) -> Grammar {
+        let mut grammar = self.clone();
+        if grammar.productions.iter().any(|production| production.body.is_empty()) {
+            grammar = grammar.remove_epsilon_productions().remove_useless_symbols();
+        }
+        if grammar.has_unit_cycles() {
+            grammar = grammar.remove_unit_productions();
+        }
+        grammar.eliminate_left_recursion_with(true)
+    }
+
+    // Auxiliar function for the removal of left recursion, the new nonterminals
+    // A' have an ε-production only if with_epsilon is true.
+    fn eliminate_left_recursion_with(&self, with_epsilon: bool) -> Grammar {
+        let mut grammar = self.clone();
+        let order = self.nonterminals.clone();
+        for (index, nonterminal) in order.iter().enumerate() {
+            for previous in order[..index].iter() {
+                grammar = grammar.substitute_leading_nonterminal(nonterminal, previous);
+            }
+            grammar = grammar.eliminate_immediate_left_recursion(nonterminal, with_epsilon);
+        }
+        grammar
+    }
+
+    // Auxiliar function that replaces every production A -> Bγ of the nonterminal A
+    // by the productions A -> βγ for every production B -> β.
+    fn substitute_leading_nonterminal(&self, nonterminal: &str, leading: &str) -> Grammar {
+        let mut grammar = self.clone();
+        let mut productions = Vec::new();
+        for production in self.productions.iter() {
+            if production.head != nonterminal || production.body.first().map(|s| s.as_str()) != Some(leading) {
+                productions.push(production.clone());
+                continue;
+            }
+            for leading_production in self.productions_of(leading) {
+                let mut body = leading_production.body.clone();
+                body.extend(production.body[1..].iter().cloned());
+                let new_production = Production::new(nonterminal, body);
+                if !productions.contains(&new_production) {
+                    productions.push(new_production);
+                }
+            }
+        }
+        grammar.productions = productions;
+        grammar
+    }
+
+    /* Function that removes the productions A -> Aα of a nonterminal A,
+     * replacing the productions A -> α1 | ... | αn | β1 | ... | βm by
+     *     A -> β1A' | ... | βmA'
+     *     A' -> α1A' | ... | αnA' | ε
+     * or, without ε-productions, by
+     *     A -> β1 | ... | βm | β1A' | ... | βmA'
+     *     A' -> α1 | ... | αn | α1A' | ... | αnA'
+     * The productions A -> A are omitted because they are useless. */
+    fn eliminate_immediate_left_recursion(&self, nonterminal: &str, with_epsilon: bool) -> Grammar {
+        let recursive: Vec<&Production> = self.productions_of(nonterminal)
+            .filter(|production| production.body.first().map(|s| s.as_str()) == Some(nonterminal))
+            .collect();
+        if recursive.is_empty() {
+            return self.clone();
+        }
+        let new_nonterminal = self.fresh_nonterminal(&format!("{}'", nonterminal));
+        let mut new_productions: Vec<Production> = Vec::new();
+        for production in self.productions_of(nonterminal).filter(|p| !recursive.contains(p)) {
+            if !with_epsilon {
+                new_productions.push(production.clone());
+            }
+            let mut body = production.body.clone();
+            body.push(new_nonterminal.clone());
+            new_productions.push(Production::new(nonterminal, body));
+        }
+        let mut recursive_productions: Vec<Production> = Vec::new();
+        for production in recursive.iter().filter(|production| production.body.len() > 1) {
+            if !with_epsilon {
+                recursive_productions.push(Production::new(&new_nonterminal, production.body[1..].to_vec()));
+            }
+            let mut body = production.body[1..].to_vec();
+            body.push(new_nonterminal.clone());
+            recursive_productions.push(Production::new(&new_nonterminal, body));
+        }
+        if with_epsilon {
+            recursive_productions.push(Production::new(&new_nonterminal, Vec::new()));
+        }
+        self.replace_productions(nonterminal, new_productions, &new_nonterminal, recursive_productions)
+    }
+
+    /* Function that returns an equivalent grammar left factored, i.e. the
+     * alternatives of a nonterminal don't begin with the same symbol. While
+     * some alternatives A -> αβ1 | ... | αβn share the longest common prefix
+     * α, they are replaced by A -> αA' and A' -> β1 | ... | βn. */
+    pub fn left_factor(&self) -> Grammar {
+        let mut grammar = self.clone();
+        let mut pending: VecDeque<Symbol> = self.nonterminals.iter().cloned().collect();
+        while let Some(nonterminal) = pending.pop_front() {
+            let bodies: Vec<&Vec<Symbol>> = grammar.productions_of(&nonterminal).map(|p| &p.body).collect();
+            let group: Option<Vec<Vec<Symbol>>> = bodies.iter()
+                .filter_map(|body| body.first())
+                .map(|first| bodies.iter()
+                    .filter(|body| body.first() == Some(first))
+                    .map(|body| (*body).clone())
+                    .collect::<Vec<_>>())
+                .find(|group| group.len() > 1);
+            let group = match group {
+                Some(group) => group,
+                None => continue,
+            };
+            let prefix_length = (1..=group[0].len())
+                .take_while(|length| group.iter().all(|body| body.len() >= *length && body[..*length] == group[0][..*length]))
+                .last()
+                .unwrap_or(1);
+            let prefix = group[0][..prefix_length].to_vec();
+            let new_nonterminal = grammar.fresh_nonterminal(&format!("{}'", nonterminal));
+            let mut new_productions: Vec<Production> = Vec::new();
+            for production in grammar.productions_of(&nonterminal) {
+                if group.contains(&production.body) {
+                    if production.body == group[0] {
+                        let mut body = prefix.clone();
+                        body.push(new_nonterminal.clone());
+                        new_productions.push(Production::new(&nonterminal, body));
+                    }
+                } else {
+                    new_productions.push(production.clone());
+                }
+            }
+            let factored_productions: Vec<Production> = group.iter()
+                .map(|body| Production::new(&new_nonterminal, body[prefix_length..].to_vec()))
+                .collect();
+            grammar = grammar.replace_productions(&nonterminal, new_productions, &new_nonterminal, factored_productions);
+            pending.push_front(new_nonterminal);
+            pending.push_front(nonterminal);
+        }
+        grammar
+    }
+
+    /* Function that returns all the analyses of the grammar in a report: the
+     * nullable, generating, reachable, useless and left recursive nonterminals,
+     * and the grammars without useless symbols, without left recursion and
+     * left factored. The last two start from the grammar without useless
+     * symbols. */
+    pub fn report(&self) -> GrammarReport {
+        let generating: BTreeSet<Symbol> = self.generating_symbols().into_iter()
+            .filter(|symbol| self.is_nonterminal(symbol))
+            .collect();
+        let reachable: BTreeSet<Symbol> = self.reachable_symbols().into_iter()
+            .filter(|symbol| self.is_nonterminal(symbol))
+            .collect();
+        let useless: BTreeSet<Symbol> = self.nonterminals.iter()
+            .filter(|nonterminal| !generating.contains(*nonterminal) || !reachable.contains(*nonterminal))
+            .cloned()
+            .collect();
+        let without_useless_symbols = self.remove_useless_symbols();
+        GrammarReport {
+            nullable: self.nullable_nonterminals(),
+            generating,
+            reachable,
+            useless,
+            left_recursive: self.left_recursive_nonterminals(),
+            without_left_recursion: without_useless_symbols.eliminate_left_recursion(),
+            left_factored: without_useless_symbols.left_factor(),
+            without_useless_symbols,
+        }
+    }
+
+    /* Auxiliar function that replaces the productions of a nonterminal and
+     * adds a new nonterminal right after it with its productions. The new
+     * productions of the nonterminal take the place of the old ones. */
+    fn replace_productions(&self, nonterminal: &str, productions: Vec<Production>,
+                           new_nonterminal: &str, new_productions: Vec<Production>) -> Grammar {
+        let mut grammar = self.clone();
+        let position = grammar.productions.iter().position(|p| p.head == nonterminal).unwrap_or(grammar.productions.len());
+        grammar.productions.retain(|production| production.head != nonterminal);
+        let position = position.min(grammar.productions.len());
+        grammar.productions.splice(position..position, productions);
+        let index = grammar.nonterminals.iter().position(|n| n == nonterminal).map_or(grammar.nonterminals.len(), |i| i + 1);
+        grammar.nonterminals.insert(index, new_nonterminal.to_string());
+        for production in new_productions {
+            grammar.add_production(new_nonterminal, production.body);
+        }
+        grammar
+    }
+
+    /* Function to split a sentential form written as text in its symbols,
+     * e.g. "aSb" or "a S0 b". */
+    pub fn parse_sentential_form(&self, text: &str) -> Vec<Symbol> {
+        split_grammar_symbols(text)
+    }
+
+    /* Function to split a derivation written as text, e.g. "S => aSb => ab",
+     * in its sentential forms. The arrow can also be written as ⇒. */
+    pub fn parse_derivation(&self, text: &str) -> Vec<Vec<Symbol>> {
+        text.split("=>")
+            .flat_map(|part| part.split('⇒'))
+            .map(|form| self.parse_sentential_form(form))
+            .collect()
+    }
+
+    /* Function to check if a sequence of sentential forms is a derivation of
+     * the grammar, i.e. it starts with the start symbol and every form is
+     * obtained from the previous one replacing one nonterminal by the body of
+     * one of its productions. If leftmost is true, the replaced nonterminal
+     * must be the leftmost one. */
+    pub fn check_derivation(&self, steps: &[Vec<Symbol>], leftmost: bool) -> bool {
+        match steps.first() {
+            Some(first) if *first == vec![self.start_symbol.clone()] => (),
+            _ => return false,
+        }
+        steps.windows(2).all(|pair| self.derives_in_one_step(&pair[0], &pair[1], leftmost))
+    }
+
+    // Auxiliar function that checks if the form "to" is obtained from "from" in one step.
+    fn derives_in_one_step(&self, from: &[Symbol], to: &[Symbol], leftmost: bool) -> bool {
+        for (position, symbol) in from.iter().enumerate() {
+            if !self.is_nonterminal(symbol) {
+                continue;
+            }
+            for production in self.productions_of(symbol) {
+                if apply_production(from, position, production) == to {
+                    return true;
+                }
+            }
+            if leftmost {
+                return false;
+            }
+        }
+        false
+    }
+
+    /* Function that searches a leftmost derivation of a word, returning the
+     * sentential forms of the derivation, or NotDerivable if the word is not
+     * generated. The sentential forms are explored breadth first, the ones
+     * whose terminal prefix is not a prefix of the word or that have more
+     * terminals than the word are discarded. Because of the ε-productions
+     * the forms can grow without adding terminals, so the forms with more
+     * nullable nonterminals than (|w| + 1) times the longest body are also
+     * discarded, and the search gives up after MAX_SENTENTIAL_FORMS, returning
+     * Truncated. */
+    pub fn leftmost_derivation(&self, word: &str) -> DerivationSearch {
+        let nullable = self.nullable_nonterminals();
+        let longest_body = self.productions.iter().map(|p| p.body.len()).max().unwrap_or(0);
+        let nullable_limit = (word.len() + 1) * longest_body.max(1);
+        let start = vec![self.start_symbol.clone()];
+        let mut forms: Vec<(Vec<Symbol>, Option<usize>)> = vec![(start.clone(), None)];
+        let mut visited: HashSet<Vec<Symbol>> = HashSet::new();
+        visited.insert(start);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(0);
+        let mut truncated = false;
+        while let Some(index) = queue.pop_front() {
+            let form = forms[index].0.clone();
+            let position = form.iter().position(|symbol| self.is_nonterminal(symbol));
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    if form.concat() == word {
+                        let mut derivation = Vec::new();
+                        let mut current = Some(index);
+                        while let Some(current_index) = current {
+                            derivation.push(forms[current_index].0.clone());
+                            current = forms[current_index].1;
+                        }
+                        derivation.reverse();
+                        return DerivationSearch::Found(derivation);
+                    }
+                    continue;
+                }
+            };
+            for production in self.productions_of(&form[position]) {
+                let next = apply_production(&form, position, production);
+                if visited.contains(&next)
+                    || !self.can_derive_word(&next, word, &nullable)
+                    || next.iter().filter(|symbol| nullable.contains(*symbol)).count() > nullable_limit {
+                    continue;
+                }
+                if visited.len() >= MAX_SENTENTIAL_FORMS {
+                    truncated = true;
+                    continue;
+                }
+                visited.insert(next.clone());
+                forms.push((next, Some(index)));
+                queue.push_back(forms.len() - 1);
+            }
+        }
+        if truncated { DerivationSearch::Truncated } else { DerivationSearch::NotDerivable }
+    }
+
+    // Auxiliar function that discards the sentential forms that can't derive the word.
+    fn can_derive_word(&self, form: &[Symbol], word: &str, nullable: &BTreeSet<Symbol>) -> bool {
+        let prefix: String = form.iter()
+            .take_while(|symbol| !self.is_nonterminal(symbol))
+            .map(|symbol| symbol.as_str())
+            .collect();
+        if !word.starts_with(&prefix) {
+            return false;
+        }
+        // Every terminal and every nonterminal that is not nullable adds at least one character.
+        let minimum_length: usize = form.iter()
+            .map(|symbol| if self.is_nonterminal(symbol) {
+                if nullable.contains(symbol) { 0 } else { 1 }
+            } else {
+                symbol.len()
+            })
+            .sum();
+        minimum_length <= word.len()
+    }
+
+    /* Function that builds a pushdown automaton that accepts the language of
+     * the grammar by simulating its leftmost derivations. It has three states:
+     * q0 pushes the start symbol over the initial stack symbol, q1 replaces the
+     * nonterminal on top of the stack by the body of one of its productions,
+     * or reads a terminal that matches the top of the stack, and q2 is the
+     * final state, reached when only the initial stack symbol is left.
+     * The symbols that are longer than one character are written between
+     * brackets in the stack, so they can be read back as a single symbol. */
+    pub fn to_pushdown_automata(&self) -> PushdownAutomata {
+        let symbols: BTreeSet<Symbol> = self.nonterminals.iter().cloned()
+            .chain(self.get_terminals())
+            .map(|symbol| stack_symbol(&symbol))
+            .collect();
+        let initial_stack_symbol = ["Z", "$", "#", "⊥"].iter()
+            .find(|symbol| !symbols.contains(**symbol))
+            .map_or("[Z]".to_string(), |symbol| symbol.to_string());
+        let mut pushdown_automaton = PushdownAutomata::new(initial_stack_symbol.clone());
+        pushdown_automaton.add_n_states(3);
+        pushdown_automaton.make_initial(0);
+        pushdown_automaton.make_final(2);
+        pushdown_automaton.add_transition(0, 1, format!("ε;{}/{}{}", initial_stack_symbol,
+            stack_symbol(&self.start_symbol), initial_stack_symbol));
+        for production in self.productions.iter() {
+            let body: String = production.body.iter().map(|symbol| stack_symbol(symbol)).collect();
+            let body = if body.is_empty() { "ε".to_string() } else { body };
+            pushdown_automaton.add_transition(1, 1, format!("ε;{}/{}", stack_symbol(&production.head), body));
+        }
+        for terminal in self.get_terminals() {
+            pushdown_automaton.add_transition(1, 1, format!("{};{}/ε", terminal, stack_symbol(&terminal)));
+        }
+        pushdown_automaton.add_transition(1, 2, format!("ε;{}/{}", initial_stack_symbol, initial_stack_symbol));
+        pushdown_automaton
+    }
+
+    /* Function that builds a pushdown automaton with a single state that
+     * accepts by empty stack the language of a grammar in Greibach normal
+     * form. The stack starts with the start symbol and every production
+     * A -> aB1...Bk is the transition a;A/B1...Bk, so the automaton reads a
+     * symbol in every move, except ε;S/ε when the grammar generates ε.
+     * Returns None if the grammar is not in Greibach normal form. */
+    pub fn to_single_state_pushdown_automata(&self) -> Option<PushdownAutomata> {
+        if !self.is_greibach_normal_form() {
+            return None;
+        }
+        let mut pushdown_automaton = PushdownAutomata::new(stack_symbol(&self.start_symbol));
+        pushdown_automaton.set_empty_stack_acceptance(true);
+        pushdown_automaton.add_n_states(1);
+        pushdown_automaton.make_initial(0);
+        for production in self.productions.iter() {
+            let (input, rest) = match production.body.split_first() {
+                Some((first, rest)) => (first.as_str(), rest),
+                None => ("ε", &[][..]),
+            };
+            let push: String = rest.iter().map(|symbol| stack_symbol(symbol)).collect();
+            let push = if push.is_empty() { "ε".to_string() } else { push };
+            pushdown_automaton.add_transition(0, 0, format!("{};{}/{}", input, stack_symbol(&production.head), push));
+        }
+        Some(pushdown_automaton)
+    }
+
+    /* Returns true if every production is of the form A -> wB or A -> w,
+     * where w is a string of terminals. */
+    pub fn is_right_linear(&self) -> bool {
+        self.productions.iter().all(|production| production.body.iter()
+            .rev()
+            .skip(1)
+            .all(|symbol| self.is_terminal(symbol)))
+    }
+
+    /* Returns true if every production is of the form A -> Bw or A -> w,
+     * where w is a string of terminals. */
+    pub fn is_left_linear(&self) -> bool {
+        self.productions.iter().all(|production| production.body.iter()
+            .skip(1)
+            .all(|symbol| self.is_terminal(symbol)))
+    }
+
+    /* Function that builds a finite automaton that accepts the language of
+     * a right-linear or left-linear grammar, or None if the grammar is not
+     * linear. The states are named after the nonterminals:
+     *  - Right-linear: the initial state is the start symbol, a production
+     *    A -> a1...ak B is a path from A to B reading a1...ak, a production
+     *    A -> w is a path from A to a new final state qf, and A -> ε makes
+     *    A final.
+     *  - Left-linear: a new initial state qs is added and the start symbol
+     *    is the final state, a production A -> B a1...ak is a path from B
+     *    to A, and A -> w is a path from qs to A.
+     * The paths with more than one terminal have new states in the middle,
+     * named after the head, so every transition reads one terminal. */
+    pub fn to_finite_automata(&self) -> Option<FiniteAutomata> {
+        let right_linear = self.is_right_linear();
+        if !right_linear && !self.is_left_linear() {
+            return None;
+        }
+        let mut finite_automaton = FiniteAutomata::new();
+        let mut names: Vec<Symbol> = self.nonterminals.clone();
+        for (id, nonterminal) in self.nonterminals.iter().enumerate() {
+            finite_automaton.add_state_with_id_label(id as u64, nonterminal);
+        }
+        let state_of = |symbol: &str| self.nonterminals.iter().position(|n| n == symbol).map(|id| id as u64);
+        let start_id = state_of(&self.start_symbol).unwrap_or(0);
+        // The state qf in the right-linear grammars and qs in the left-linear ones.
+        let needs_extra = !right_linear || self.productions.iter()
+            .any(|production| !production.body.is_empty() && production.body.iter().all(|symbol| self.is_terminal(symbol)));
+        let extra_id = names.len() as u64;
+        if needs_extra {
+            let extra_name = fresh_symbol(if right_linear { "qf" } else { "qs" }, |name| names.iter().any(|n| n == name));
+            finite_automaton.add_state_with_id_label(extra_id, &extra_name);
+            names.push(extra_name);
+        }
+        if right_linear {
+            finite_automaton.make_initial(start_id);
+            if needs_extra {
+                finite_automaton.make_final(extra_id);
+            }
+        } else {
+            finite_automaton.make_initial(extra_id);
+            finite_automaton.make_final(start_id);
+        }
+        for production in self.productions.iter() {
+            let head_id = state_of(&production.head).unwrap_or(start_id);
+            let (terminals, nonterminal_id) = match production.body.iter().position(|symbol| self.is_nonterminal(symbol)) {
+                Some(position) => {
+                    let terminals = if right_linear { &production.body[..position] } else { &production.body[position + 1..] };
+                    (terminals, state_of(&production.body[position]))
+                }
+                None => (&production.body[..], None),
+            };
+            let (from, to) = match (right_linear, nonterminal_id) {
+                (true, Some(id)) => (head_id, id),
+                (true, None) if terminals.is_empty() => {
+                    finite_automaton.make_final(head_id);
+                    continue;
+                }
+                (true, None) => (head_id, extra_id),
+                (false, Some(id)) => (id, head_id),
+                (false, None) => (extra_id, head_id),
+            };
+            if terminals.is_empty() {
+                finite_automaton.add_transition(from, to, "ε".to_string());
+                continue;
+            }
+            let mut current = from;
+            for (index, terminal) in terminals.iter().enumerate() {
+                let next = if index + 1 == terminals.len() {
+                    to
+                } else {
+                    let name = fresh_symbol(&format!("{}{}", production.head, index + 1), |name| names.iter().any(|n| n == name));
+                    let id = names.len() as u64;
+                    finite_automaton.add_state_with_id_label(id, &name);
+                    names.push(name);
+                    id
+                };
+                finite_automaton.add_transition(current, next, terminal.clone());
+                current = next;
+            }
+        }
+        Some(finite_automaton)
+    }
+
+    /* Returns the productions grouped by head, with the heads in the order
+     * of the nonterminals. Used to print the grammar. */
+    pub fn productions_by_head(&self) -> Vec<(Symbol, Vec<&Production>)> {
+        let mut groups: HashMap<&Symbol, Vec<&Production>> = HashMap::new();
+        for production in self.productions.iter() {
+            groups.entry(&production.head).or_default().push(production);
+        }
+        let mut heads: Vec<&Symbol> = vec![&self.start_symbol];
+        heads.extend(self.nonterminals.iter().filter(|n| **n != self.start_symbol));
+        heads.into_iter()
+            .filter_map(|head| groups.remove(head).map(|productions| (head.clone(), productions)))
+            .collect()
+    }
+}
+
+/* The grammar is printed in the same notation that Grammar::parse reads,
+ * one line per nonterminal starting with the start symbol. */
+impl fmt::Display for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let separate = !self.nonterminals.iter()
+            .chain(self.get_terminals().iter())
+            .all(|symbol| is_compact_symbol(symbol));
+        for (head, productions) in self.productions_by_head() {
+            let bodies: Vec<String> = productions.iter()
+                .map(|production| if separate {
+                    if production.body.is_empty() { "ε".to_string() } else { production.body.join(" ") }
+                } else {
+                    format_symbols(&production.body)
+                })
+                .collect();
+            writeln!(f, "{} -> {}", head, bodies.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+/* Function to write a sentential form as text. The symbols are separated by
+ * spaces only if one of them is longer than one character, and the empty
+ * form is written as ε. */
+pub fn format_symbols(symbols: &[Symbol]) -> String {
+    if symbols.is_empty() {
+        return "ε".to_string();
+    }
+    if symbols.iter().all(|symbol| is_compact_symbol(symbol)) {
+        symbols.concat()
+    } else {
+        symbols.join(" ")
+    }
+}
+
+/* Function to write a table as plain text with its columns aligned and
+ * separated by |, the first row is usually the header. Used to print the
+ * parse tables. */
+pub fn format_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| rows.iter()
+            .map(|row| row.get(column).map_or(0, |cell| cell.chars().count()))
+            .max()
+            .unwrap_or(0))
+        .collect();
+    let mut table = String::new();
+    for row in rows {
+        let cells: Vec<String> = row.iter().zip(widths.iter())
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        table.push_str(cells.join(" | ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/* Function to write a grammar symbol as a stack symbol of a pushdown
+ * automaton, the symbols that can't be read back alone, that open a group
+ * without closing it or that have the separators ; or / of the transition
+ * labels are written between brackets, e.g. S0 is written as [S0], / as [/]
+ * and [ as [[]. */
+pub fn stack_symbol(symbol: &str) -> String {
+    let unclosed = (symbol.starts_with('[') && !symbol.ends_with(']'))
+        || (symbol.starts_with('<') && !symbol.ends_with('>'));
+    if split_stack_symbols(symbol) == [symbol] && !unclosed && !symbol.contains([';', '/']) {
+        symbol.to_string()
+    } else {
+        format!("[{}]", symbol)
+    }
+}
+
+// Returns true if the symbol can be written next to other symbols and read back alone.
+fn is_compact_symbol(symbol: &str) -> bool {
+    split_grammar_symbols(symbol) == [symbol]
+}
+
+/* Function that splits a sentential form in its symbols. If the string has
+ * whitespace the symbols are the words, otherwise every character is a
+ * symbol, except for the groups between angle brackets like <a> and the ones
+ * followed by apostrophes like A'. Unlike split_stack_symbols, [ and ] are
+ * symbols, so [S]S has the symbols [, S, ] and S. The symbol ε is omitted. */
+fn split_grammar_symbols(string: &str) -> Vec<Symbol> {
+    if string.split_whitespace().count() > 1 {
+        return string.split_whitespace()
+            .filter(|word| *word != "ε")
+            .map(|word| word.to_string())
+            .collect();
+    }
+    let mut symbols = Vec::new();
+    let mut chars = string.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut symbol = c.to_string();
+        if c == '<' {
+            for next in chars.by_ref() {
+                symbol.push(next);
+                if next == '>' {
+                    break;
+                }
+            }
+        }
+        while let Some('\'') = chars.peek() {
+            symbol.push('\'');
+            chars.next();
+        }
+        if symbol != "ε" {
+            symbols.push(symbol);
+        }
+    }
+    symbols
+}
+
+/* Returns the name if it is not used, otherwise it is changed increasing the
+ * number before the closing > or adding apostrophes, e.g. <A1> becomes <A2>
+ * and S' becomes S''. */
+fn fresh_symbol(name: &str, is_used: impl Fn(&str) -> bool) -> Symbol {
+    if let Some(base) = name.strip_suffix("1>") {
+        let mut number = 1;
+        while is_used(&format!("{}{}>", base, number)) {
+            number += 1;
+        }
+        return format!("{}{}>", base, number);
+    }
+    let mut name = name.to_string();
+    while is_used(&name) {
+        name.push('\'');
+    }
+    name
+}
+
+// Returns the form obtained by replacing the symbol in the position by the body of the production.
+fn apply_production(form: &[Symbol], position: usize, production: &Production) -> Vec<Symbol> {
+    let mut next = form[..position].to_vec();
+    next.extend(production.body.iter().cloned());
+    next.extend(form[position + 1..].iter().cloned());
+    next
+}
+
+// Returns true if a symbol is written as a nonterminal, i.e. A, A', [qAp] or <expr>.
+fn looks_like_nonterminal(symbol: &str) -> bool {
+    match symbol.chars().next() {
+        Some(c) if c.is_uppercase() => true,
+        Some('[') => symbol.len() > 2 && symbol.ends_with(']'),
+        Some('<') => symbol.len() > 2 && symbol.ends_with('>'),
+        _ => false,
+    }
+}
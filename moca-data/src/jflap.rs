@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use crate::machine_file::{MachineFile, MachineFileError, MachineFileKind, Position, StateEntry, TransitionEntry, FORMAT_VERSION};
+use crate::mealy_machine;
+use crate::pushdown_automata;
+use crate::turing_machine;
+
+/* Blank symbol of the Turing machines and initial stack symbol of the
+ * pushdown automata read from JFLAP files, the ones JFLAP uses. */
+const JFLAP_BLANK_SYMBOL: &str = "_";
+const JFLAP_INITIAL_STACK_SYMBOL: &str = "Z";
+
+// An element of an XML document, with its text and the elements inside it.
+struct XmlElement {
+    name: String,
+    attributes: HashMap<String, String>,
+    text: String,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn has_child(&self, name: &str) -> bool {
+        self.child(name).is_some()
+    }
+
+    // Text of a child, the empty string if there is no child with that name.
+    fn child_text(&self, name: &str) -> &str {
+        self.child(name).map_or("", |child| child.text.trim())
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+/* Function that reads a JFLAP file (.jff) as a machine file. The finite
+ * automata, pushdown automata, Turing machines with one tape, Mealy machines
+ * and Moore machines are supported. The coordinates of the states are their
+ * positions, and the empty labels, which JFLAP shows as λ, are read as ε. In
+ * a Turing machine an empty symbol is the blank symbol _, and the initial
+ * stack symbol of a pushdown automaton is Z like in JFLAP. */
+pub fn read_jff(text: &str) -> Result<MachineFile, MachineFileError> {
+    let structure = parse_xml(text)?;
+    if structure.name != "structure" {
+        return Err(jflap_error(format!("expected a structure element, found {}", structure.name)));
+    }
+    let kind = match structure.child_text("type") {
+        "fa" => MachineFileKind::Finite,
+        "pda" => MachineFileKind::Pushdown,
+        "turing" => MachineFileKind::Turing,
+        "mealy" => MachineFileKind::Mealy,
+        "moore" => MachineFileKind::Moore,
+        other => return Err(jflap_error(format!("the JFLAP type {} is not supported", other))),
+    };
+    if structure.child_text("tapes").parse::<usize>().is_ok_and(|tapes| tapes > 1) {
+        return Err(jflap_error("only Turing machines with one tape are supported".to_string()));
+    }
+    // JFLAP 7 keeps the states in an automaton element, older versions in the structure.
+    let automaton = structure.child("automaton").unwrap_or(&structure);
+    if automaton.has_child("block") {
+        return Err(jflap_error("Turing machines with building blocks are not supported".to_string()));
+    }
+
+    let mut states = Vec::new();
+    for state in automaton.children_named("state") {
+        let id = state.attributes.get("id")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(|| jflap_error("a state does not have a numeric id".to_string()))?;
+        let name = state.attributes.get("name").cloned().unwrap_or_else(|| format!("q{}", id));
+        let position = match (state.child_text("x").parse::<f32>(), state.child_text("y").parse::<f32>()) {
+            (Ok(x), Ok(y)) => Some(Position { x, y }),
+            _ => None,
+        };
+        let output = match kind {
+            MachineFileKind::Moore => Some(state.child_text("output").to_string()),
+            _ => None,
+        };
+        states.push(StateEntry {
+            id,
+            name,
+            label: Vec::new(),
+            position,
+            initial: state.has_child("initial"),
+            is_final: state.has_child("final"),
+            output,
+        });
+    }
+
+    let mut transitions = Vec::new();
+    for transition in automaton.children_named("transition") {
+        let from = transition.child_text("from").parse()
+            .map_err(|_| jflap_error("a transition does not have a numeric from state".to_string()))?;
+        let to = transition.child_text("to").parse()
+            .map_err(|_| jflap_error("a transition does not have a numeric to state".to_string()))?;
+        let read = transition.child_text("read");
+        let label = match kind {
+            MachineFileKind::Finite | MachineFileKind::Moore => epsilon_if_empty(read).to_string(),
+            MachineFileKind::Pushdown => format!(
+                "{};{}/{}",
+                epsilon_if_empty(read),
+                epsilon_if_empty(transition.child_text("pop")),
+                epsilon_if_empty(transition.child_text("push")),
+            ),
+            MachineFileKind::Turing => format!(
+                "{}/{},{}",
+                blank_if_empty(read),
+                blank_if_empty(transition.child_text("write")),
+                transition.child_text("move"),
+            ),
+            MachineFileKind::Mealy => format!("{}/{}", read, epsilon_if_empty(transition.child_text("transout"))),
+        };
+        transitions.push(TransitionEntry { from, to, label });
+    }
+
+    let file = MachineFile {
+        version: FORMAT_VERSION,
+        kind,
+        initial_stack_symbol: (kind == MachineFileKind::Pushdown).then(|| JFLAP_INITIAL_STACK_SYMBOL.to_string()),
+        empty_stack_acceptance: false,
+        blank_symbol: (kind == MachineFileKind::Turing).then(|| JFLAP_BLANK_SYMBOL.to_string()),
+        states,
+        transitions,
+    };
+    file.validate()?;
+    Ok(file)
+}
+
+/* Function that writes a machine file as a JFLAP file, the inverse of
+ * read_jff. The ε labels and the blank symbol are written as empty
+ * elements, and the states without a position are placed at the origin. The
+ * transitions whose label can't be read by the machine are left out. */
+pub fn write_jff(file: &MachineFile) -> String {
+    let mut writer = Writer::new_with_indent(Vec::new(), b'\t', 1);
+    write_structure(&mut writer, file).expect("a JFLAP file can always be written in memory");
+    String::from_utf8(writer.into_inner()).expect("the JFLAP file is written as UTF-8")
+}
+
+// Auxiliar function that writes the structure element of a JFLAP file.
+fn write_structure(writer: &mut Writer<Vec<u8>>, file: &MachineFile) -> std::io::Result<()> {
+    let blank_symbol = file.blank_symbol.as_deref().unwrap_or(JFLAP_BLANK_SYMBOL);
+    let type_name = match file.kind {
+        MachineFileKind::Finite => "fa",
+        MachineFileKind::Pushdown => "pda",
+        MachineFileKind::Turing => "turing",
+        MachineFileKind::Mealy => "mealy",
+        MachineFileKind::Moore => "moore",
+    };
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), Some("no"))))?;
+    writer.create_element("structure").write_inner_content(|writer| {
+        write_text_element(writer, "type", type_name)?;
+        writer.create_element("automaton").write_inner_content(|writer| {
+            for state in file.states.iter() {
+                writer.create_element("state")
+                    .with_attributes([("id", state.id.to_string().as_str()), ("name", state.name.as_str())])
+                    .write_inner_content(|writer| {
+                        let position = state.position.unwrap_or(Position { x: 0.0, y: 0.0 });
+                        write_text_element(writer, "x", &position.x.to_string())?;
+                        write_text_element(writer, "y", &position.y.to_string())?;
+                        if state.initial {
+                            writer.create_element("initial").write_empty()?;
+                        }
+                        if state.is_final {
+                            writer.create_element("final").write_empty()?;
+                        }
+                        if let Some(output) = &state.output {
+                            write_text_element(writer, "output", empty_if_epsilon(output))?;
+                        }
+                        Ok(())
+                    })?;
+            }
+            for transition in file.transitions.iter() {
+                let elements = match transition_elements(file.kind, &transition.label, blank_symbol) {
+                    Some(elements) => elements,
+                    None => continue,
+                };
+                writer.create_element("transition").write_inner_content(|writer| {
+                    write_text_element(writer, "from", &transition.from.to_string())?;
+                    write_text_element(writer, "to", &transition.to.to_string())?;
+                    for (name, text) in elements.iter() {
+                        write_text_element(writer, name, text)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// Auxiliar function that splits a transition label in the elements JFLAP writes for
+// it, or None if the label can't be read by a machine of that kind.
+fn transition_elements(kind: MachineFileKind, label: &str, blank_symbol: &str) -> Option<Vec<(&'static str, String)>> {
+    let empty_if_blank = |symbol: &str| if symbol == blank_symbol { String::new() } else { symbol.to_string() };
+    match kind {
+        MachineFileKind::Finite | MachineFileKind::Moore => Some(vec![("read", empty_if_epsilon(label).to_string())]),
+        MachineFileKind::Pushdown => {
+            let (input, pop, push) = pushdown_automata::parse_transition_label(label)?;
+            Some(vec![
+                ("read", empty_if_epsilon(&input).to_string()),
+                ("pop", empty_if_epsilon(&pop).to_string()),
+                ("push", empty_if_epsilon(&push).to_string()),
+            ])
+        }
+        MachineFileKind::Turing => {
+            let (read, write, tape_move) = turing_machine::parse_transition_label(label)?;
+            Some(vec![
+                ("read", empty_if_blank(&read)),
+                ("write", empty_if_blank(&write)),
+                ("move", tape_move.to_string()),
+            ])
+        }
+        MachineFileKind::Mealy => {
+            let (input, output) = mealy_machine::parse_transition_label(label)?;
+            Some(vec![("read", input), ("transout", output)])
+        }
+    }
+}
+
+// Writes an element with a text, or an empty element if the text is empty.
+fn write_text_element(writer: &mut Writer<Vec<u8>>, name: &str, text: &str) -> std::io::Result<()> {
+    if text.is_empty() {
+        writer.create_element(name).write_empty()?;
+    } else {
+        writer.create_element(name).write_text_content(BytesText::new(text))?;
+    }
+    Ok(())
+}
+
+/* Auxiliar function that reads an XML document as a tree of elements and
+ * returns its root element. Only comments and whitespace can follow it. */
+fn parse_xml(text: &str) -> Result<XmlElement, MachineFileError> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut stack: Vec<XmlElement> = Vec::new();
+    // The root element once it is closed, the rest of the document can only have comments.
+    let mut root: Option<XmlElement> = None;
+    loop {
+        let event = reader.read_event().map_err(|error| {
+            jflap_error(format!("invalid XML at position {}: {}", reader.error_position(), error))
+        })?;
+        let is_empty = matches!(event, Event::Empty(_));
+        if root.is_some() && matches!(event, Event::Start(_) | Event::Empty(_) | Event::Text(_) | Event::CData(_)) {
+            return Err(jflap_error("the document has content after its root element".to_string()));
+        }
+        match event {
+            Event::Start(start) | Event::Empty(start) => {
+                let mut attributes = HashMap::new();
+                for attribute in start.attributes() {
+                    let attribute = attribute.map_err(|error| jflap_error(format!("invalid XML attribute: {}", error)))?;
+                    let value = attribute.unescape_value().map_err(|error| jflap_error(error.to_string()))?;
+                    attributes.insert(String::from_utf8_lossy(attribute.key.as_ref()).to_string(), value.to_string());
+                }
+                let element = XmlElement {
+                    name: String::from_utf8_lossy(start.name().as_ref()).to_string(),
+                    attributes,
+                    text: String::new(),
+                    children: Vec::new(),
+                };
+                if is_empty {
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => root = Some(element),
+                    }
+                } else {
+                    stack.push(element);
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.unescape().map_err(|error| jflap_error(error.to_string()))?);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop().expect("the reader checks that the end tags match");
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+            }
+            Event::Eof => return root.ok_or_else(|| jflap_error("the document ends before its root element is closed".to_string())),
+            _ => (),
+        }
+    }
+}
+
+fn epsilon_if_empty(symbol: &str) -> &str {
+    if symbol.is_empty() { "ε" } else { symbol }
+}
+
+fn empty_if_epsilon(symbol: &str) -> &str {
+    if symbol == "ε" { "" } else { symbol }
+}
+
+fn blank_if_empty(symbol: &str) -> &str {
+    if symbol.is_empty() { JFLAP_BLANK_SYMBOL } else { symbol }
+}
+
+fn jflap_error(message: String) -> MachineFileError {
+    MachineFileError { message: format!("invalid JFLAP file: {}", message) }
+}
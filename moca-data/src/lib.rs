@@ -1,8 +1,27 @@
 // Library that exposes the API for creating, modifying and applying algorithms to that automatas.
 
+pub mod ambiguity;
+pub mod automaton_text;
+pub mod cyk;
+pub mod dot;
+pub mod earley;
+pub mod file_format;
 pub mod finite_automata;
+pub mod generator;
+pub mod grammar;
+pub mod jflap;
+pub mod ll1;
+pub mod lr;
+pub mod machine_file;
+pub mod mealy_machine;
+pub mod moore_machine;
+pub mod multi_tape_turing_machine;
 pub mod pushdown_automata;
+pub mod regular_expression;
 pub mod state_machine;
 pub mod state;
+pub mod test_suite;
+pub mod tikz;
+pub mod turing_machine;
 
 
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use crate::grammar::{format_symbols, format_table, Grammar, Production, Symbol};
+
+/* Maximum number of steps of the predictive parser, a grammar that is not
+ * LL(1) can make the parser expand a left recursive nonterminal forever. */
+pub const MAX_PARSER_STEPS: usize = 10_000;
+
+/* Struct that represents a conflict of the LL(1) table, i.e. a cell
+ * M[nonterminal, terminal] with more than one production. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LL1Conflict {
+    pub nonterminal: Symbol,
+    pub terminal: Symbol,
+    pub productions: Vec<Production>,
+}
+
+impl fmt::Display for LL1Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let productions: Vec<String> = self.productions.iter().map(|production| production.to_string()).collect();
+        write!(f, "M[{}, {}]: {}", self.nonterminal, self.terminal, productions.join(", "))
+    }
+}
+
+/* The actions of the predictive parser in every step. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PredictiveAction {
+    Expand(Production),
+    Match(Symbol),
+    Accept,
+    Error(String),
+}
+
+impl fmt::Display for PredictiveAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PredictiveAction::Expand(production) => write!(f, "{}", production),
+            PredictiveAction::Match(terminal) => write!(f, "match {}", terminal),
+            PredictiveAction::Accept => write!(f, "accept"),
+            PredictiveAction::Error(message) => write!(f, "error: {}", message),
+        }
+    }
+}
+
+/* Struct that represents a configuration of the predictive parser and the
+ * action taken on it. The top of the stack is the last symbol, like in a
+ * PushdownConfiguration. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredictiveStep {
+    pub stack: Vec<Symbol>,
+    pub remaining_input: Vec<Symbol>,
+    pub action: PredictiveAction,
+}
+
+/* Structure that represents the LL(1) parse table of a grammar. The cell
+ * M[A, a] has the productions A -> α such that a is in FIRST(α), or ε is
+ * in FIRST(α) and a is in FOLLOW(A). The grammar is LL(1) if every cell
+ * has at most one production. */
+#[derive(Debug, Clone)]
+pub struct LL1Table {
+    grammar: Grammar,
+    first: BTreeMap<Symbol, BTreeSet<Symbol>>,
+    follow: BTreeMap<Symbol, BTreeSet<Symbol>>,
+    end_marker: Symbol,
+    cells: BTreeMap<(Symbol, Symbol), Vec<Production>>,
+}
+
+impl LL1Table {
+    pub fn new(grammar: &Grammar) -> Self {
+        let first = grammar.first_sets();
+        let follow = grammar.follow_sets();
+        let mut cells: BTreeMap<(Symbol, Symbol), Vec<Production>> = BTreeMap::new();
+        for production in grammar.get_productions().iter() {
+            let body_first = grammar.first_of_with(&production.body, &first);
+            let mut terminals: BTreeSet<&Symbol> = body_first.iter().filter(|terminal| *terminal != "ε").collect();
+            if body_first.contains("ε") {
+                if let Some(head_follow) = follow.get(&production.head) {
+                    terminals.extend(head_follow.iter());
+                }
+            }
+            for terminal in terminals {
+                cells.entry((production.head.clone(), terminal.clone()))
+                    .or_default()
+                    .push(production.clone());
+            }
+        }
+        LL1Table {
+            grammar: grammar.clone(),
+            first,
+            follow,
+            end_marker: grammar.end_marker(),
+            cells,
+        }
+    }
+
+    // Getter for the FIRST sets of the nonterminals.
+    pub fn get_first_sets(&self) -> &BTreeMap<Symbol, BTreeSet<Symbol>> {
+        &self.first
+    }
+
+    // Getter for the FOLLOW sets of the nonterminals.
+    pub fn get_follow_sets(&self) -> &BTreeMap<Symbol, BTreeSet<Symbol>> {
+        &self.follow
+    }
+
+    // Returns the productions of the cell M[nonterminal, terminal].
+    pub fn get_cell(&self, nonterminal: &str, terminal: &str) -> &[Production] {
+        self.cells.get(&(nonterminal.to_string(), terminal.to_string()))
+            .map_or(&[], |productions| productions.as_slice())
+    }
+
+    // Returns the columns of the table, the terminals and the end marker.
+    pub fn get_columns(&self) -> Vec<Symbol> {
+        let mut columns: Vec<Symbol> = self.grammar.get_terminals().into_iter().collect();
+        columns.push(self.end_marker.clone());
+        columns
+    }
+
+    // Returns the cells with more than one production, in the order of the rows.
+    pub fn get_conflicts(&self) -> Vec<LL1Conflict> {
+        let mut conflicts = Vec::new();
+        for nonterminal in self.grammar.get_nonterminals().iter() {
+            for terminal in self.get_columns() {
+                let productions = self.get_cell(nonterminal, &terminal);
+                if productions.len() > 1 {
+                    conflicts.push(LL1Conflict {
+                        nonterminal: nonterminal.clone(),
+                        terminal,
+                        productions: productions.to_vec(),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    pub fn is_ll1(&self) -> bool {
+        self.cells.values().all(|productions| productions.len() <= 1)
+    }
+
+    /* Function that runs the table-driven predictive parser on a word,
+     * returning every step. The stack starts with the end marker and the
+     * start symbol. If a nonterminal is on top, it is replaced by the body
+     * of the production of the cell for the next input symbol; if a terminal
+     * is on top, it must match the next input symbol. When a cell has a
+     * conflict the first production is used. Every terminal and nonterminal
+     * that is not nullable in the stack needs at least one input symbol, so
+     * the parser stops with an error when there are more of them than the
+     * remaining input, e.g. when it expands a left recursive nonterminal.
+     * The last action is Accept or Error. */
+    pub fn parse(&self, word: &[Symbol]) -> Vec<PredictiveStep> {
+        let mut steps = Vec::new();
+        let end_marker = &self.end_marker;
+        let mut stack = vec![end_marker.clone(), self.grammar.get_start_symbol().clone()];
+        let mut input: Vec<Symbol> = word.to_vec();
+        input.push(end_marker.clone());
+        let mut position = 0;
+        let nullable = self.grammar.nullable_nonterminals();
+        loop {
+            let lookahead = &input[position];
+            let top = stack.last().cloned().unwrap_or_default();
+            let needed = stack.iter()
+                .filter(|symbol| *symbol != end_marker && !nullable.contains(*symbol))
+                .count();
+            let action = if steps.len() == MAX_PARSER_STEPS {
+                PredictiveAction::Error("too many steps".to_string())
+            } else if needed > input.len() - position - 1 {
+                PredictiveAction::Error("the stack has more symbols than the remaining input".to_string())
+            } else if top == *end_marker && lookahead == end_marker {
+                PredictiveAction::Accept
+            } else if self.grammar.is_nonterminal(&top) {
+                match self.get_cell(&top, lookahead).first() {
+                    Some(production) => PredictiveAction::Expand(production.clone()),
+                    None => PredictiveAction::Error(format!("M[{}, {}] is empty", top, lookahead)),
+                }
+            } else if top == *lookahead {
+                PredictiveAction::Match(top.clone())
+            } else {
+                PredictiveAction::Error(format!("expected {} but found {}", top, lookahead))
+            };
+            steps.push(PredictiveStep {
+                stack: stack.clone(),
+                remaining_input: input[position..].to_vec(),
+                action: action.clone(),
+            });
+            match action {
+                PredictiveAction::Expand(production) => {
+                    stack.pop();
+                    stack.extend(production.body.iter().rev().cloned());
+                }
+                PredictiveAction::Match(_) => {
+                    stack.pop();
+                    position += 1;
+                }
+                PredictiveAction::Accept | PredictiveAction::Error(_) => return steps,
+            }
+        }
+    }
+
+    // Returns true if the parser accepts the word.
+    pub fn accepts(&self, word: &[Symbol]) -> bool {
+        self.parse(word).last().map(|step| step.action == PredictiveAction::Accept).unwrap_or(false)
+    }
+}
+
+/* The table is printed with one row per nonterminal and one column per
+ * terminal, the cells with a conflict have their productions separated by
+ * commas. The columns are aligned so it can be read as plain text. */
+impl fmt::Display for LL1Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self.get_columns();
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new()).chain(columns.iter().cloned()).collect()];
+        for nonterminal in self.grammar.get_nonterminals().iter() {
+            let mut row = vec![nonterminal.clone()];
+            for terminal in columns.iter() {
+                let productions: Vec<String> = self.get_cell(nonterminal, terminal).iter()
+                    .map(|production| format!("{} -> {}", production.head, format_symbols(&production.body)))
+                    .collect();
+                row.push(productions.join(", "));
+            }
+            rows.push(row);
+        }
+        write!(f, "{}", format_table(&rows))
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use crate::finite_automata::FiniteAutomata;
+use crate::grammar::{format_symbols, format_table, Grammar, Production, Symbol};
+use crate::state_machine::StateMachine;
+
+/* Struct that represents an LR(0) item, a production with a dot that
+ * separates the part of the body that is already on the stack. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LRItem {
+    pub production: Production,
+    pub dot: usize,
+}
+
+impl LRItem {
+    pub fn new(production: &Production, dot: usize) -> Self {
+        LRItem {
+            production: production.clone(),
+            dot,
+        }
+    }
+
+    // Returns the symbol after the dot, or None if the item is completed.
+    pub fn next_symbol(&self) -> Option<&Symbol> {
+        self.production.body.get(self.dot)
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.dot == self.production.body.len()
+    }
+
+    // Returns the item with the dot moved one symbol to the right.
+    fn advance(&self) -> LRItem {
+        LRItem::new(&self.production, self.dot + 1)
+    }
+}
+
+/* The item is printed as A -> a·Sb. */
+impl fmt::Display for LRItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (read, unread) = self.production.body.split_at(self.dot);
+        let write = |symbols: &[Symbol]| if symbols.is_empty() { String::new() } else { format_symbols(symbols) };
+        write!(f, "{} -> {}·{}", self.production.head, write(read), write(unread))
+    }
+}
+
+/* Structure that represents the canonical LR(0) automaton of a grammar.
+ * The grammar is augmented with a new start symbol S' -> S, and every
+ * state is the closure of a set of items, the state 0 is the closure of
+ * S' -> ·S. The transitions are labeled with the grammar symbols. */
+#[derive(Debug, Clone)]
+pub struct LR0Automaton {
+    grammar: Grammar,
+    states: Vec<Vec<LRItem>>,
+    transitions: BTreeMap<(usize, Symbol), usize>,
+}
+
+impl LR0Automaton {
+    /* The states are numbered in the order they are found, moving the dot
+     * over the symbols in the order they appear in the items of each state. */
+    pub fn new(grammar: &Grammar) -> Self {
+        let grammar = grammar.add_new_start_symbol();
+        let start_items: Vec<LRItem> = grammar.productions_of(grammar.get_start_symbol())
+            .map(|production| LRItem::new(production, 0))
+            .collect();
+        let mut states: Vec<Vec<LRItem>> = vec![closure(&grammar, start_items)];
+        // The states are compared as sets, the same items can be found in a different order.
+        let mut item_sets: HashMap<BTreeSet<LRItem>, usize> = HashMap::new();
+        item_sets.insert(states[0].iter().cloned().collect(), 0);
+        let mut transitions: BTreeMap<(usize, Symbol), usize> = BTreeMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(0);
+        while let Some(state) = queue.pop_front() {
+            let mut symbols: Vec<&Symbol> = Vec::new();
+            for item in states[state].iter() {
+                if let Some(symbol) = item.next_symbol() {
+                    if !symbols.contains(&symbol) {
+                        symbols.push(symbol);
+                    }
+                }
+            }
+            let mut new_transitions = Vec::new();
+            for symbol in symbols {
+                let kernel: Vec<LRItem> = states[state].iter()
+                    .filter(|item| item.next_symbol() == Some(symbol))
+                    .map(|item| item.advance())
+                    .collect();
+                new_transitions.push((symbol.clone(), closure(&grammar, kernel)));
+            }
+            for (symbol, items) in new_transitions {
+                let item_set: BTreeSet<LRItem> = items.iter().cloned().collect();
+                let target = match item_sets.get(&item_set) {
+                    Some(target) => *target,
+                    None => {
+                        states.push(items);
+                        item_sets.insert(item_set, states.len() - 1);
+                        queue.push_back(states.len() - 1);
+                        states.len() - 1
+                    }
+                };
+                transitions.insert((state, symbol), target);
+            }
+        }
+        LR0Automaton {
+            grammar,
+            states,
+            transitions,
+        }
+    }
+
+    // Getter for the augmented grammar of the automaton.
+    pub fn get_grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    // Getter for the item sets, the state i has the items of states[i].
+    pub fn get_states(&self) -> &Vec<Vec<LRItem>> {
+        &self.states
+    }
+
+    // Getter for the transitions, indexed by the state and the symbol.
+    pub fn get_transitions(&self) -> &BTreeMap<(usize, Symbol), usize> {
+        &self.transitions
+    }
+
+    pub fn get_transition(&self, state: usize, symbol: &str) -> Option<usize> {
+        self.transitions.get(&(state, symbol.to_string())).copied()
+    }
+
+    /* Function that returns the automaton as a finite automaton so it can
+     * be drawn, the state i is named Ii and the final states are the ones
+     * with a completed item, i.e. the states where the parser can reduce. */
+    pub fn to_finite_automata(&self) -> FiniteAutomata {
+        let mut finite_automaton = FiniteAutomata::new();
+        for (index, items) in self.states.iter().enumerate() {
+            finite_automaton.add_state_with_id_label(index as u64, &format!("I{}", index));
+            if items.iter().any(|item| item.is_completed()) {
+                finite_automaton.make_final(index as u64);
+            }
+        }
+        finite_automaton.make_initial(0);
+        for ((from, symbol), to) in self.transitions.iter() {
+            finite_automaton.add_transition(*from as u64, *to as u64, symbol.clone());
+        }
+        finite_automaton
+    }
+}
+
+/* The automaton is printed as the list of its item sets. */
+impl fmt::Display for LR0Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, items) in self.states.iter().enumerate() {
+            writeln!(f, "I{}:", index)?;
+            for item in items.iter() {
+                writeln!(f, "  {}", item)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/* The actions of the ACTION table of an LR parser. */
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LRAction {
+    Shift(usize),
+    Reduce(Production),
+    Accept,
+}
+
+impl fmt::Display for LRAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LRAction::Shift(state) => write!(f, "s{}", state),
+            LRAction::Reduce(production) => write!(f, "r {}", production),
+            LRAction::Accept => write!(f, "acc"),
+        }
+    }
+}
+
+/* Struct that represents a conflict of the ACTION table, i.e. a cell
+ * ACTION[state, symbol] with more than one action. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LRConflict {
+    pub state: usize,
+    pub symbol: Symbol,
+    pub actions: Vec<LRAction>,
+}
+
+impl LRConflict {
+    // Returns true if one of the actions is a shift, otherwise it is a reduce/reduce conflict.
+    pub fn is_shift_reduce(&self) -> bool {
+        self.actions.iter().any(|action| matches!(action, LRAction::Shift(_)))
+    }
+}
+
+impl fmt::Display for LRConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_shift_reduce() { "shift/reduce" } else { "reduce/reduce" };
+        let actions: Vec<String> = self.actions.iter().map(|action| action.to_string()).collect();
+        write!(f, "ACTION[{}, {}]: {} conflict between {}", self.state, self.symbol, kind, actions.join(" and "))
+    }
+}
+
+/* The kinds of LR tables that are built over the LR(0) automaton, they
+ * only differ in the lookaheads of the reductions. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LRTableKind {
+    Slr,
+    Lalr,
+}
+
+impl LRTableKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LRTableKind::Slr => "SLR(1)",
+            LRTableKind::Lalr => "LALR(1)",
+        }
+    }
+}
+
+/* Structure that represents the ACTION and GOTO tables of an LR parser.
+ * A cell of the ACTION table can have several actions, in that case the
+ * grammar is not of the kind of the table and the cell is a conflict. */
+#[derive(Debug, Clone)]
+pub struct LRTable {
+    kind: LRTableKind,
+    grammar: Grammar,
+    states: usize,
+    action: BTreeMap<(usize, Symbol), Vec<LRAction>>,
+    goto: BTreeMap<(usize, Symbol), usize>,
+}
+
+impl LRTable {
+    /* Function that builds the SLR(1) table, the completed items A -> α·
+     * reduce on the terminals of FOLLOW(A). */
+    pub fn slr(automaton: &LR0Automaton) -> Self {
+        let follow = automaton.grammar.follow_sets();
+        let lookaheads = automaton.states.iter()
+            .map(|items| items.iter()
+                .filter(|item| item.is_completed())
+                .map(|item| (item.clone(), follow.get(&item.production.head).cloned().unwrap_or_default()))
+                .collect())
+            .collect();
+        LRTable::with_lookaheads(automaton, LRTableKind::Slr, lookaheads)
+    }
+
+    /* Function that builds the LALR(1) table. The canonical LR(1) automaton
+     * is built and its states with the same items, without lookaheads, are
+     * merged, which gives the states of the LR(0) automaton with the
+     * lookaheads of every completed item. */
+    pub fn lalr(automaton: &LR0Automaton) -> Self {
+        let grammar = &automaton.grammar;
+        let first = grammar.first_sets();
+        let mut lookaheads: Vec<BTreeMap<LRItem, BTreeSet<Symbol>>> = vec![BTreeMap::new(); automaton.states.len()];
+        let start_items: BTreeSet<(LRItem, Symbol)> = grammar.productions_of(grammar.get_start_symbol())
+            .map(|production| (LRItem::new(production, 0), grammar.end_marker()))
+            .collect();
+        let mut states: Vec<(usize, BTreeSet<(LRItem, Symbol)>)> = vec![(0, lr1_closure(grammar, &first, start_items))];
+        // The LR(0) state of every LR(1) state that has been found.
+        let mut found: HashMap<BTreeSet<(LRItem, Symbol)>, usize> = HashMap::new();
+        found.insert(states[0].1.clone(), 0);
+        let mut index = 0;
+        while index < states.len() {
+            let (state, items) = states[index].clone();
+            index += 1;
+            for (item, lookahead) in items.iter().filter(|(item, _)| item.is_completed()) {
+                lookaheads[state].entry(item.clone()).or_default().insert(lookahead.clone());
+            }
+            for ((from, symbol), to) in automaton.transitions.range((state, String::new())..) {
+                if *from != state {
+                    break;
+                }
+                let kernel: BTreeSet<(LRItem, Symbol)> = items.iter()
+                    .filter(|(item, _)| item.next_symbol() == Some(symbol))
+                    .map(|(item, lookahead)| (item.advance(), lookahead.clone()))
+                    .collect();
+                let items = lr1_closure(grammar, &first, kernel);
+                if !found.contains_key(&items) {
+                    found.insert(items.clone(), *to);
+                    states.push((*to, items));
+                }
+            }
+        }
+        LRTable::with_lookaheads(automaton, LRTableKind::Lalr, lookaheads)
+    }
+
+    // Auxiliar function that builds the table given the lookaheads of the completed items.
+    fn with_lookaheads(automaton: &LR0Automaton, kind: LRTableKind,
+                       lookaheads: Vec<BTreeMap<LRItem, BTreeSet<Symbol>>>) -> Self {
+        let grammar = &automaton.grammar;
+        let mut action: BTreeMap<(usize, Symbol), Vec<LRAction>> = BTreeMap::new();
+        let mut goto: BTreeMap<(usize, Symbol), usize> = BTreeMap::new();
+        for ((state, symbol), target) in automaton.transitions.iter() {
+            if grammar.is_nonterminal(symbol) {
+                goto.insert((*state, symbol.clone()), *target);
+            } else {
+                action.entry((*state, symbol.clone())).or_default().push(LRAction::Shift(*target));
+            }
+        }
+        for (state, items) in lookaheads.into_iter().enumerate() {
+            for (item, symbols) in items {
+                for symbol in symbols {
+                    let new_action = if item.production.head == *grammar.get_start_symbol() {
+                        LRAction::Accept
+                    } else {
+                        LRAction::Reduce(item.production.clone())
+                    };
+                    let cell = action.entry((state, symbol)).or_default();
+                    if !cell.contains(&new_action) {
+                        cell.push(new_action);
+                    }
+                }
+            }
+        }
+        LRTable {
+            kind,
+            grammar: grammar.clone(),
+            states: automaton.states.len(),
+            action,
+            goto,
+        }
+    }
+
+    pub fn get_kind(&self) -> LRTableKind {
+        self.kind
+    }
+
+    // Returns the actions of the cell ACTION[state, terminal].
+    pub fn get_action(&self, state: usize, terminal: &str) -> &[LRAction] {
+        self.action.get(&(state, terminal.to_string()))
+            .map_or(&[], |actions| actions.as_slice())
+    }
+
+    // Returns the cell GOTO[state, nonterminal].
+    pub fn get_goto(&self, state: usize, nonterminal: &str) -> Option<usize> {
+        self.goto.get(&(state, nonterminal.to_string())).copied()
+    }
+
+    // Returns the cells of the ACTION table with more than one action.
+    pub fn get_conflicts(&self) -> Vec<LRConflict> {
+        self.action.iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|((state, symbol), actions)| LRConflict {
+                state: *state,
+                symbol: symbol.clone(),
+                actions: actions.clone(),
+            })
+            .collect()
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        self.action.values().any(|actions| actions.len() > 1)
+    }
+}
+
+/* The table is printed with one row per state, the columns of the ACTION
+ * table (the terminals and the end marker) and then the ones of the GOTO
+ * table (the nonterminals of the original grammar). */
+impl fmt::Display for LRTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut terminals: Vec<Symbol> = self.grammar.get_terminals().into_iter().collect();
+        terminals.push(self.grammar.end_marker());
+        let nonterminals: Vec<&Symbol> = self.grammar.get_nonterminals().iter()
+            .filter(|nonterminal| *nonterminal != self.grammar.get_start_symbol())
+            .collect();
+        let mut header = vec!["State".to_string()];
+        header.extend(terminals.iter().cloned());
+        header.extend(nonterminals.iter().map(|nonterminal| nonterminal.to_string()));
+        let mut rows = vec![header];
+        for state in 0..self.states {
+            let mut row = vec![state.to_string()];
+            for terminal in terminals.iter() {
+                let actions: Vec<String> = self.get_action(state, terminal).iter().map(|action| action.to_string()).collect();
+                row.push(actions.join(", "));
+            }
+            for nonterminal in nonterminals.iter() {
+                row.push(self.get_goto(state, nonterminal).map_or(String::new(), |target| target.to_string()));
+            }
+            rows.push(row);
+        }
+        write!(f, "{}", format_table(&rows))
+    }
+}
+
+// Returns the closure of a set of items, keeping the order they are found.
+fn closure(grammar: &Grammar, items: Vec<LRItem>) -> Vec<LRItem> {
+    let mut items = items;
+    let mut index = 0;
+    while index < items.len() {
+        if let Some(symbol) = items[index].next_symbol().cloned() {
+            for production in grammar.productions_of(&symbol) {
+                let item = LRItem::new(production, 0);
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+        index += 1;
+    }
+    items
+}
+
+/* Returns the closure of a set of LR(1) items, for every item [A -> α·Bβ, a]
+ * the items [B -> ·γ, b] are added for every b in FIRST(βa). The FIRST sets
+ * of the nonterminals are given, so they are computed once per table. */
+fn lr1_closure(grammar: &Grammar, first: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+               items: BTreeSet<(LRItem, Symbol)>) -> BTreeSet<(LRItem, Symbol)> {
+    let mut items = items;
+    let mut pending: Vec<(LRItem, Symbol)> = items.iter().cloned().collect();
+    while let Some((item, lookahead)) = pending.pop() {
+        let symbol = match item.next_symbol() {
+            Some(symbol) if grammar.is_nonterminal(symbol) => symbol,
+            _ => continue,
+        };
+        let mut rest: Vec<Symbol> = item.production.body[item.dot + 1..].to_vec();
+        rest.push(lookahead.clone());
+        // The lookahead is a terminal, so FIRST(βa) never has ε.
+        let lookaheads = grammar.first_of_with(&rest, first);
+        for production in grammar.productions_of(symbol) {
+            for new_lookahead in lookaheads.iter() {
+                let new_item = (LRItem::new(production, 0), new_lookahead.clone());
+                if items.insert(new_item.clone()) {
+                    pending.push(new_item);
+                }
+            }
+        }
+    }
+    items
+}
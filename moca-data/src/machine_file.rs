@@ -0,0 +1,366 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::finite_automata::FiniteAutomata;
+use crate::mealy_machine::MealyMachine;
+use crate::moore_machine::MooreMachine;
+use crate::pushdown_automata::PushdownAutomata;
+use crate::state::StateID;
+use crate::state_machine::StateMachine;
+use crate::turing_machine::TuringMachine;
+
+/* Version of the file format written by this program. The files of a newer
+ * version are rejected, the older ones are read as they were written. */
+pub const FORMAT_VERSION: u32 = 1;
+
+/* Error found when reading a machine file, with a message that can be
+ * shown to the user. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineFileError {
+    pub message: String,
+}
+
+impl fmt::Display for MachineFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/* The kinds of machines that can be saved. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MachineFileKind {
+    Finite,
+    Pushdown,
+    Turing,
+    Mealy,
+    Moore,
+}
+
+/* Position of a state in the editor, the machines don't have one so it is
+ * supplied by the GUI when saving. */
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+/* A state as it is written in the file, the label is the set of states it
+ * comes from in a transformation and the output is only used by Moore
+ * machines. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub id: StateID,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub label: Vec<StateID>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    #[serde(default)]
+    pub initial: bool,
+    #[serde(default, rename = "final")]
+    pub is_final: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/* A transition as it is written in the file, one for every label. */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransitionEntry {
+    pub from: StateID,
+    pub to: StateID,
+    pub label: String,
+}
+
+/* Structure that represents a machine file, the JSON document where a
+ * machine is saved. Besides the states and transitions it has the kind of
+ * the machine and its settings, the stack symbol of a pushdown automaton and
+ * the blank symbol of a Turing machine. For example:
+ *     {
+ *       "version": 1,
+ *       "kind": "finite",
+ *       "states": [
+ *         { "id": 0, "name": "q0", "position": { "x": 100.0, "y": 80.0 }, "initial": true, "final": false },
+ *         { "id": 1, "name": "q1", "initial": false, "final": true }
+ *       ],
+ *       "transitions": [{ "from": 0, "to": 1, "label": "a" }]
+ *     }
+ * The states and transitions are ordered by id, so saving the same machine
+ * twice gives the same file. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MachineFile {
+    pub version: u32,
+    pub kind: MachineFileKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_stack_symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub empty_stack_acceptance: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blank_symbol: Option<String>,
+    pub states: Vec<StateEntry>,
+    pub transitions: Vec<TransitionEntry>,
+}
+
+/* A machine read from a file or to be saved, of any kind. */
+#[derive(Debug, Clone)]
+pub enum FileMachine {
+    Finite(FiniteAutomata),
+    Pushdown(PushdownAutomata),
+    Turing(TuringMachine),
+    Mealy(MealyMachine),
+    Moore(MooreMachine),
+}
+
+impl FileMachine {
+    pub fn kind(&self) -> MachineFileKind {
+        match self {
+            FileMachine::Finite(_) => MachineFileKind::Finite,
+            FileMachine::Pushdown(_) => MachineFileKind::Pushdown,
+            FileMachine::Turing(_) => MachineFileKind::Turing,
+            FileMachine::Mealy(_) => MachineFileKind::Mealy,
+            FileMachine::Moore(_) => MachineFileKind::Moore,
+        }
+    }
+
+    pub fn as_state_machine(&self) -> &dyn StateMachine {
+        match self {
+            FileMachine::Finite(machine) => machine,
+            FileMachine::Pushdown(machine) => machine,
+            FileMachine::Turing(machine) => machine,
+            FileMachine::Mealy(machine) => machine,
+            FileMachine::Moore(machine) => machine,
+        }
+    }
+
+    pub fn as_state_machine_mut(&mut self) -> &mut dyn StateMachine {
+        match self {
+            FileMachine::Finite(machine) => machine,
+            FileMachine::Pushdown(machine) => machine,
+            FileMachine::Turing(machine) => machine,
+            FileMachine::Mealy(machine) => machine,
+            FileMachine::Moore(machine) => machine,
+        }
+    }
+
+    /* Function that checks if the machine accepts a word. A Turing machine
+     * that doesn't halt in turing_machine::MAX_STEPS steps rejects it, and
+     * Mealy and Moore machines accept the words they can read. A machine
+     * without initial state accepts no word. */
+    pub fn accepts(&self, word: &str) -> bool {
+        if self.as_state_machine().get_initial_state_id().is_none() {
+            return false;
+        }
+        match self {
+            FileMachine::Finite(machine) => machine.check_input(&mut word.to_string()),
+            FileMachine::Pushdown(machine) => machine.check_input(&mut word.to_string()),
+            FileMachine::Turing(machine) => machine.check_input(&mut word.to_string()),
+            FileMachine::Mealy(machine) => machine.run(word).is_some(),
+            FileMachine::Moore(machine) => machine.run(word).is_some(),
+        }
+    }
+}
+
+impl MachineFile {
+    /* Function that builds the file of a machine, with the positions of the
+     * states that have one. */
+    pub fn new(machine: &FileMachine, positions: &HashMap<StateID, Position>) -> Self {
+        let state_machine = machine.as_state_machine();
+        let mut ids: Vec<&StateID> = state_machine.get_states_by_id_ref().keys().collect();
+        ids.sort();
+        let mut states = Vec::new();
+        let mut transitions = Vec::new();
+        for id in ids {
+            let state = &state_machine.get_states_by_id_ref()[id];
+            let output = match machine {
+                FileMachine::Moore(moore_machine) => Some(moore_machine.get_output(*id).to_string()),
+                _ => None,
+            };
+            states.push(StateEntry {
+                id: *id,
+                name: state.name.clone(),
+                label: state.label.iter().cloned().collect(),
+                position: positions.get(id).copied(),
+                initial: state_machine.get_initial_state_id() == &Some(*id),
+                is_final: state_machine.get_final_states().contains(id),
+                output,
+            });
+            let mut state_transitions: Vec<TransitionEntry> = state.iter_by_transition()
+                .flat_map(|(to_id, labels)| labels.iter().map(move |label| TransitionEntry {
+                    from: *id,
+                    to: *to_id,
+                    label: label.clone(),
+                }))
+                .collect();
+            state_transitions.sort_by(|a, b| (a.to, &a.label).cmp(&(b.to, &b.label)));
+            transitions.extend(state_transitions);
+        }
+        let (initial_stack_symbol, empty_stack_acceptance) = match machine {
+            FileMachine::Pushdown(pushdown_automaton) => (
+                Some(pushdown_automaton.get_initial_stack_symbol().to_string()),
+                pushdown_automaton.accepts_by_empty_stack(),
+            ),
+            _ => (None, false),
+        };
+        let blank_symbol = match machine {
+            FileMachine::Turing(turing_machine) => Some(turing_machine.get_blank_symbol().to_string()),
+            _ => None,
+        };
+        MachineFile {
+            version: FORMAT_VERSION,
+            kind: machine.kind(),
+            initial_stack_symbol,
+            empty_stack_acceptance,
+            blank_symbol,
+            states,
+            transitions,
+        }
+    }
+
+    /* Function that reads a machine file written as JSON. The JSON syntax
+     * errors have the line and column where they were found. Besides the
+     * syntax, the version must not be newer than FORMAT_VERSION, the ids of
+     * the states must be different and the transitions must go between
+     * states of the file. */
+    pub fn from_json(text: &str) -> Result<MachineFile, MachineFileError> {
+        let file: MachineFile = serde_json::from_str(text).map_err(|error| MachineFileError {
+            message: format!("invalid machine file: {}", error),
+        })?;
+        check_version(file.version)?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    /* Function that checks that the states and transitions of the file can
+     * be a machine: the ids of the states are unique, the transitions go
+     * between states of the file and there is at most one initial state. */
+    pub fn validate(&self) -> Result<(), MachineFileError> {
+        let mut ids: BTreeSet<StateID> = BTreeSet::new();
+        for state in self.states.iter() {
+            if !ids.insert(state.id) {
+                return Err(MachineFileError { message: format!("the state id {} is repeated", state.id) });
+            }
+        }
+        if let Some(transition) = self.transitions.iter().find(|t| !ids.contains(&t.from) || !ids.contains(&t.to)) {
+            return Err(MachineFileError {
+                message: format!("the transition {} from {} to {} uses a state that does not exist", transition.label, transition.from, transition.to),
+            });
+        }
+        if self.states.iter().filter(|state| state.initial).count() > 1 {
+            return Err(MachineFileError { message: "the machine has more than one initial state".to_string() });
+        }
+        Ok(())
+    }
+
+    // Writes the file as indented JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a machine file can always be written as JSON")
+    }
+
+    /* Function that builds the machine saved in the file. */
+    pub fn to_machine(&self) -> FileMachine {
+        let mut machine = match self.kind {
+            MachineFileKind::Finite => FileMachine::Finite(FiniteAutomata::new()),
+            MachineFileKind::Pushdown => {
+                let initial_stack_symbol = self.initial_stack_symbol.clone().unwrap_or_else(|| "Z".to_string());
+                let mut pushdown_automaton = PushdownAutomata::new(initial_stack_symbol);
+                pushdown_automaton.set_empty_stack_acceptance(self.empty_stack_acceptance);
+                FileMachine::Pushdown(pushdown_automaton)
+            }
+            MachineFileKind::Turing => FileMachine::Turing(TuringMachine::new(self.blank_symbol.clone().unwrap_or_else(|| "_".to_string()))),
+            MachineFileKind::Mealy => FileMachine::Mealy(MealyMachine::new()),
+            MachineFileKind::Moore => FileMachine::Moore(MooreMachine::new()),
+        };
+        let state_machine = machine.as_state_machine_mut();
+        for state in self.states.iter() {
+            state_machine.add_state_with_id_label(state.id, &state.name);
+            if let Some(machine_state) = state_machine.get_states_by_id_mut_ref().get_mut(&state.id) {
+                machine_state.label = state.label.iter().cloned().collect();
+            }
+        }
+        for transition in self.transitions.iter() {
+            state_machine.add_transition(transition.from, transition.to, transition.label.clone());
+        }
+        for state in self.states.iter() {
+            if state.is_final {
+                state_machine.make_final(state.id);
+            }
+            if state.initial {
+                state_machine.make_initial(state.id);
+            }
+        }
+        if let FileMachine::Moore(moore_machine) = &mut machine {
+            for state in self.states.iter() {
+                if let Some(output) = &state.output {
+                    moore_machine.set_output(state.id, output.clone());
+                }
+            }
+        }
+        machine
+    }
+
+    /* Returns the positions of the states that have one. */
+    pub fn positions(&self) -> HashMap<StateID, Position> {
+        self.states.iter()
+            .filter_map(|state| state.position.map(|position| (state.id, position)))
+            .collect()
+    }
+}
+
+/* A tab of a project, its name and its machine. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTab {
+    pub name: String,
+    pub machine: MachineFile,
+}
+
+/* Structure that represents a project file, the JSON document where every
+ * tab of the editor is saved, in order, with the index of the tab that was
+ * active. It has the same version as the machine files. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectFile {
+    pub version: u32,
+    #[serde(default)]
+    pub active_tab: usize,
+    pub tabs: Vec<ProjectTab>,
+}
+
+impl ProjectFile {
+    pub fn new(tabs: Vec<ProjectTab>, active_tab: usize) -> Self {
+        ProjectFile {
+            version: FORMAT_VERSION,
+            active_tab,
+            tabs,
+        }
+    }
+
+    /* Function that reads a project file written as JSON, the machine of
+     * every tab is checked like in MachineFile::from_json. */
+    pub fn from_json(text: &str) -> Result<ProjectFile, MachineFileError> {
+        let file: ProjectFile = serde_json::from_str(text).map_err(|error| MachineFileError {
+            message: format!("invalid project file: {}", error),
+        })?;
+        check_version(file.version)?;
+        for tab in file.tabs.iter() {
+            check_version(tab.machine.version)?;
+            tab.machine.validate().map_err(|error| MachineFileError {
+                message: format!("tab {}: {}", tab.name, error.message),
+            })?;
+        }
+        Ok(file)
+    }
+
+    // Writes the file as indented JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a project file can always be written as JSON")
+    }
+}
+
+// Auxiliar function that rejects the files written by a newer version of the program.
+fn check_version(version: u32) -> Result<(), MachineFileError> {
+    if version > FORMAT_VERSION {
+        return Err(MachineFileError {
+            message: format!("the file has version {}, but the newest version supported is {}", version, FORMAT_VERSION),
+        });
+    }
+    Ok(())
+}
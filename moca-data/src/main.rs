@@ -1,11 +1,48 @@
 pub mod state;
+mod ambiguity;
+mod automaton_text;
+mod cli;
+mod cyk;
+mod dot;
+mod earley;
+mod file_format;
 mod finite_automata;
+mod generator;
+mod grammar;
+mod jflap;
+mod ll1;
+mod lr;
+mod machine_file;
+mod mealy_machine;
+mod moore_machine;
+mod multi_tape_turing_machine;
 mod pushdown_automata;
+mod regular_expression;
 mod state_machine;
+mod test_suite;
+mod tikz;
+mod turing_machine;
 #[cfg(test)]
 pub mod tests;
 
-fn main() {}
+/* Runs the command given in the arguments, see cli::run. The output is
+ * written to the standard output and the errors to the standard error. */
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdin = std::io::stdin();
+    match cli::run(&args, &mut stdin.lock()) {
+        Ok(output) => {
+            print!("{}", output.text);
+            if !output.success {
+                std::process::exit(1);
+            }
+        }
+        Err(error) => {
+            eprintln!("moca-data: {}", error);
+            std::process::exit(2);
+        }
+    }
+}
 
 /*println!("id \t label \t\t transitions"); to_string of an automata to debug more easily
         for (id, state) in deterministic_automata.get_states_by_id_ref() {
@@ -0,0 +1,307 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use crate::moore_machine::MooreMachine;
+use crate::state::{Input, State, StateID};
+use crate::state_machine::StateMachine;
+
+/* The output and the block of the next state of a state with every input
+ * symbol, the states of a block with different signatures are split when
+ * the machine is minimized. */
+type Signature = Vec<Option<(String, usize)>>;
+
+/* Structure that represents a Mealy machine, a finite transducer whose
+ * outputs are attached to the transitions. The transitions are labeled as
+ * input/output, e.g. a/01 reads the symbol a and writes 01, the input is a
+ * single character and the output any string, ε or a blank output writes
+ * nothing. The machine has no accepting states, but the final states are
+ * kept like in the other machines. */
+#[derive(Debug, Default, Clone)]
+pub struct MealyMachine {
+    states_by_id: HashMap<StateID, State>,
+    initial_state_id: Option<StateID>,
+    final_states: HashSet<StateID>,
+    deterministic: bool,
+}
+
+impl MealyMachine {
+    pub fn new() -> Self {
+        MealyMachine {
+            states_by_id: HashMap::new(),
+            initial_state_id: None,
+            final_states: HashSet::new(),
+            deterministic: true,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states_by_id.clear();
+        self.initial_state_id = None;
+        self.final_states.clear();
+        self.deterministic = true;
+    }
+
+    /* Returns the input symbols read by some transition, in order. */
+    pub fn input_symbols(&self) -> BTreeSet<String> {
+        self.states_by_id.values()
+            .flat_map(|state| state.iter_by_transition())
+            .flat_map(|(_, labels)| labels.iter())
+            .filter_map(|label| parse_transition_label(label))
+            .map(|(input, _)| input)
+            .collect()
+    }
+
+    // The transition function of the machine, maps a state and an input symbol to the
+    // next state and the output of the transition, or None if there is no transition.
+    pub fn transition(&self, state_id: StateID, symbol: &str) -> Option<(StateID, String)> {
+        let state = self.states_by_id.get(&state_id)?;
+        let mut moves: Vec<(StateID, String)> = state.iter_by_transition()
+            .flat_map(|(to_id, labels)| labels.iter().map(move |label| (*to_id, label)))
+            .filter_map(|(to_id, label)| parse_transition_label(label).map(|(input, output)| (to_id, input, output)))
+            .filter(|(_, input, _)| input == symbol)
+            .map(|(to_id, _, output)| (to_id, output))
+            .collect();
+        moves.sort();
+        moves.into_iter().next()
+    }
+
+    /* Function that runs the machine on the input and returns the
+     * concatenation of the outputs of the transitions taken, or None if some
+     * symbol can't be read or there is no initial state. If the machine is
+     * not deterministic the same transition is always taken. */
+    pub fn run(&self, input: &str) -> Option<String> {
+        let mut state_id = self.initial_state_id?;
+        let mut output = String::new();
+        for symbol in input.chars() {
+            let (next_id, transition_output) = self.transition(state_id, &symbol.to_string())?;
+            output.push_str(&transition_output);
+            state_id = next_id;
+        }
+        Some(output)
+    }
+
+    /* Function that builds an equivalent Moore machine. Its states are the
+     * pairs of a state and the output of a transition that enters it, the
+     * output of the pair, so a state of this machine is split in as many
+     * states as different outputs reach it. The initial state is the pair of
+     * the initial state and the empty output, so both machines write the same
+     * output for every input. A pair with an empty output keeps the name of
+     * the state, the other ones are named state/output, e.g. q1/0. Only the
+     * pairs reachable from the initial one are built. */
+    pub fn to_moore(&self) -> MooreMachine {
+        let mut moore_machine = MooreMachine::new();
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return moore_machine,
+        };
+        let symbols = self.input_symbols();
+        let mut ids: HashMap<(StateID, String), StateID> = HashMap::new();
+        let mut queue: VecDeque<(StateID, String)> = VecDeque::new();
+        let initial_pair = (initial_id, String::new());
+        self.add_moore_state(&mut moore_machine, &initial_pair, &mut ids);
+        moore_machine.make_initial(0);
+        queue.push_back(initial_pair);
+        while let Some(pair) = queue.pop_front() {
+            let from_id = ids[&pair];
+            for symbol in symbols.iter() {
+                if let Some(next_pair) = self.transition(pair.0, symbol) {
+                    if !ids.contains_key(&next_pair) {
+                        self.add_moore_state(&mut moore_machine, &next_pair, &mut ids);
+                        queue.push_back(next_pair.clone());
+                    }
+                    moore_machine.add_transition(from_id, ids[&next_pair], symbol.clone());
+                }
+            }
+        }
+        moore_machine
+    }
+
+    // Auxiliar function that adds to the Moore machine the state of a pair of a state
+    // and an output.
+    fn add_moore_state(&self, moore_machine: &mut MooreMachine, pair: &(StateID, String), ids: &mut HashMap<(StateID, String), StateID>) {
+        let (state_id, output) = pair;
+        let id = ids.len() as StateID;
+        let name = self.states_by_id.get(state_id).map_or(state_id.to_string(), |state| state.name.clone());
+        let name = if output.is_empty() { name } else { format!("{}/{}", name, output) };
+        moore_machine.add_state_with_id_label(id, &name);
+        moore_machine.set_output(id, output.clone());
+        if self.final_states.contains(state_id) {
+            moore_machine.make_final(id);
+        }
+        ids.insert(pair.clone(), id);
+    }
+
+    /* Method that minimizes the machine and returns a copy of it minimized.
+     * The unreachable states are removed and the equivalent states are
+     * merged, two states are equivalent if they write the same output for
+     * every input. The equivalence is found refining the partition of the
+     * states by the outputs of their transitions, until the states of every
+     * block go to the same blocks with every symbol (Moore's algorithm). The
+     * missing transitions count as a different output. The states are named
+     * q0, q1, ... in the order they are reached from the initial state, and
+     * their label is the set of the original states. */
+    pub fn minimize(&self) -> MealyMachine {
+        let mut minimized_machine = MealyMachine::new();
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return minimized_machine,
+        };
+        let symbols: Vec<String> = self.input_symbols().into_iter().collect();
+        let reachable_states = self.reachable_states(initial_id, &symbols);
+        let mut blocks: HashMap<StateID, usize> = reachable_states.iter().map(|id| (*id, 0)).collect();
+        let mut block_count = 1;
+        loop {
+            let mut signatures: HashMap<(usize, Signature), usize> = HashMap::new();
+            let mut next_blocks: HashMap<StateID, usize> = HashMap::new();
+            for state_id in reachable_states.iter() {
+                let signature: Signature = symbols.iter()
+                    .map(|symbol| self.transition(*state_id, symbol).map(|(to_id, output)| (output, blocks[&to_id])))
+                    .collect();
+                let next_block_count = signatures.len();
+                let block = *signatures.entry((blocks[state_id], signature)).or_insert(next_block_count);
+                next_blocks.insert(*state_id, block);
+            }
+            blocks = next_blocks;
+            if signatures.len() == block_count {
+                break;
+            }
+            block_count = signatures.len();
+        }
+
+        // The blocks become states in the order their first state was reached.
+        let mut block_ids: HashMap<usize, StateID> = HashMap::new();
+        for state_id in reachable_states.iter() {
+            let block = blocks[state_id];
+            if !block_ids.contains_key(&block) {
+                block_ids.insert(block, block_ids.len() as StateID);
+                minimized_machine.add_state();
+            }
+            let id = block_ids[&block];
+            if let Some(state) = minimized_machine.states_by_id.get_mut(&id) {
+                state.label.insert(*state_id);
+            }
+            if self.final_states.contains(state_id) {
+                minimized_machine.make_final(id);
+            }
+        }
+        minimized_machine.make_initial(block_ids[&blocks[&initial_id]]);
+        let mut added_blocks: HashSet<usize> = HashSet::new();
+        for state_id in reachable_states.iter() {
+            let block = blocks[state_id];
+            if !added_blocks.insert(block) {
+                continue;
+            }
+            for symbol in symbols.iter() {
+                if let Some((to_id, output)) = self.transition(*state_id, symbol) {
+                    let label = if output.is_empty() { format!("{}/ε", symbol) } else { format!("{}/{}", symbol, output) };
+                    minimized_machine.add_transition(block_ids[&block], block_ids[&blocks[&to_id]], label);
+                }
+            }
+        }
+        minimized_machine
+    }
+
+    // Auxiliar function that returns the states reachable from the initial state, in
+    // breadth first order.
+    fn reachable_states(&self, initial_id: StateID, symbols: &[String]) -> Vec<StateID> {
+        let mut reachable_states = vec![initial_id];
+        let mut visited: HashSet<StateID> = HashSet::new();
+        visited.insert(initial_id);
+        let mut index = 0;
+        while index < reachable_states.len() {
+            let state_id = reachable_states[index];
+            for symbol in symbols.iter() {
+                if let Some((to_id, _)) = self.transition(state_id, symbol) {
+                    if visited.insert(to_id) {
+                        reachable_states.push(to_id);
+                    }
+                }
+            }
+            index += 1;
+        }
+        reachable_states
+    }
+}
+
+/* Function that splits a transition label of a Mealy machine as input and
+ * output. The input must be a single character, and the output ε or a blank
+ * output is the empty string. Returns None if the label does not have the
+ * form input/output. */
+pub fn parse_transition_label(label: &str) -> Option<(String, String)> {
+    let (input, output) = label.split_once('/')?;
+    let input = input.trim();
+    let output = output.trim();
+    if input.chars().count() != 1 {
+        return None;
+    }
+    let output = if output == "ε" { "" } else { output };
+    Some((input.to_string(), output.to_string()))
+}
+
+impl StateMachine for MealyMachine {
+    fn get_states_by_id_mut_ref(&mut self) -> &mut HashMap<StateID, State> {
+        &mut self.states_by_id
+    }
+
+    fn get_states_by_id_ref(&self) -> &HashMap<StateID, State> {
+        &self.states_by_id
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    fn get_final_states(&self) -> &HashSet<StateID> {
+        &self.final_states
+    }
+
+    fn get_initial_state_id(&self) -> &Option<StateID> {
+        &self.initial_state_id
+    }
+
+    /* The machine stops being deterministic when a state has two
+     * transitions that read the same symbol. */
+    fn add_transition(&mut self, state_id1: StateID, state_id2: StateID, input: Input) {
+        // Labels that are not input/output are not added.
+        let symbol = match parse_transition_label(&input) {
+            Some((symbol, _)) => symbol,
+            None => return,
+        };
+        if !self.states_by_id.contains_key(&state_id2) {
+            return;
+        }
+        let state = match self.states_by_id.get_mut(&state_id1) {
+            Some(state) => state,
+            None => return,
+        };
+        // The same transition added again is not another move.
+        let repeated = state.iter_by_transition()
+            .flat_map(|(to_id, labels)| labels.iter().map(move |label| (*to_id, label)))
+            .filter(|(to_id, label)| *to_id != state_id2 || **label != input)
+            .filter_map(|(_, label)| parse_transition_label(label))
+            .any(|(other_symbol, _)| other_symbol == symbol);
+        if repeated {
+            self.deterministic = false;
+        }
+        state.add_transition(state_id2, input);
+    }
+
+    fn make_initial(&mut self, state_id: StateID) {
+        if !self.states_by_id.contains_key(&state_id) {
+            return;
+        }
+        if let Some(old_initial_state) = self.initial_state_id.and_then(|old_id| self.states_by_id.get_mut(&old_id)) {
+            old_initial_state.initial_flag = false;
+        }
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.initial_flag = true;
+            self.initial_state_id = Some(state_id);
+        }
+    }
+
+    /* Function to make a state final. */
+    fn make_final(&mut self, state_id: StateID) {
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.final_flag = true;
+            self.final_states.insert(state_id);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use crate::mealy_machine::MealyMachine;
+use crate::state::{Input, State, StateID};
+use crate::state_machine::StateMachine;
+
+/* Structure that represents a Moore machine, a finite transducer whose
+ * outputs are attached to the states. The transitions are labeled with the
+ * input symbol they read, a single character, and every state writes its
+ * output when the machine enters it, the initial state before reading the
+ * input. The states without an output write nothing. */
+#[derive(Debug, Default, Clone)]
+pub struct MooreMachine {
+    states_by_id: HashMap<StateID, State>,
+    initial_state_id: Option<StateID>,
+    final_states: HashSet<StateID>,
+    outputs: HashMap<StateID, String>,
+    deterministic: bool,
+}
+
+impl MooreMachine {
+    pub fn new() -> Self {
+        MooreMachine {
+            states_by_id: HashMap::new(),
+            initial_state_id: None,
+            final_states: HashSet::new(),
+            outputs: HashMap::new(),
+            deterministic: true,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states_by_id.clear();
+        self.initial_state_id = None;
+        self.final_states.clear();
+        self.outputs.clear();
+        self.deterministic = true;
+    }
+
+    // Getter for the output of a state, empty if it has none.
+    pub fn get_output(&self, state_id: StateID) -> &str {
+        self.outputs.get(&state_id).map_or("", |output| output.as_str())
+    }
+
+    /* Function to change the output of a state, ε is the empty output. */
+    pub fn set_output(&mut self, state_id: StateID, output: String) {
+        if self.states_by_id.contains_key(&state_id) {
+            let output = if output == "ε" { String::new() } else { output };
+            self.outputs.insert(state_id, output);
+        }
+    }
+
+    // The transition function of the machine, maps a state and an input symbol to the
+    // next state, or None if there is no transition.
+    pub fn transition(&self, state_id: StateID, symbol: &str) -> Option<StateID> {
+        let state = self.states_by_id.get(&state_id)?;
+        state.iter_by_transition()
+            .filter(|(_, labels)| labels.contains(symbol))
+            .map(|(to_id, _)| *to_id)
+            .min()
+    }
+
+    /* Function that runs the machine on the input and returns the output of
+     * the initial state followed by the outputs of the states it enters, or
+     * None if some symbol can't be read or there is no initial state. If the
+     * machine is not deterministic the same transition is always taken. */
+    pub fn run(&self, input: &str) -> Option<String> {
+        let mut state_id = self.initial_state_id?;
+        let mut output = self.get_output(state_id).to_string();
+        for symbol in input.chars() {
+            state_id = self.transition(state_id, &symbol.to_string())?;
+            output.push_str(self.get_output(state_id));
+        }
+        Some(output)
+    }
+
+    /* Function that builds an equivalent Mealy machine with the same states,
+     * every transition p -a-> q writes the output of q, so it is labeled
+     * a/output. The Mealy machine can't write the output of the initial state,
+     * so for every input it writes the output of this machine without it. */
+    pub fn to_mealy(&self) -> MealyMachine {
+        let mut mealy_machine = MealyMachine::new();
+        for (id, state) in self.states_by_id.iter() {
+            mealy_machine.add_state_with_id_label(*id, &state.name);
+        }
+        for (from_id, state) in self.states_by_id.iter() {
+            for (to_id, labels) in state.iter_by_transition() {
+                let output = self.get_output(*to_id);
+                let output = if output.is_empty() { "ε" } else { output };
+                for label in labels {
+                    mealy_machine.add_transition(*from_id, *to_id, format!("{}/{}", label, output));
+                }
+            }
+        }
+        for final_id in self.final_states.iter() {
+            mealy_machine.make_final(*final_id);
+        }
+        if let Some(initial_id) = self.initial_state_id {
+            mealy_machine.make_initial(initial_id);
+        }
+        mealy_machine
+    }
+}
+
+impl StateMachine for MooreMachine {
+    fn get_states_by_id_mut_ref(&mut self) -> &mut HashMap<StateID, State> {
+        &mut self.states_by_id
+    }
+
+    fn get_states_by_id_ref(&self) -> &HashMap<StateID, State> {
+        &self.states_by_id
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    fn get_final_states(&self) -> &HashSet<StateID> {
+        &self.final_states
+    }
+
+    fn get_initial_state_id(&self) -> &Option<StateID> {
+        &self.initial_state_id
+    }
+
+    /* The inputs of the transitions are single characters, the machine stops
+     * being deterministic when a state has two transitions that read the
+     * same symbol. */
+    fn add_transition(&mut self, state_id1: StateID, state_id2: StateID, input: Input) {
+        let input = input.trim().to_string();
+        if input.chars().count() != 1 || !self.states_by_id.contains_key(&state_id2) {
+            return;
+        }
+        if let Some(state) = self.states_by_id.get_mut(&state_id1) {
+            self.deterministic = state.add_transition(state_id2, input) && self.deterministic;
+        }
+    }
+
+    fn make_initial(&mut self, state_id: StateID) {
+        if !self.states_by_id.contains_key(&state_id) {
+            return;
+        }
+        if let Some(old_initial_state) = self.initial_state_id.and_then(|old_id| self.states_by_id.get_mut(&old_id)) {
+            old_initial_state.initial_flag = false;
+        }
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.initial_flag = true;
+            self.initial_state_id = Some(state_id);
+        }
+    }
+
+    /* Function to make a state final. */
+    fn make_final(&mut self, state_id: StateID) {
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.final_flag = true;
+            self.final_states.insert(state_id);
+        }
+    }
+}
@@ -0,0 +1,508 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use crate::state::{Input, State, StateID};
+use crate::state_machine::StateMachine;
+use crate::turing_machine::{self, breadth_first_run, HaltingStatus, TapeMove, TuringMachine, MAX_STEPS};
+
+/* Structure that represents a Turing machine with k tapes, every tape is
+ * infinite in both directions and has its own head. The transitions are
+ * labeled with one read/write,move part for every tape separated by |, e.g.
+ * a/a,R|_/a,R reads a on the first tape and a blank on the second one,
+ * writes a on both and moves both heads to the right. The input is written
+ * on the first tape and the other ones start blank. The machine can be
+ * nondeterministic, the runs are explored breadth first. */
+#[derive(Debug, Clone)]
+pub struct MultiTapeTuringMachine {
+    states_by_id: HashMap<StateID, State>,
+    initial_state_id: Option<StateID>,
+    final_states: HashSet<StateID>,
+    blank_symbol: String,
+    tape_count: usize,
+    deterministic: bool,
+}
+
+impl MultiTapeTuringMachine {
+    pub fn new(blank_symbol: String, tape_count: usize) -> Self {
+        MultiTapeTuringMachine {
+            states_by_id: HashMap::new(),
+            initial_state_id: None,
+            final_states: HashSet::new(),
+            blank_symbol,
+            tape_count: tape_count.max(1),
+            deterministic: true,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states_by_id.clear();
+        self.initial_state_id = None;
+        self.final_states.clear();
+        self.deterministic = true;
+    }
+
+    pub fn get_blank_symbol(&self) -> &str {
+        &self.blank_symbol
+    }
+
+    pub fn get_tape_count(&self) -> usize {
+        self.tape_count
+    }
+
+    /* Function to check if a given input string is accepted by the machine,
+     * i.e. some run reaches a final state exploring at most MAX_STEPS
+     * configurations. */
+    pub fn check_input(&self, input: &mut Input) -> bool {
+        self.run(input, MAX_STEPS).status == HaltingStatus::Accept
+    }
+
+    /* Returns the configuration before reading the input, with the input on
+     * the first tape and every head on the first cell. Returns None if there
+     * is no initial state. */
+    pub fn initial_configuration(&self, input: &str) -> Option<MultiTapeConfiguration> {
+        let mut tapes = vec![vec![self.blank_symbol.clone()]; self.tape_count];
+        if !input.is_empty() {
+            tapes[0] = input.chars().map(|c| c.to_string()).collect();
+        }
+        Some(MultiTapeConfiguration {
+            state_id: self.initial_state_id?,
+            tapes,
+            heads: vec![0; self.tape_count],
+        })
+    }
+
+    /* Function that runs the machine on the input exploring its
+     * configurations breadth first, at most max_steps of them. For a
+     * deterministic machine it is the only run, otherwise the trace is the
+     * run that reaches a final state if the input is accepted. */
+    pub fn run(&self, input: &str, max_steps: usize) -> MultiTapeRun {
+        let initial_configuration = match self.initial_configuration(input) {
+            Some(configuration) => configuration,
+            None => return MultiTapeRun { status: HaltingStatus::Reject, trace: Vec::new() },
+        };
+        let transitions = self.parsed_transitions();
+        let (status, trace) = breadth_first_run(initial_configuration, max_steps,
+            |configuration| self.final_states.contains(&configuration.state_id),
+            |configuration| transitions.iter()
+                .filter_map(|transition| transition.apply(configuration, &self.blank_symbol))
+                .collect());
+        MultiTapeRun { status, trace }
+    }
+
+    /* Function that writes a configuration as the name of the state followed
+     * by every tape, with the symbol under the head between brackets and
+     * without the blanks at both ends, e.g. q1: a[b] | [_]. */
+    pub fn format_configuration(&self, configuration: &MultiTapeConfiguration) -> String {
+        let state_name = self.states_by_id.get(&configuration.state_id)
+            .map_or(configuration.state_id.to_string(), |state| state.name.clone());
+        let tapes: Vec<String> = configuration.tapes.iter()
+            .zip(configuration.heads.iter())
+            .map(|(tape, head)| {
+                let is_written = |symbol: &String| *symbol != self.blank_symbol;
+                let start = tape.iter().position(is_written).unwrap_or(*head).min(*head);
+                let end = tape.iter().rposition(is_written).map_or(*head, |index| index.max(*head));
+                (start..=end)
+                    .map(|index| if index == *head { format!("[{}]", tape[index]) } else { tape[index].clone() })
+                    .collect()
+            })
+            .collect();
+        format!("{}: {}", state_name, tapes.join(" | "))
+    }
+
+    /* Function that builds a single-tape machine that simulates this one. The
+     * tape of the new machine has k tracks, every cell is written as
+     * [a|b^|...] with the symbols of the k tapes and ^ after the symbol under
+     * each head. A step of the machine is simulated in three phases:
+     *  1. The tape is scanned from left to right collecting the symbols under
+     *     the heads, in states named q [a|?] that remember them.
+     *  2. At the right end a transition that reads those symbols is chosen.
+     *  3. The tape is scanned from right to left writing the symbols and
+     *     moving the head marks, the marks that move right need a detour
+     *     of one cell to the right and back.
+     * Before the first step the input is rewritten as the first track with
+     * every head mark on the first cell. The states of the simulated machine
+     * keep their names at the start of every scan, and the final ones are
+     * final. If the machine is nondeterministic so is the new one. */
+    pub fn to_single_tape(&self) -> TuringMachine {
+        let mut machine = TuringMachine::new(self.blank_symbol.clone());
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return machine,
+        };
+        let tape_count = self.tape_count;
+        let blank = self.blank_symbol.clone();
+        let transitions = self.parsed_transitions();
+        let mut alphabet: BTreeSet<String> = BTreeSet::new();
+        alphabet.insert(blank.clone());
+        for transition in transitions.iter() {
+            for (read, write, _) in transition.moves.iter() {
+                alphabet.insert(read.clone());
+                alphabet.insert(write.clone());
+            }
+        }
+        // Every cell of the tracks, a symbol and a head mark for every tape.
+        let mut cells: Vec<TrackCell> = vec![TrackCell { symbols: Vec::new(), marks: Vec::new() }];
+        for _ in 0..tape_count {
+            cells = cells.into_iter()
+                .flat_map(|cell| alphabet.iter().flat_map(move |symbol| [false, true].map(|mark| {
+                    let mut cell = cell.clone();
+                    cell.symbols.push(symbol.clone());
+                    cell.marks.push(mark);
+                    cell
+                })))
+                .collect();
+        }
+        let blank_cell = |marks: Vec<bool>| TrackCell { symbols: vec![blank.clone(); tape_count], marks };
+        let input_cell = |symbol: &String, marks: Vec<bool>| {
+            let mut cell = blank_cell(marks);
+            cell.symbols[0] = symbol.clone();
+            cell
+        };
+        let none = vec![false; tape_count];
+        let all = vec![true; tape_count];
+        let input_symbols: Vec<&String> = alphabet.iter().filter(|symbol| **symbol != blank).collect();
+        let state_name = |id: &StateID| self.states_by_id.get(id).map_or(id.to_string(), |state| state.name.clone());
+
+        let mut ids: HashMap<SimulationState, StateID> = HashMap::new();
+        let mut pending: VecDeque<SimulationState> = VecDeque::new();
+        let mut moves: Vec<(StateID, SimulationState, String)> = Vec::new();
+        let add_state = |simulation_state: &SimulationState, machine: &mut TuringMachine,
+                         ids: &mut HashMap<SimulationState, StateID>, pending: &mut VecDeque<SimulationState>| -> StateID {
+            if let Some(id) = ids.get(simulation_state) {
+                return *id;
+            }
+            let id = ids.len() as StateID;
+            let name = match simulation_state {
+                SimulationState::Init => "init".to_string(),
+                SimulationState::Copy => "copy".to_string(),
+                SimulationState::Rewind => "rewind".to_string(),
+                SimulationState::Scan(state_id, collected) if collected.iter().all(|symbol| symbol.is_none()) => state_name(state_id),
+                SimulationState::Scan(state_id, collected) => {
+                    let symbols: Vec<&str> = collected.iter().map(|symbol| symbol.as_deref().unwrap_or("?")).collect();
+                    format!("{} [{}]", state_name(state_id), symbols.join("|"))
+                }
+                SimulationState::Update(index, processed, carried) =>
+                    format!("t{} write {}/{}", index, format_marks(processed), format_marks(carried)),
+                SimulationState::Right(index, processed, carried, right) =>
+                    format!("t{} right {}/{}/{}", index, format_marks(processed), format_marks(carried), format_marks(right)),
+                SimulationState::Back(index, processed, carried) =>
+                    format!("t{} back {}/{}", index, format_marks(processed), format_marks(carried)),
+            };
+            machine.add_state_with_id_label(id, &name);
+            ids.insert(simulation_state.clone(), id);
+            pending.push_back(simulation_state.clone());
+            id
+        };
+        let init_id = add_state(&SimulationState::Init, &mut machine, &mut ids, &mut pending);
+        machine.make_initial(init_id);
+        let label = |read: &str, write: &str, direction: TapeMove| format!("{}/{},{}", read, write, direction);
+
+        while let Some(simulation_state) = pending.pop_front() {
+            let from_id = ids[&simulation_state];
+            match &simulation_state {
+                SimulationState::Init => {
+                    let scan = SimulationState::Scan(initial_id, vec![None; tape_count]);
+                    for symbol in input_symbols.iter() {
+                        moves.push((from_id, SimulationState::Copy, label(symbol, &input_cell(symbol, all.clone()).to_string(), TapeMove::Right)));
+                    }
+                    moves.push((from_id, scan, label(&blank, &blank_cell(all.clone()).to_string(), TapeMove::Stay)));
+                }
+                SimulationState::Copy => {
+                    for symbol in input_symbols.iter() {
+                        moves.push((from_id, SimulationState::Copy, label(symbol, &input_cell(symbol, none.clone()).to_string(), TapeMove::Right)));
+                    }
+                    moves.push((from_id, SimulationState::Rewind, label(&blank, &blank, TapeMove::Left)));
+                }
+                SimulationState::Rewind => {
+                    for cell in cells.iter() {
+                        moves.push((from_id, SimulationState::Rewind, label(&cell.to_string(), &cell.to_string(), TapeMove::Left)));
+                    }
+                    let scan = SimulationState::Scan(initial_id, vec![None; tape_count]);
+                    moves.push((from_id, scan, label(&blank, &blank, TapeMove::Right)));
+                }
+                SimulationState::Scan(state_id, collected) => {
+                    // The machine halts as soon as it reaches a final state.
+                    if self.final_states.contains(state_id) {
+                        continue;
+                    }
+                    for cell in cells.iter() {
+                        let mut next = collected.clone();
+                        for (tape, symbol) in cell.symbols.iter().enumerate().filter(|(tape, _)| cell.marks[*tape]) {
+                            next[tape] = Some(symbol.clone());
+                        }
+                        let next = SimulationState::Scan(*state_id, next);
+                        moves.push((from_id, next, label(&cell.to_string(), &cell.to_string(), TapeMove::Right)));
+                    }
+                    for (index, transition) in transitions.iter().enumerate().filter(|(_, t)| t.from_id == *state_id) {
+                        let reads = transition.moves.iter().zip(collected.iter())
+                            .all(|((read, _, _), symbol)| symbol.as_ref() == Some(read));
+                        if reads {
+                            let update = SimulationState::Update(index, none.clone(), none.clone());
+                            moves.push((from_id, update, label(&blank, &blank, TapeMove::Left)));
+                        }
+                    }
+                }
+                SimulationState::Update(index, processed, carried) => {
+                    let transition = &transitions[*index];
+                    for cell in cells.iter() {
+                        let mut written = cell.clone();
+                        let mut processed = processed.clone();
+                        let mut next_carried = none.clone();
+                        let mut right = none.clone();
+                        for tape in 0..tape_count {
+                            if carried[tape] {
+                                written.marks[tape] = true;
+                                continue;
+                            }
+                            if !cell.marks[tape] || processed[tape] {
+                                continue;
+                            }
+                            let (_, write, direction) = &transition.moves[tape];
+                            written.symbols[tape] = write.clone();
+                            processed[tape] = true;
+                            match direction {
+                                TapeMove::Left => {
+                                    written.marks[tape] = false;
+                                    next_carried[tape] = true;
+                                }
+                                TapeMove::Right => {
+                                    written.marks[tape] = false;
+                                    right[tape] = true;
+                                }
+                                TapeMove::Stay => (),
+                            }
+                        }
+                        let (next, direction) = if right.contains(&true) {
+                            (SimulationState::Right(*index, processed, next_carried, right), TapeMove::Right)
+                        } else {
+                            (SimulationState::Update(*index, processed, next_carried), TapeMove::Left)
+                        };
+                        moves.push((from_id, next, label(&cell.to_string(), &written.to_string(), direction)));
+                    }
+                    // At the left end every head was moved, so the next step can start.
+                    if processed.iter().all(|processed| *processed) {
+                        let scan = SimulationState::Scan(transition.to_id, vec![None; tape_count]);
+                        if carried.contains(&true) {
+                            moves.push((from_id, scan, label(&blank, &blank_cell(carried.clone()).to_string(), TapeMove::Stay)));
+                        } else {
+                            moves.push((from_id, scan, label(&blank, &blank, TapeMove::Right)));
+                        }
+                    }
+                }
+                SimulationState::Right(index, processed, carried, right) => {
+                    let back = SimulationState::Back(*index, processed.clone(), carried.clone());
+                    for cell in cells.iter() {
+                        let mut marked = cell.clone();
+                        for tape in (0..tape_count).filter(|tape| right[*tape]) {
+                            marked.marks[tape] = true;
+                        }
+                        moves.push((from_id, back.clone(), label(&cell.to_string(), &marked.to_string(), TapeMove::Left)));
+                    }
+                    moves.push((from_id, back, label(&blank, &blank_cell(right.clone()).to_string(), TapeMove::Left)));
+                }
+                SimulationState::Back(index, processed, carried) => {
+                    let update = SimulationState::Update(*index, processed.clone(), carried.clone());
+                    for cell in cells.iter() {
+                        moves.push((from_id, update.clone(), label(&cell.to_string(), &cell.to_string(), TapeMove::Left)));
+                    }
+                }
+            }
+            for (from_id, to, label) in moves.drain(..) {
+                let to_id = add_state(&to, &mut machine, &mut ids, &mut pending);
+                machine.add_transition(from_id, to_id, label);
+            }
+        }
+        for (simulation_state, id) in ids.iter() {
+            if let SimulationState::Scan(state_id, collected) = simulation_state {
+                if self.final_states.contains(state_id) && collected.iter().all(|symbol| symbol.is_none()) {
+                    machine.make_final(*id);
+                }
+            }
+        }
+        machine
+    }
+
+    // Auxiliar function that reads every transition label of the machine.
+    // The labels that are not valid or don't have a part for every tape are ignored.
+    fn parsed_transitions(&self) -> Vec<MultiTapeTransition> {
+        let mut transitions = Vec::new();
+        let mut from_ids: Vec<&StateID> = self.states_by_id.keys().collect();
+        from_ids.sort();
+        for from_id in from_ids {
+            let mut state_transitions: Vec<(&StateID, &String)> = self.states_by_id[from_id].iter_by_transition()
+                .flat_map(|(to_id, labels)| labels.iter().map(move |label| (to_id, label)))
+                .collect();
+            state_transitions.sort();
+            for (to_id, label) in state_transitions {
+                if let Some(moves) = parse_transition_label(label).filter(|moves| moves.len() == self.tape_count) {
+                    transitions.push(MultiTapeTransition {
+                        from_id: *from_id,
+                        to_id: *to_id,
+                        moves,
+                    });
+                }
+            }
+        }
+        transitions
+    }
+}
+
+/* A configuration of a machine with k tapes, the state, the written part
+ * of every tape and the position of every head. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MultiTapeConfiguration {
+    pub state_id: StateID,
+    pub tapes: Vec<Vec<String>>,
+    pub heads: Vec<usize>,
+}
+
+/* The result of running a machine with k tapes, the configurations are in
+ * the order of the run starting with the initial one. */
+#[derive(Debug, Clone)]
+pub struct MultiTapeRun {
+    pub status: HaltingStatus,
+    pub trace: Vec<MultiTapeConfiguration>,
+}
+
+/* Transition label already split in one part for every tape. */
+struct MultiTapeTransition {
+    from_id: StateID,
+    to_id: StateID,
+    moves: Vec<(String, String, TapeMove)>,
+}
+
+impl MultiTapeTransition {
+    // Returns the configuration reached by taking the transition, if it can be taken.
+    fn apply(&self, configuration: &MultiTapeConfiguration, blank_symbol: &str) -> Option<MultiTapeConfiguration> {
+        if configuration.state_id != self.from_id {
+            return None;
+        }
+        let reads = self.moves.iter().enumerate()
+            .all(|(tape, (read, _, _))| configuration.tapes[tape][configuration.heads[tape]] == *read);
+        if !reads {
+            return None;
+        }
+        let mut tapes = configuration.tapes.clone();
+        let mut heads = configuration.heads.clone();
+        for (tape, (_, write, direction)) in self.moves.iter().enumerate() {
+            tapes[tape][heads[tape]] = write.clone();
+            heads[tape] = direction.apply(&mut tapes[tape], heads[tape], blank_symbol);
+        }
+        Some(MultiTapeConfiguration {
+            state_id: self.to_id,
+            tapes,
+            heads,
+        })
+    }
+}
+
+/* The states of the single-tape machine that simulates a machine with k
+ * tapes, see to_single_tape. The transitions are given by their index and
+ * the vectors have an element for every tape. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SimulationState {
+    Init,
+    Copy,
+    Rewind,
+    // State of the simulated machine and the symbols under the heads found.
+    Scan(StateID, Vec<Option<String>>),
+    // Transition, heads already moved and heads to mark in the next cell.
+    Update(usize, Vec<bool>, Vec<bool>),
+    // Same as Update and the heads to mark in the cell to the right.
+    Right(usize, Vec<bool>, Vec<bool>, Vec<bool>),
+    Back(usize, Vec<bool>, Vec<bool>),
+}
+
+/* A cell of the tape with k tracks, written as [a|b^] where ^ marks the
+ * symbols under the heads. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrackCell {
+    symbols: Vec<String>,
+    marks: Vec<bool>,
+}
+
+impl std::fmt::Display for TrackCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tracks: Vec<String> = self.symbols.iter().zip(self.marks.iter())
+            .map(|(symbol, mark)| if *mark { format!("{}^", symbol) } else { symbol.clone() })
+            .collect();
+        write!(f, "[{}]", tracks.join("|"))
+    }
+}
+
+// Writes a set of tapes as a string of 0 and 1, used in the names of the states.
+fn format_marks(marks: &[bool]) -> String {
+    marks.iter().map(|mark| if *mark { '1' } else { '0' }).collect()
+}
+
+/* Function that splits a transition label of a machine with k tapes in one
+ * read/write,move part for every tape, separated by |. Returns None if some
+ * part does not have that form. */
+pub fn parse_transition_label(label: &str) -> Option<Vec<(String, String, TapeMove)>> {
+    label.split('|').map(turing_machine::parse_transition_label).collect()
+}
+
+impl StateMachine for MultiTapeTuringMachine {
+    fn get_states_by_id_mut_ref(&mut self) -> &mut HashMap<StateID, State> {
+        &mut self.states_by_id
+    }
+
+    fn get_states_by_id_ref(&self) -> &HashMap<StateID, State> {
+        &self.states_by_id
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    fn get_final_states(&self) -> &HashSet<StateID> {
+        &self.final_states
+    }
+
+    fn get_initial_state_id(&self) -> &Option<StateID> {
+        &self.initial_state_id
+    }
+
+    /* The machine stops being deterministic when a state has two
+     * transitions that read the same symbols on every tape. */
+    fn add_transition(&mut self, state_id1: StateID, state_id2: StateID, input: Input) {
+        // Labels without a read/write,move part for every tape are not added.
+        let reads: Vec<String> = match parse_transition_label(&input) {
+            Some(moves) if moves.len() == self.tape_count => moves.into_iter().map(|(read, _, _)| read).collect(),
+            _ => return,
+        };
+        let state = match self.states_by_id.get_mut(&state_id1) {
+            Some(state) => state,
+            None => return,
+        };
+        // The same transition added again is not another move.
+        let repeated = state.iter_by_transition()
+            .flat_map(|(to_id, labels)| labels.iter().map(move |label| (*to_id, label)))
+            .filter(|(to_id, label)| *to_id != state_id2 || **label != input)
+            .filter_map(|(_, label)| parse_transition_label(label))
+            .any(|moves| moves.iter().map(|(read, _, _)| read).eq(reads.iter()));
+        if repeated {
+            self.deterministic = false;
+        }
+        state.add_transition(state_id2, input);
+    }
+
+    fn make_initial(&mut self, state_id: StateID) {
+        if !self.states_by_id.contains_key(&state_id) {
+            return;
+        }
+        if let Some(old_initial_state) = self.initial_state_id.and_then(|old_id| self.states_by_id.get_mut(&old_id)) {
+            old_initial_state.initial_flag = false;
+        }
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.initial_flag = true;
+            self.initial_state_id = Some(state_id);
+        }
+    }
+
+    /* Function to make a state final. */
+    fn make_final(&mut self, state_id: StateID) {
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.final_flag = true;
+            self.final_states.insert(state_id);
+        }
+    }
+}
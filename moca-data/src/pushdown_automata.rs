@@ -1,7 +1,9 @@
-use core::panic;
-use std::collections::{HashMap, HashSet, BTreeSet};
+use std::collections::{HashMap, HashSet, BTreeSet, VecDeque};
+use crate::dot::{self, DotParseError};
+use crate::grammar::{Grammar, Symbol};
 use crate::state::{Input, State, StateID};
 use crate::state_machine::StateMachine;
+use crate::turing_machine::HaltingStatus;
 
 /* Structure that represents a pushdown automaton.
  * The inisital_state_id represents the initial state
@@ -10,6 +12,9 @@ use crate::state_machine::StateMachine;
  * The string_transitions field is used to store all
  * the string transitions the automaton has.
  * The stack represents the stack of the pushdown automaton.
+ * The automaton accepts by final state, unless the
+ * empty_stack_acceptance flag is set, then it accepts
+ * when the stack is empty after reading the input.
  */
 #[derive(Debug, Clone)]
 pub struct PushdownAutomata {
@@ -18,6 +23,7 @@ pub struct PushdownAutomata {
     initial_state_id: Option<StateID>,
     final_states: HashSet<StateID>,
     initial_stack_symbol: String,
+    empty_stack_acceptance: bool,
     deterministic: bool,
 }
 
@@ -31,26 +37,48 @@ impl PushdownAutomata {
             initial_state_id: None,
             final_states: HashSet::new(),
             initial_stack_symbol,
+            empty_stack_acceptance: false,
             deterministic: true,
         }
     }
 
+    pub fn clear(&mut self) {
+        self.states_by_id.clear();
+        self.string_transitions.clear();
+        self.initial_state_id = None;
+        self.final_states.clear();
+        self.deterministic = true;
+    }
+
     // Getter for the string transitions of the automata,
     pub fn get_string_transitions(&self) -> &HashMap<(StateID, String), (StateID, String)> {
         &self.string_transitions
     }
 
+    // Getter for the symbol that is in the stack before reading the input.
+    pub fn get_initial_stack_symbol(&self) -> &str {
+        &self.initial_stack_symbol
+    }
+
+    pub fn set_initial_stack_symbol(&mut self, initial_stack_symbol: String) {
+        self.initial_stack_symbol = initial_stack_symbol;
+    }
+
+    // Returns true if the automaton accepts by empty stack instead of by final state.
+    pub fn accepts_by_empty_stack(&self) -> bool {
+        self.empty_stack_acceptance
+    }
+
+    pub fn set_empty_stack_acceptance(&mut self, empty_stack_acceptance: bool) {
+        self.empty_stack_acceptance = empty_stack_acceptance;
+    }
+
     /* Function to check if a given input string is accepted by the automata,
     * i.e. the final state is final and the input is consumed. 
-    * This implementation works with acceptting states (final states). */
+    * This implementation works with acceptting states (final states),
+    * or with the empty stack if the automaton accepts by empty stack. */
     pub fn check_input(&self, input: &mut Input) -> bool {
-        match self.initial_state_id {
-            Some(initial_id) => {
-                let mut stack: Vec<String> = vec![self.initial_stack_symbol.to_string()];
-                self.recursive_traversing(&initial_id, input, &mut stack)
-            },
-            None => todo!(), // Future implementation of an error
-        }
+        self.run(input).status == HaltingStatus::Accept
     }
 
     // Function to add a label to a state given by it's id.
@@ -60,89 +88,514 @@ impl PushdownAutomata {
         }
     }
 
-    /* Function to traverse the automaton and checking the input string, i.e.
-     * Check if the automaton accepts the input string. This function is very
-     * similar to the implementation in the finite_automaton function with 
-     * the same name, with the stack added. */
-    fn recursive_traversing(&self, state_id: &StateID, input: &mut Input, stack: &mut Vec<String>) -> bool {
-        match self.states_by_id.get(&state_id) {
-            Some(state) => {
-                print!("-------------------------------------\n");
-                print!("The stack is {:?}\n", stack);
-                print!("The input is {} and the state is {}\n", input, state_id);
-                print!("-------------------------------------");
-                if state.final_flag == true && input.is_empty() {
-                    return true;
+    /* Function that checks the input exploring the configurations of the
+     * automaton breadth first, so the accepting run returned is one with the
+     * least number of moves. The stacks are stored as shared lists where every
+     * symbol points to the ones below it, so a move only stores the symbols it
+     * pushes. Because ε-transitions can push symbols forever, the
+     * configurations whose stack can't be emptied with the input left are
+     * dropped (see StackTable), and the search gives up with the status
+     * Timeout after MAX_CONFIGURATIONS configurations have been visited.
+     * Without initial state the input is rejected. */
+    pub fn run(&self, input: &str) -> PushdownRun {
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return PushdownRun { status: HaltingStatus::Reject, trace: Vec::new() },
+        };
+        let transitions = self.parsed_transitions();
+        let mut stacks = StackTable::new(self, &transitions);
+        let initial_configuration = SearchConfiguration {
+            state_id: initial_id,
+            position: 0,
+            stack: stacks.push(&self.initial_stack_symbol, None),
+        };
+        // Every visited configuration stores the index of its predecessor to rebuild the run.
+        let mut configurations: Vec<(SearchConfiguration, Option<usize>)> = vec![(initial_configuration, None)];
+        let mut visited: HashSet<SearchConfiguration> = HashSet::new();
+        visited.insert(initial_configuration);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        queue.push_back(0);
+        let mut truncated = false;
+        while let Some(index) = queue.pop_front() {
+            let configuration = configurations[index].0;
+            let accepted = if self.empty_stack_acceptance {
+                configuration.stack.is_none()
+            } else {
+                self.final_states.contains(&configuration.state_id)
+            };
+            if accepted && configuration.position == input.len() {
+                let mut trace = Vec::new();
+                let mut current = Some(index);
+                while let Some(current_index) = current {
+                    let configuration = configurations[current_index].0;
+                    trace.push(PushdownConfiguration {
+                        state_id: configuration.state_id,
+                        remaining_input: input[configuration.position..].to_string(),
+                        stack: stacks.symbols(configuration.stack),
+                    });
+                    current = configurations[current_index].1;
                 }
-                let mut string_matches_id: Vec<u64> = Vec::new();
-                let mut stack_matches: Vec<String> = Vec::new();
-                let mut string_ref = "";
-                let mut string_len_max = 0;
-                let mut accepted_bool = false;
-                for (id, transition) in state.iter_by_transition() {
-                    for string in transition.iter() {
-                        if string == "ε" {
-                            accepted_bool = accepted_bool || self.recursive_traversing(&id, &mut input.clone(), stack);
-                            continue;
-                        }
-                        let string_transitions: Vec<&str> = string.split(';').collect();
-                        let stack_transition: Vec<_> = string_transitions[1].split('/').collect();
-                        if let Some(value) = stack.get(stack.len()-1) {
-                            if stack_transition[0] != *value {
-                               continue;
-                            }
-                        }
-                        if input.starts_with(string_transitions[0]) {
-                            if string.len() == string_len_max {
-                                string_matches_id.push(*id);
-                                stack_matches.push((string_transitions[1]).to_string());
-                            }
-                            else if string.len() > string_len_max {
-                                string_len_max = string.len();
-                                string_ref = string_transitions[0];
-                                string_matches_id.clear();
-                                stack_matches.clear();
-                                string_matches_id.push(*id);
-                                stack_matches.push((string_transitions[1]).to_string());
-                            }
-                        }
-                        if (input.trim() == string_ref.trim()) && stack.len() == 1 {
-                            stack_matches.push(string.clone());
-                            string_matches_id.push(*id);
+                trace.reverse();
+                return PushdownRun { status: HaltingStatus::Accept, trace };
+            }
+            for transition in transitions.iter().filter(|t| t.from_id == configuration.state_id) {
+                let next = match stacks.apply(transition, &configuration, input) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                if visited.contains(&next) || !stacks.can_finish(&next, input) {
+                    continue;
+                }
+                if visited.len() >= MAX_CONFIGURATIONS {
+                    truncated = true;
+                    continue;
+                }
+                visited.insert(next);
+                configurations.push((next, Some(index)));
+                queue.push_back(configurations.len() - 1);
+            }
+        }
+        let status = if truncated { HaltingStatus::Timeout } else { HaltingStatus::Reject };
+        PushdownRun { status, trace: Vec::new() }
+    }
+
+    /* Function that builds a context-free grammar that generates the language
+     * accepted by the automaton, using the triple construction. The nonterminal
+     * [p,X,q] generates the strings that take the automaton from the state p
+     * to the state q popping the symbol X from the stack. The construction
+     * needs an automaton that accepts by empty stack and pops exactly one
+     * symbol in every move, so first:
+     *  - A new initial state pushes a new bottom symbol under the initial
+     *    stack symbol, and a new state empties the stack from the final states.
+     *    If the automaton already accepts by empty stack, the bottom symbol
+     *    is popped from any state instead.
+     *  - The transitions that don't pop are replaced by one for every stack
+     *    symbol X that pops X and pushes it back.
+     *  - The transitions that pop several symbols are split in several moves.
+     * The states with the same name are told apart adding apostrophes. The
+     * grammar is cleaned of useless symbols, so only the triples that are
+     * used in some derivation are left. */
+    pub fn to_grammar(&self) -> Grammar {
+        let mut grammar = Grammar::new("S");
+        let initial_id = match self.initial_state_id {
+            Some(id) => id,
+            None => return grammar,
+        };
+        let transitions = self.parsed_transitions();
+        let mut stack_symbols: BTreeSet<Symbol> = BTreeSet::new();
+        stack_symbols.insert(self.initial_stack_symbol.clone());
+        for transition in transitions.iter() {
+            stack_symbols.extend(transition.pop.iter().cloned());
+            stack_symbols.extend(transition.push.iter().cloned());
+        }
+        // The states of the moves are indices in state_names, every state has a different name.
+        let mut ids: Vec<&StateID> = self.states_by_id.keys().collect();
+        ids.sort();
+        let mut state_names: Vec<String> = Vec::new();
+        let mut indices: HashMap<StateID, usize> = HashMap::new();
+        for id in ids {
+            indices.insert(*id, state_names.len());
+            state_names.push(fresh_name(&self.states_by_id[id].name, &state_names));
+        }
+        let start_state = state_names.len();
+        state_names.push(fresh_name("qs", &state_names));
+        let empty_state = state_names.len();
+        state_names.push(fresh_name("qe", &state_names));
+        let bottom_symbol = fresh_name("⊥", &stack_symbols.iter().cloned().collect::<Vec<_>>());
+        stack_symbols.insert(bottom_symbol.clone());
+
+        // Moves (p, input, X, r, push) that pop exactly one symbol X.
+        let mut moves: Vec<(usize, String, Symbol, usize, Vec<Symbol>)> = vec![(
+            start_state, "ε".to_string(), bottom_symbol.clone(),
+            indices[&initial_id], vec![self.initial_stack_symbol.clone(), bottom_symbol.clone()],
+        )];
+        if self.empty_stack_acceptance {
+            for id in self.states_by_id.keys() {
+                moves.push((indices[id], "ε".to_string(), bottom_symbol.clone(), empty_state, Vec::new()));
+            }
+        } else {
+            for final_id in self.final_states.iter() {
+                for symbol in stack_symbols.iter() {
+                    moves.push((indices[final_id], "ε".to_string(), symbol.clone(), empty_state, Vec::new()));
+                    moves.push((empty_state, "ε".to_string(), symbol.clone(), empty_state, Vec::new()));
+                }
+            }
+        }
+        for transition in transitions.iter() {
+            let from = indices[&transition.from_id];
+            let to = indices[&transition.to_id];
+            match transition.pop.len() {
+                0 => for symbol in stack_symbols.iter() {
+                    let mut push = transition.push.clone();
+                    push.push(symbol.clone());
+                    moves.push((from, transition.input.clone(), symbol.clone(), to, push));
+                },
+                pop_len => {
+                    let mut current = from;
+                    let mut input = transition.input.clone();
+                    for (index, symbol) in transition.pop.iter().enumerate() {
+                        if index + 1 == pop_len {
+                            moves.push((current, input.clone(), symbol.clone(), to, transition.push.clone()));
+                        } else {
+                            let next = state_names.len();
+                            state_names.push(fresh_name(&format!("{}'", state_names[from]), &state_names));
+                            moves.push((current, input.clone(), symbol.clone(), next, Vec::new()));
+                            current = next;
+                            input = "ε".to_string();
                         }
                     }
                 }
-                if (string_len_max == 0 && string_matches_id.is_empty()) &&
-                    accepted_bool != true { return false; }
-                input.replace_range(0..string_ref.len(),"");
-                let mut count = 0;
-                for id in string_matches_id {
-                    let string = stack_matches[count].clone();
-                    let stack_string: Vec<_> = string.split('/').map(|s| s.to_string()).collect();
-                    self.stack_transition(stack_string[0].clone(), stack_string[1].clone(), stack);
-                    accepted_bool = accepted_bool || self.recursive_traversing(&id, &mut input.clone(), stack);
-                    count += count + 1;
+            }
+        }
+
+        let triple = |p: usize, symbol: &str, q: usize| format!("[{},{},{}]", state_names[p], symbol, state_names[q]);
+        for state in 0..state_names.len() {
+            grammar.add_production("S", vec![triple(start_state, &bottom_symbol, state)]);
+        }
+        for (from, input, symbol, to, push) in moves.iter() {
+            let input: Vec<Symbol> = if input == "ε" { Vec::new() } else { vec![input.clone()] };
+            if push.is_empty() {
+                grammar.add_production(&triple(*from, symbol, *to), input);
+                continue;
+            }
+            // Every sequence of states q1 ... qk for the pushed symbols Y1 ... Yk.
+            let mut sequences: Vec<Vec<usize>> = vec![Vec::new()];
+            for _ in push.iter() {
+                sequences = sequences.into_iter()
+                    .flat_map(|sequence| (0..state_names.len()).map(move |state| {
+                        let mut sequence = sequence.clone();
+                        sequence.push(state);
+                        sequence
+                    }))
+                    .collect();
+            }
+            for sequence in sequences {
+                let mut body = input.clone();
+                let mut current = *to;
+                for (pushed_symbol, next) in push.iter().zip(sequence.iter()) {
+                    body.push(triple(current, pushed_symbol, *next));
+                    current = *next;
+                }
+                for nonterminal in body.iter().skip(input.len()) {
+                    grammar.add_nonterminal(nonterminal);
+                }
+                grammar.add_production(&triple(*from, symbol, current), body);
+            }
+        }
+        grammar.remove_useless_symbols()
+    }
+
+    // Auxiliar function that reads every transition label of the automaton.
+    // The labels that are not valid are ignored.
+    fn parsed_transitions(&self) -> Vec<ParsedTransition> {
+        let mut transitions = Vec::new();
+        for (from_id, state) in self.states_by_id.iter() {
+            for (to_id, labels) in state.iter_by_transition() {
+                for label in labels {
+                    if let Some((input, pop, push)) = parse_transition_label(label) {
+                        transitions.push(ParsedTransition {
+                            from_id: *from_id,
+                            to_id: *to_id,
+                            input,
+                            pop: split_stack_symbols(&pop),
+                            push: split_stack_symbols(&push),
+                        });
+                    }
                 }
-                return accepted_bool;
             }
-            None => {return false;},
         }
+        transitions
+    }
+
+    /* Function that writes the automaton as a Graphviz digraph, see dot::to_dot. */
+    pub fn to_dot(&self) -> String {
+        dot::to_dot(self)
+    }
+
+    /* Function that reads an automaton from a Graphviz digraph, with one
+     * transition label input;pop/push per line of the labels of the edges. */
+    pub fn from_dot(text: &str, initial_stack_symbol: String) -> Result<PushdownAutomata, DotParseError> {
+        let mut automaton = PushdownAutomata::new(initial_stack_symbol);
+        dot::parse_dot(text)?.build_machine(&mut automaton, false)?;
+        Ok(automaton)
+    }
+}
+
+/* Maximum number of configurations visited while checking an input. */
+pub const MAX_CONFIGURATIONS: usize = 100_000;
+
+/* The result of checking an input on a pushdown automaton. The trace is
+ * the accepting run when the status is Accept and empty otherwise, and the
+ * status is Timeout when the search gave up before deciding the input. */
+#[derive(Debug, Clone)]
+pub struct PushdownRun {
+    pub status: HaltingStatus,
+    pub trace: Vec<PushdownConfiguration>,
+}
+
+/* A configuration (instantaneous description) of a pushdown automaton.
+ * The top of the stack is the last element of the vector. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PushdownConfiguration {
+    pub state_id: StateID,
+    pub remaining_input: String,
+    pub stack: Vec<String>,
+}
+
+/* Transition label already split in its three parts, the pop and push
+ * strings are stored as symbols with the leftmost symbol as the top. */
+struct ParsedTransition {
+    from_id: StateID,
+    to_id: StateID,
+    input: String,
+    pop: Vec<String>,
+    push: Vec<String>,
+}
+
+/* A configuration while checking an input, the position is the byte of the
+ * input where the remaining input starts and the stack is its top symbol
+ * in a StackTable, None for the empty stack. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SearchConfiguration {
+    state_id: StateID,
+    position: usize,
+    stack: Option<usize>,
+}
+
+/* Stack symbol stored once for all the stacks that share it and the
+ * symbols below it. The cost is the least number of input symbols that
+ * have to be read to pop it and the symbols below it. */
+struct StackNode {
+    symbol: String,
+    below: Option<usize>,
+    cost: usize,
+}
+
+/* Table of the stacks of the configurations visited while checking an
+ * input. Equal stacks are stored once, so they can be compared by their top.
+ * The table also knows when the stack has to be emptied to accept:
+ *  - When the automaton accepts by empty stack.
+ *  - When every transition to a final state pops the initial stack symbol
+ *    and that symbol is only pushed back at the bottom, as in the automata
+ *    built from grammars. Then, out of the final states, every symbol above
+ *    the bottom has to be popped before accepting.
+ * The symbols that can't be popped at all cost usize::MAX. */
+struct StackTable {
+    nodes: Vec<StackNode>,
+    ids: HashMap<(String, Option<usize>), usize>,
+    pop_costs: HashMap<String, usize>,
+    bottom_symbol: Option<String>,
+    must_empty: bool,
+    final_states: HashSet<StateID>,
+}
+
+impl StackTable {
+    fn new(automaton: &PushdownAutomata, transitions: &[ParsedTransition]) -> Self {
+        let initial_symbol = &automaton.initial_stack_symbol;
+        let bottom_guarded = transitions.iter().all(|transition| {
+            let enters_final = automaton.final_states.contains(&transition.to_id);
+            let pushes_initial = transition.push.iter().any(|symbol| symbol == initial_symbol);
+            (!enters_final || transition.pop.first() == Some(initial_symbol))
+                && (!pushes_initial || (transition.pop == [initial_symbol.clone()]
+                    && transition.push.iter().position(|symbol| symbol == initial_symbol) == Some(transition.push.len() - 1)))
+        });
+        StackTable {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            pop_costs: pop_costs(transitions),
+            bottom_symbol: (bottom_guarded && !automaton.empty_stack_acceptance).then(|| initial_symbol.clone()),
+            must_empty: automaton.empty_stack_acceptance || bottom_guarded,
+            final_states: automaton.final_states.clone(),
+        }
+    }
+
+    // Returns the stack with the symbol pushed on top of the given one.
+    fn push(&mut self, symbol: &str, below: Option<usize>) -> Option<usize> {
+        let key = (symbol.to_string(), below);
+        if let Some(id) = self.ids.get(&key) {
+            return Some(*id);
+        }
+        let cost = if below.is_none() && self.bottom_symbol.as_deref() == Some(symbol) {
+            0
+        } else {
+            self.pop_costs.get(symbol).copied().unwrap_or(usize::MAX)
+        };
+        let below_cost = below.map_or(0, |below| self.nodes[below].cost);
+        self.nodes.push(StackNode { symbol: symbol.to_string(), below, cost: cost.saturating_add(below_cost) });
+        self.ids.insert(key, self.nodes.len() - 1);
+        Some(self.nodes.len() - 1)
+    }
+
+    // Returns the configuration reached by taking the transition, if it can be taken.
+    fn apply(&mut self, transition: &ParsedTransition, configuration: &SearchConfiguration, input: &str) -> Option<SearchConfiguration> {
+        let position = if is_empty_symbol(&transition.input) {
+            configuration.position
+        } else if input[configuration.position..].starts_with(transition.input.as_str()) {
+            configuration.position + transition.input.len()
+        } else {
+            return None;
+        };
+        let mut stack = configuration.stack;
+        for symbol in transition.pop.iter() {
+            let node = &self.nodes[stack?];
+            if &node.symbol != symbol {
+                return None;
+            }
+            stack = node.below;
+        }
+        for symbol in transition.push.iter().rev() {
+            stack = self.push(symbol, stack);
+        }
+        Some(SearchConfiguration { state_id: transition.to_id, position, stack })
+    }
+
+    // Returns false if the configuration has to empty its stack and the input left is too short for it.
+    fn can_finish(&self, configuration: &SearchConfiguration, input: &str) -> bool {
+        if !self.must_empty || (self.bottom_symbol.is_some() && self.final_states.contains(&configuration.state_id)) {
+            return true;
+        }
+        let cost = configuration.stack.map_or(0, |stack| self.nodes[stack].cost);
+        cost <= input[configuration.position..].chars().count()
     }
 
-    // Auxiliar function to modify the stack given a stack change 
-    fn stack_transition<'a> (&self, string1: String, string2: String, stack: &mut Vec<String>) {
-        if string2.contains(&string1) && string2.len() > string1.len() {
-            let (first,_) = string2.split_at(string1.len());
-            stack.push(first.to_string())
+    // Returns the symbols of the stack with the top as the last element.
+    fn symbols(&self, stack: Option<usize>) -> Vec<String> {
+        let mut symbols = Vec::new();
+        let mut current = stack;
+        while let Some(index) = current {
+            symbols.push(self.nodes[index].symbol.clone());
+            current = self.nodes[index].below;
         }
-        else if string2 == "ε" {
-            stack.pop();
+        symbols.reverse();
+        symbols
+    }
+}
+
+/* Function that computes for every stack symbol the least number of input
+ * symbols read to pop it, counting the symbols pushed in its place. The
+ * symbols popped together with others cost 0, since the input read can be
+ * counted for any of them. */
+fn pop_costs(transitions: &[ParsedTransition]) -> HashMap<String, usize> {
+    let mut costs: HashMap<String, usize> = HashMap::new();
+    for transition in transitions.iter().filter(|transition| transition.pop.len() > 1) {
+        for symbol in transition.pop.iter() {
+            costs.insert(symbol.clone(), 0);
         }
-        else if string1 != string2 {
-            stack.pop();
-            stack.push(string2)
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for transition in transitions.iter().filter(|transition| transition.pop.len() == 1) {
+            let input_cost = if is_empty_symbol(&transition.input) { 0 } else { transition.input.chars().count() };
+            let cost = transition.push.iter().fold(input_cost, |cost, symbol| {
+                cost.saturating_add(costs.get(symbol).copied().unwrap_or(usize::MAX))
+            });
+            let current = costs.entry(transition.pop[0].clone()).or_insert(usize::MAX);
+            if cost < *current {
+                *current = cost;
+                changed = true;
+            }
         }
     }
+    costs
+}
+
+// Returns the name followed by apostrophes until it is not one of the used names.
+fn fresh_name(name: &str, used_names: &[String]) -> String {
+    let mut name = name.to_string();
+    while used_names.contains(&name) {
+        name.push('\'');
+    }
+    name
+}
+
+// Returns true if a part of a transition label represents the empty string.
+fn is_empty_symbol(string: &str) -> bool {
+    string.trim().is_empty() || string.trim() == "ε"
+}
+
+/* Function that splits a transition label of the form input;pop/push in
+ * its three parts. The label ε alone is a shortcut for ε;ε/ε, and an empty
+ * input is read as ε. The stack symbols with ; or / are written between
+ * brackets, e.g. a;[/]/ε pops the symbol /, and the input is everything
+ * before the stack change, so ;;[;]/ε reads the symbol ;.
+ * Returns None if the label does not have that form. */
+pub fn parse_transition_label(label: &str) -> Option<(String, String, String)> {
+    let label = label.trim();
+    if label == "ε" {
+        return Some(("ε".to_string(), "ε".to_string(), "ε".to_string()));
+    }
+    let (input, stack_change) = split_transition_label(label)?;
+    let separator = unbracketed_position(stack_change, '/')?;
+    let (pop, push) = (&stack_change[..separator], &stack_change[separator + 1..]);
+    if pop.trim().is_empty() || push.trim().is_empty() || unbracketed_position(push, '/').is_some() {
+        return None;
+    }
+    let input = if is_empty_symbol(input) { "ε" } else { input };
+    Some((input.to_string(), pop.trim().to_string(), push.trim().to_string()))
+}
+
+// Auxiliar function that splits a label in the input and the stack change at the first ; after
+// which there is no other ; out of brackets.
+fn split_transition_label(label: &str) -> Option<(&str, &str)> {
+    label.char_indices()
+        .filter(|(_, c)| *c == ';')
+        .map(|(index, _)| (&label[..index], &label[index + 1..]))
+        .find(|(_, stack_change)| unbracketed_position(stack_change, ';').is_none())
+}
+
+// Returns the position of the first occurrence of the character that is not in a group between
+// brackets, like [qAp] or <A>, as split_stack_symbols reads them.
+fn unbracketed_position(string: &str, character: char) -> Option<usize> {
+    let mut closing: Option<char> = None;
+    for (index, c) in string.char_indices() {
+        match closing {
+            Some(closing_char) if c == closing_char => closing = None,
+            Some(_) => (),
+            None if c == character => return Some(index),
+            None if c == '[' => closing = Some(']'),
+            None if c == '<' => closing = Some('>'),
+            None => (),
+        }
+    }
+    None
+}
+
+/* Function that splits a string of stack symbols in its symbols.
+ * If the string has whitespace the symbols are the words, otherwise every
+ * character is a symbol, except for the groups between brackets like [qAp]
+ * and the ones followed by apostrophes like A'. The symbol ε is omitted. */
+pub fn split_stack_symbols(string: &str) -> Vec<String> {
+    let mut symbols = Vec::new();
+    if string.split_whitespace().count() > 1 {
+        for word in string.split_whitespace() {
+            if word != "ε" {
+                symbols.push(word.to_string());
+            }
+        }
+        return symbols;
+    }
+    let mut chars = string.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut symbol = c.to_string();
+        if c == '[' || c == '<' {
+            let closing = if c == '[' { ']' } else { '>' };
+            for next in chars.by_ref() {
+                symbol.push(next);
+                if next == closing {
+                    break;
+                }
+            }
+        }
+        while let Some('\'') = chars.peek() {
+            symbol.push('\'');
+            chars.next();
+        }
+        if symbol != "ε" {
+            symbols.push(symbol);
+        }
+    }
+    symbols
 }
 
 impl StateMachine for PushdownAutomata {
@@ -169,13 +622,20 @@ impl StateMachine for PushdownAutomata {
     /* The implementation for finite automaton checks if the automaton
      * is deterministic or not. */
     fn add_transition(&mut self, state_id1: StateID, state_id2: StateID, input: Input) {
+        // Labels that are not of the form input;pop/push are not added.
+        if parse_transition_label(&input).is_none() {
+            return;
+        }
         let mut input_clone = input.clone();
         if input == "ε" {
             // This is for ease to use
             input_clone = "ε;ε".to_string();
             self.deterministic = false;
         }
-        let transition: Vec<_> = input_clone.split(';').collect();
+        let transition = match split_transition_label(input_clone.trim()) {
+            Some((input, stack_change)) => [input, stack_change],
+            None => return,
+        };
         // The second condition dictates that the automata is non deterministic, because if the
         // stack transition is a ε-transition and there exists another input transition with the
         // same symbol, then if the input is equal (the get returns a value) then the automata can
@@ -0,0 +1,381 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::finite_automata::FiniteAutomata;
+use crate::state::StateID;
+use crate::state_machine::StateMachine;
+
+/* Error found when parsing a regular expression, the position is the index
+ * of the character, counted from 0, where it was found. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RegexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "position {}: {}", self.position, self.message)
+    }
+}
+
+/* Structure that represents a regular expression. Empty is the expression
+ * of the empty language, written ∅, and Epsilon the one of the empty word,
+ * written ε or λ. A symbol is any other character, the characters used by
+ * the syntax can be written as symbols escaping them with a backslash, e.g.
+ * \+. The union is written + or |, the concatenation by juxtaposition and
+ * the Kleene star with *, e.g. (a+b)*abb. Symbols read from the transitions
+ * of an automaton can have more than one character. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegularExpression {
+    Empty,
+    Epsilon,
+    Symbol(String),
+    Concat(Box<RegularExpression>, Box<RegularExpression>),
+    Union(Box<RegularExpression>, Box<RegularExpression>),
+    Star(Box<RegularExpression>),
+}
+
+impl RegularExpression {
+    /* Function that parses a regular expression. The star binds tighter than
+     * the concatenation and the concatenation tighter than the union, the
+     * spaces are ignored. */
+    pub fn parse(text: &str) -> Result<RegularExpression, RegexParseError> {
+        let mut parser = RegexParser {
+            chars: text.chars().collect(),
+            position: 0,
+        };
+        parser.skip_spaces();
+        if parser.position == parser.chars.len() {
+            return Err(parser.error("the expression is empty"));
+        }
+        let expression = parser.parse_union()?;
+        if parser.position < parser.chars.len() {
+            return Err(parser.error(&format!("unexpected {}", parser.chars[parser.position])));
+        }
+        Ok(expression)
+    }
+
+    /* The union of two expressions, simplified when one of them is ∅ or
+     * both are the same. */
+    pub fn union(left: RegularExpression, right: RegularExpression) -> RegularExpression {
+        match (left, right) {
+            (RegularExpression::Empty, other) | (other, RegularExpression::Empty) => other,
+            (left, right) if left == right => left,
+            (left, right) => RegularExpression::Union(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /* The concatenation of two expressions, simplified when one of them is
+     * ∅ or ε. */
+    pub fn concat(left: RegularExpression, right: RegularExpression) -> RegularExpression {
+        match (left, right) {
+            (RegularExpression::Empty, _) | (_, RegularExpression::Empty) => RegularExpression::Empty,
+            (RegularExpression::Epsilon, other) | (other, RegularExpression::Epsilon) => other,
+            (left, right) => RegularExpression::Concat(Box::new(left), Box::new(right)),
+        }
+    }
+
+    /* The Kleene star of an expression, simplified when it is ∅, ε or
+     * already a star. */
+    pub fn star(expression: RegularExpression) -> RegularExpression {
+        match expression {
+            RegularExpression::Empty | RegularExpression::Epsilon => RegularExpression::Epsilon,
+            RegularExpression::Star(inner) => RegularExpression::Star(inner),
+            other => RegularExpression::Star(Box::new(other)),
+        }
+    }
+
+    /* Function that builds a finite automaton that recognizes the language
+     * of the expression with Thompson's construction. Every subexpression
+     * gives a part with one entry and one exit state joined by
+     * ε-transitions, so the automaton is not deterministic in general. */
+    pub fn to_finite_automata(&self) -> FiniteAutomata {
+        let mut automaton = FiniteAutomata::new();
+        let mut next_id = 0;
+        let (entry, exit) = self.thompson(&mut automaton, &mut next_id);
+        automaton.make_initial(entry);
+        automaton.make_final(exit);
+        automaton
+    }
+
+    // Auxiliar function of Thompson's construction, adds the states of the expression and
+    // returns its entry and exit states.
+    fn thompson(&self, automaton: &mut FiniteAutomata, next_id: &mut StateID) -> (StateID, StateID) {
+        match self {
+            RegularExpression::Empty => (add_thompson_state(automaton, next_id), add_thompson_state(automaton, next_id)),
+            RegularExpression::Epsilon | RegularExpression::Symbol(_) => {
+                let entry = add_thompson_state(automaton, next_id);
+                let exit = add_thompson_state(automaton, next_id);
+                let label = match self {
+                    RegularExpression::Symbol(symbol) => symbol.clone(),
+                    _ => "ε".to_string(),
+                };
+                automaton.add_transition(entry, exit, label);
+                (entry, exit)
+            }
+            RegularExpression::Concat(left, right) => {
+                let (left_entry, left_exit) = left.thompson(automaton, next_id);
+                let (right_entry, right_exit) = right.thompson(automaton, next_id);
+                automaton.add_transition(left_exit, right_entry, "ε".to_string());
+                (left_entry, right_exit)
+            }
+            RegularExpression::Union(left, right) => {
+                let entry = add_thompson_state(automaton, next_id);
+                let (left_entry, left_exit) = left.thompson(automaton, next_id);
+                let (right_entry, right_exit) = right.thompson(automaton, next_id);
+                let exit = add_thompson_state(automaton, next_id);
+                automaton.add_transition(entry, left_entry, "ε".to_string());
+                automaton.add_transition(entry, right_entry, "ε".to_string());
+                automaton.add_transition(left_exit, exit, "ε".to_string());
+                automaton.add_transition(right_exit, exit, "ε".to_string());
+                (entry, exit)
+            }
+            RegularExpression::Star(inner) => {
+                let entry = add_thompson_state(automaton, next_id);
+                let (inner_entry, inner_exit) = inner.thompson(automaton, next_id);
+                let exit = add_thompson_state(automaton, next_id);
+                automaton.add_transition(entry, inner_entry, "ε".to_string());
+                automaton.add_transition(entry, exit, "ε".to_string());
+                automaton.add_transition(inner_exit, inner_entry, "ε".to_string());
+                automaton.add_transition(inner_exit, exit, "ε".to_string());
+                (entry, exit)
+            }
+        }
+    }
+
+    /* Function that gives a regular expression of the language of a finite
+     * automaton by state elimination. A new entry state goes by ε to the
+     * initial state and every final state goes by ε to a new exit state, then
+     * the states of the automaton are removed in order of id, replacing
+     * every path p -> k -> q by the transition R(p,k) R(k,k)* R(k,q). The
+     * expression left between the entry and the exit is the result, ∅ if the
+     * automaton has no initial state. The transitions labeled ε or with the
+     * empty string are ε-transitions. */
+    pub fn from_finite_automata(automaton: &FiniteAutomata) -> RegularExpression {
+        let initial_id = match automaton.get_initial_state_id() {
+            Some(id) => *id,
+            None => return RegularExpression::Empty,
+        };
+        let mut edges: EliminationEdges = BTreeMap::new();
+        let mut ids: Vec<&StateID> = automaton.get_states_by_id_ref().keys().collect();
+        ids.sort();
+        add_edge(&mut edges, (None, Some(initial_id)), RegularExpression::Epsilon);
+        for id in ids.iter() {
+            let state = &automaton.get_states_by_id_ref()[id];
+            let mut transitions: Vec<(&StateID, &String)> = state.iter_by_transition()
+                .flat_map(|(to_id, labels)| labels.iter().map(move |label| (to_id, label)))
+                .collect();
+            transitions.sort();
+            for (to_id, label) in transitions {
+                let expression = if label == "ε" || label.is_empty() {
+                    RegularExpression::Epsilon
+                } else {
+                    RegularExpression::Symbol(label.clone())
+                };
+                add_edge(&mut edges, (Some(**id), Some(*to_id)), expression);
+            }
+            if automaton.get_final_states().contains(id) {
+                add_edge(&mut edges, (Some(**id), None), RegularExpression::Epsilon);
+            }
+        }
+        for id in ids {
+            let eliminated = Some(*id);
+            let loop_expression = RegularExpression::star(edges.remove(&(eliminated, eliminated)).unwrap_or(RegularExpression::Empty));
+            let incoming: Vec<(Option<StateID>, RegularExpression)> = edges.iter()
+                .filter(|((from, to), _)| *to == eliminated && *from != eliminated)
+                .map(|((from, _), expression)| (*from, expression.clone()))
+                .collect();
+            let outgoing: Vec<(Option<StateID>, RegularExpression)> = edges.iter()
+                .filter(|((from, to), _)| *from == eliminated && *to != eliminated)
+                .map(|((_, to), expression)| (*to, expression.clone()))
+                .collect();
+            edges.retain(|(from, to), _| *from != eliminated && *to != eliminated);
+            for (from, incoming_expression) in incoming.iter() {
+                for (to, outgoing_expression) in outgoing.iter() {
+                    let path = RegularExpression::concat(
+                        RegularExpression::concat(incoming_expression.clone(), loop_expression.clone()),
+                        outgoing_expression.clone(),
+                    );
+                    add_edge(&mut edges, (*from, *to), path);
+                }
+            }
+        }
+        edges.remove(&(None, None)).unwrap_or(RegularExpression::Empty)
+    }
+
+    // Precedence of the expression when it is written, the higher the tighter it binds.
+    fn precedence(&self) -> u8 {
+        match self {
+            RegularExpression::Union(_, _) => 0,
+            RegularExpression::Concat(_, _) => 1,
+            RegularExpression::Symbol(symbol) if symbol.chars().count() > 1 => 1,
+            _ => 2,
+        }
+    }
+
+    // Auxiliar function that writes a subexpression, between parentheses if it binds looser
+    // than the position where it is.
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+}
+
+/* The expressions are written with + for the union and with the
+ * parentheses that are needed, e.g. (a+b)*abb. */
+impl fmt::Display for RegularExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegularExpression::Empty => write!(f, "∅"),
+            RegularExpression::Epsilon => write!(f, "ε"),
+            RegularExpression::Symbol(symbol) => {
+                for c in symbol.chars() {
+                    if is_reserved(c) {
+                        write!(f, "\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
+                Ok(())
+            }
+            RegularExpression::Union(left, right) => {
+                left.fmt_operand(f, 0)?;
+                write!(f, "+")?;
+                right.fmt_operand(f, 0)
+            }
+            RegularExpression::Concat(left, right) => {
+                left.fmt_operand(f, 1)?;
+                right.fmt_operand(f, 1)
+            }
+            RegularExpression::Star(inner) => {
+                inner.fmt_operand(f, 2)?;
+                write!(f, "*")
+            }
+        }
+    }
+}
+
+// Recursive descent parser of the regular expressions, it reads the characters from position.
+struct RegexParser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl RegexParser {
+    fn error(&self, message: &str) -> RegexParseError {
+        RegexParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    // Next character that is not a space, without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        self.skip_spaces();
+        self.chars.get(self.position).copied()
+    }
+
+    fn parse_union(&mut self) -> Result<RegularExpression, RegexParseError> {
+        let mut expression = self.parse_concat()?;
+        while let Some('+') | Some('|') = self.peek() {
+            self.position += 1;
+            let right = self.parse_concat()?;
+            expression = RegularExpression::Union(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_concat(&mut self) -> Result<RegularExpression, RegexParseError> {
+        let mut expression = self.parse_star()?;
+        while let Some(c) = self.peek() {
+            if c == '+' || c == '|' || c == ')' {
+                break;
+            }
+            let right = self.parse_star()?;
+            expression = RegularExpression::Concat(Box::new(expression), Box::new(right));
+        }
+        Ok(expression)
+    }
+
+    fn parse_star(&mut self) -> Result<RegularExpression, RegexParseError> {
+        let mut expression = self.parse_atom()?;
+        while let Some('*') = self.peek() {
+            self.position += 1;
+            expression = RegularExpression::Star(Box::new(expression));
+        }
+        Ok(expression)
+    }
+
+    fn parse_atom(&mut self) -> Result<RegularExpression, RegexParseError> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(self.error("expected a symbol or ( at the end of the expression")),
+        };
+        match c {
+            '(' => {
+                self.position += 1;
+                let expression = self.parse_union()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("expected )"));
+                }
+                self.position += 1;
+                Ok(expression)
+            }
+            '\\' => {
+                self.position += 1;
+                match self.chars.get(self.position) {
+                    Some(escaped) => {
+                        self.position += 1;
+                        Ok(RegularExpression::Symbol(escaped.to_string()))
+                    }
+                    None => Err(self.error("expected a character after \\")),
+                }
+            }
+            'ε' | 'λ' => {
+                self.position += 1;
+                Ok(RegularExpression::Epsilon)
+            }
+            '∅' => {
+                self.position += 1;
+                Ok(RegularExpression::Empty)
+            }
+            '+' | '|' | '*' | ')' => Err(self.error(&format!("expected a symbol or ( before {}", c))),
+            _ => {
+                self.position += 1;
+                Ok(RegularExpression::Symbol(c.to_string()))
+            }
+        }
+    }
+}
+
+// Transitions of the state elimination, the entry and exit states are None and the states of
+// the automaton Some(id).
+type EliminationEdges = BTreeMap<(Option<StateID>, Option<StateID>), RegularExpression>;
+
+// Auxiliar function that adds an expression to a transition of the state elimination, in
+// union with the expression it had.
+fn add_edge(edges: &mut EliminationEdges, key: (Option<StateID>, Option<StateID>), expression: RegularExpression) {
+    let old = edges.remove(&key).unwrap_or(RegularExpression::Empty);
+    edges.insert(key, RegularExpression::union(old, expression));
+}
+
+// Auxiliar function that adds a state named after its id to the automaton of Thompson's construction.
+fn add_thompson_state(automaton: &mut FiniteAutomata, next_id: &mut StateID) -> StateID {
+    let id = *next_id;
+    automaton.add_state_with_id_label(id, &format!("q{}", id));
+    *next_id += 1;
+    id
+}
+
+// The characters that have to be escaped to be read as symbols.
+fn is_reserved(c: char) -> bool {
+    matches!(c, '(' | ')' | '+' | '|' | '*' | '\\' | 'ε' | 'λ' | '∅') || c.is_whitespace()
+}
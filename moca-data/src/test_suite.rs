@@ -0,0 +1,178 @@
+use std::fmt;
+use crate::machine_file::FileMachine;
+
+/* Error found when parsing a test suite, with the line where it was found. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestSuiteError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TestSuiteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/* The result a test case expects from the machine. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expectation {
+    Accept,
+    Reject,
+}
+
+impl fmt::Display for Expectation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expectation::Accept => write!(f, "accept"),
+            Expectation::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/* A word with the result expected for it and the line of the suite where
+ * it was written. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub word: String,
+    pub expected: Expectation,
+    pub line: usize,
+}
+
+/* Structure that represents a test suite, a list of words that a machine
+ * must accept or reject. It is written one case per line, the keyword accept
+ * or reject followed by the word, ε, λ or nothing for the empty word. The
+ * empty lines and the lines that start with # are skipped. For example:
+ *     # Words with an even number of a.
+ *     accept ε
+ *     accept abba
+ *     reject ab */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestSuite {
+    pub cases: Vec<TestCase>,
+}
+
+impl TestSuite {
+    pub fn parse(text: &str) -> Result<TestSuite, TestSuiteError> {
+        let mut cases = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, word) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let expected = match keyword {
+                "accept" => Expectation::Accept,
+                "reject" => Expectation::Reject,
+                _ => return Err(TestSuiteError {
+                    line: line_number,
+                    message: format!("expected accept or reject, found {}", keyword),
+                }),
+            };
+            let word = match word.trim() {
+                "ε" | "λ" => "",
+                word => word,
+            };
+            if word.contains(char::is_whitespace) {
+                return Err(TestSuiteError {
+                    line: line_number,
+                    message: format!("the word {} has spaces", word),
+                });
+            }
+            cases.push(TestCase {
+                word: word.to_string(),
+                expected,
+                line: line_number,
+            });
+        }
+        Ok(TestSuite { cases })
+    }
+
+    /* Function that runs every case of the suite on the machine, see
+     * FileMachine::accepts. */
+    pub fn run(&self, machine: &FileMachine) -> TestSuiteReport {
+        TestSuiteReport {
+            results: self.cases.iter()
+                .map(|case| TestCaseResult {
+                    case: case.clone(),
+                    accepted: machine.accepts(&case.word),
+                })
+                .collect(),
+        }
+    }
+}
+
+/* The suite is written back in the format it is parsed from, without the
+ * comments. */
+impl fmt::Display for TestSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in self.cases.iter() {
+            writeln!(f, "{} {}", case.expected, display_word(&case.word))?;
+        }
+        Ok(())
+    }
+}
+
+/* A case of a suite with the answer of the machine. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCaseResult {
+    pub case: TestCase,
+    pub accepted: bool,
+}
+
+impl TestCaseResult {
+    pub fn passed(&self) -> bool {
+        self.accepted == (self.case.expected == Expectation::Accept)
+    }
+}
+
+/* A result is written with the line of its case, the answer of the machine
+ * and the one expected, e.g. line 4: ab was accepted, expected reject. */
+impl fmt::Display for TestCaseResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let answer = if self.accepted { "accepted" } else { "rejected" };
+        write!(f, "line {}: {} was {}, expected {}", self.case.line, display_word(&self.case.word), answer, self.case.expected)
+    }
+}
+
+/* The results of running a test suite on a machine, in the order of the
+ * suite. */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestSuiteReport {
+    pub results: Vec<TestCaseResult>,
+}
+
+impl TestSuiteReport {
+    pub fn passed_count(&self) -> usize {
+        self.results.iter().filter(|result| result.passed()).count()
+    }
+
+    pub fn all_passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed())
+    }
+
+    // The results of the cases where the machine didn't give the expected answer.
+    pub fn mismatches(&self) -> Vec<&TestCaseResult> {
+        self.results.iter().filter(|result| !result.passed()).collect()
+    }
+}
+
+/* The report is written as the number of cases passed followed by a line
+ * for every mismatch, e.g.
+ *     2 of 3 cases passed
+ *     line 4: ab was accepted, expected reject */
+impl fmt::Display for TestSuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} of {} cases passed", self.passed_count(), self.results.len())?;
+        for result in self.mismatches() {
+            writeln!(f, "{}", result)?;
+        }
+        Ok(())
+    }
+}
+
+// Writes the empty word as ε.
+fn display_word(word: &str) -> &str {
+    if word.is_empty() { "ε" } else { word }
+}
@@ -0,0 +1,71 @@
+use crate::ambiguity::{AmbiguitySearch, AutomatonAmbiguity, GrammarAmbiguity};
+use crate::finite_automata::FiniteAutomata;
+use crate::grammar::Grammar;
+use crate::state_machine::StateMachine;
+
+#[test]
+fn grammar_ambiguity_test() {
+    let grammar = Grammar::parse("E -> E+E | a").unwrap();
+    let ambiguity = GrammarAmbiguity::find(&grammar, 5).found().unwrap();
+    assert_eq!(ambiguity.word.concat(), "a+a+a");
+    let [first, second] = &ambiguity.derivations;
+    assert_ne!(first, second);
+    for derivation in [first, second] {
+        assert_eq!(derivation.last(), Some(&ambiguity.word));
+        assert!(grammar.check_derivation(derivation, true));
+    }
+    // The shortest ambiguous word is longer than the bound.
+    assert!(matches!(GrammarAmbiguity::find(&grammar, 4), AmbiguitySearch::NotFound));
+    assert!(matches!(GrammarAmbiguity::find(&Grammar::parse("S -> aSb | ε").unwrap(), 8), AmbiguitySearch::NotFound));
+    // The empty string has two derivations.
+    let ambiguity = GrammarAmbiguity::find(&Grammar::parse("S -> AB\nA -> a | ε\nB -> a | ε").unwrap(), 2).found().unwrap();
+    assert_eq!(ambiguity.word.concat(), "a");
+    assert_eq!(ambiguity.to_string().lines().count(), 3);
+    // The search gives up before trying every word.
+    let grammar = Grammar::parse("S -> aS | bS | cS | dS | ε").unwrap();
+    assert!(matches!(GrammarAmbiguity::find(&grammar, 12), AmbiguitySearch::Truncated));
+}
+
+#[test]
+fn automaton_ambiguity_test() {
+    // Two paths read ab from q0 to q3.
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(4);
+    automaton.make_initial(0);
+    automaton.make_final(3);
+    automaton.add_transition(0, 1, "a".to_string());
+    automaton.add_transition(0, 2, "a".to_string());
+    automaton.add_transition(1, 3, "b".to_string());
+    automaton.add_transition(2, 3, "b".to_string());
+    automaton.add_transition(3, 3, "c".to_string());
+    let ambiguity = AutomatonAmbiguity::find(&automaton, 3).found().unwrap();
+    assert_eq!(ambiguity.word, "ab");
+    let [first, second] = &ambiguity.runs;
+    assert_ne!(first.states, second.states);
+    assert_eq!(first.inputs.concat(), "ab");
+    assert_eq!(second.inputs.concat(), "ab");
+    assert!(matches!(AutomatonAmbiguity::find(&automaton, 1), AmbiguitySearch::NotFound));
+    // A deterministic automaton is never ambiguous.
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(2);
+    automaton.make_initial(0);
+    automaton.make_final(1);
+    automaton.add_transition(0, 1, "a".to_string());
+    automaton.add_transition(1, 0, "b".to_string());
+    assert!(matches!(AutomatonAmbiguity::find(&automaton, 10), AmbiguitySearch::NotFound));
+    // An ε-transition gives a second run of a.
+    automaton.add_transition(0, 0, "ε".to_string());
+    let ambiguity = AutomatonAmbiguity::find(&automaton, 10).found().unwrap();
+    assert_eq!(ambiguity.word, "a");
+    assert!(ambiguity.to_string().contains("q0 -ε-> q0 -a-> q1"));
+    // The search gives up before trying every word.
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(1);
+    automaton.make_initial(0);
+    automaton.make_final(0);
+    for symbol in ["a", "b", "c", "d"] {
+        automaton.add_transition(0, 0, symbol.to_string());
+    }
+    assert!(matches!(AutomatonAmbiguity::find(&automaton, 10), AmbiguitySearch::Truncated));
+    assert!(matches!(AutomatonAmbiguity::find(&automaton, 4), AmbiguitySearch::NotFound));
+}
@@ -0,0 +1,63 @@
+use crate::automaton_text::{self, AutomatonTextError};
+use crate::machine_file::{FileMachine, MachineFileKind};
+use crate::state_machine::StateMachine;
+
+#[test]
+fn parse_automaton_text_test() {
+    let text = "initial q0; final q2\n# The words ab and ac.\nq0 -a-> q1; q1 -b,c-> q2\nq2 -λ-> q2\nstate q3";
+    let file = automaton_text::parse_automaton(text).unwrap();
+    assert_eq!(file.kind, MachineFileKind::Finite);
+    let names: Vec<&str> = file.states.iter().map(|state| state.name.as_str()).collect();
+    assert_eq!(names, vec!["q0", "q2", "q1", "q3"]);
+    let labels: Vec<&str> = file.transitions.iter().map(|transition| transition.label.as_str()).collect();
+    assert_eq!(labels, vec!["a", "b", "c", "ε"]);
+    let automaton = match file.to_machine() {
+        FileMachine::Finite(automaton) => automaton,
+        other => panic!("expected a finite automaton, got {:?}", other.kind()),
+    };
+    assert_eq!(automaton.get_initial_state_id(), &Some(0));
+    assert_eq!(automaton.check_input(&mut "ac".to_string()), true);
+    assert_eq!(automaton.check_input(&mut "a".to_string()), false);
+}
+
+#[test]
+fn format_automaton_text_test() {
+    let text = "type pushdown; stack \"⊥\"\ninitial q0; final q1\nq0 -\"a;⊥/A⊥\", \"a;A/AA\"-> q0\nq0 -\"ε;A/A\"-> q1\nq1 -\"b;A/ε\"-> q1";
+    let file = automaton_text::parse_automaton(text).unwrap();
+    let expected = "type pushdown;
+stack \"⊥\";
+initial q0;
+final q1;
+q0 -\"a;⊥/A⊥\",\"a;A/AA\"-> q0;
+q0 -\"ε;A/A\"-> q1;
+q1 -\"b;A/ε\"-> q1;
+";
+    assert_eq!(automaton_text::format_automaton(&file), expected);
+    assert_eq!(automaton_text::parse_automaton(expected).unwrap(), file);
+    match file.to_machine() {
+        FileMachine::Pushdown(automaton) => {
+            assert_eq!(automaton.get_initial_stack_symbol(), "⊥");
+            assert_eq!(automaton.check_input(&mut "aabb".to_string()), true);
+        }
+        other => panic!("expected a pushdown automaton, got {:?}", other.kind()),
+    }
+    // Names that can't be read as names are quoted.
+    let file = automaton_text::parse_automaton("type moore; initial \"{q0, q1}\"; output \"{q0, q1}\" 1; \"final\" -0-> \"{q0, q1}\"").unwrap();
+    let text = automaton_text::format_automaton(&file);
+    assert_eq!(text, "type moore;\ninitial \"{q0, q1}\";\noutput \"{q0, q1}\" 1;\n\"final\" -0-> \"{q0, q1}\";\n");
+    assert_eq!(automaton_text::parse_automaton(&text).unwrap(), file);
+}
+
+#[test]
+fn automaton_text_error_test() {
+    let error = automaton_text::parse_automaton("initial q0\nq0 -a q1").unwrap_err();
+    assert_eq!(error, AutomatonTextError { line: 2, column: 9, message: "expected , or -> after the label".to_string() });
+    let error = automaton_text::parse_automaton("q0 -a-> q1 q2").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 12));
+    let error = automaton_text::parse_automaton("type turing\nq0 -a/b,R-> q1").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 5));
+    assert!(error.message.contains("read/write,move"));
+    let error = automaton_text::parse_automaton("initial q0\ninitial q1").unwrap_err();
+    assert_eq!(error.to_string(), "line 2, column 1: the initial state is already given in line 1");
+    assert!(automaton_text::parse_automaton("q0 -\"a-> q1").is_err());
+}
@@ -0,0 +1,109 @@
+use crate::cli::{self, CliOutput};
+use crate::machine_file::{FileMachine, MachineFile};
+
+// Writes a file with the given name in the temporary directory and returns its path.
+fn temp_file(name: &str, text: &str) -> String {
+    let path = std::env::temp_dir().join(format!("moca-data-cli-{}", name));
+    std::fs::write(&path, text).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+fn run(args: &[&str], input: &str) -> Result<CliOutput, cli::CliError> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    cli::run(&args, &mut input.as_bytes())
+}
+
+/* NFA of the words over a and b that end with ab. */
+const ENDS_WITH_AB: &str = "initial q0; final q2\nq0 -a,b-> q0; q0 -a-> q1; q1 -b-> q2\n";
+
+#[test]
+fn check_command_test() {
+    let path = temp_file("check.txt", ENDS_WITH_AB);
+    let output = run(&["check", &path, "ab", "ba"], "").unwrap();
+    assert_eq!(output, CliOutput { text: "ab: accepted\nba: rejected\n".to_string(), success: false });
+    // Without words they are read from the input, one per line.
+    let output = run(&["check", &path], "aab\n\nbab\n").unwrap();
+    assert_eq!(output, CliOutput { text: "aab: accepted\nε: rejected\nbab: accepted\n".to_string(), success: false });
+    assert_eq!(run(&["check", &path, "ab"], "").unwrap().success, true);
+    // Mealy machines give their output.
+    let path = temp_file("check-mealy.txt", "type mealy; initial q0\nq0 -a/1,b/0-> q0\n");
+    assert_eq!(run(&["check", &path, "aba"], "").unwrap().text, "aba: output 101\n");
+    let path = temp_file("check-no-initial.txt", "q0 -a-> q0\n");
+    assert_eq!(run(&["check", &path, "a"], "").unwrap_err().message, "the machine has no initial state");
+}
+
+#[test]
+fn transformation_commands_test() {
+    let path = temp_file("transform.txt", ENDS_WITH_AB);
+    let output_path = std::env::temp_dir().join("moca-data-cli-transform-dfa.json").to_string_lossy().to_string();
+    assert_eq!(run(&["determinize", &path, "-o", &output_path], "").unwrap().text, "");
+    let file = MachineFile::from_json(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+    match file.to_machine() {
+        FileMachine::Finite(automaton) => {
+            assert!(crate::state_machine::StateMachine::is_deterministic(&automaton));
+            assert_eq!(automaton.check_input(&mut "bab".to_string()), true);
+            assert_eq!(automaton.check_input(&mut "abb".to_string()), false);
+        }
+        other => panic!("expected a finite automaton, got {:?}", other.kind()),
+    }
+    assert_eq!(run(&["equiv", &path, &output_path], "").unwrap(), CliOutput { text: "equivalent\n".to_string(), success: true });
+    let minimized = run(&["minimize", &path], "").unwrap().text;
+    let minimized_path = temp_file("transform-min.txt", &minimized);
+    assert!(run(&["info", &minimized_path], "").unwrap().text.contains("states: 3\n"));
+    assert_eq!(run(&["to-regex", &path], "").unwrap().text, "(a+b)*ab\n");
+    let regex_path = temp_file("transform-regex.txt", &run(&["from-regex", "(a+b)*b"], "").unwrap().text);
+    let output = run(&["equiv", &path, &regex_path], "").unwrap();
+    assert_eq!(output.success, false);
+    assert!(output.text.starts_with("not equivalent: b is accepted by"));
+}
+
+#[test]
+fn epsilon_cycles_test() {
+    // The nested stars give cycles of ε-transitions.
+    let path = temp_file("epsilon-cycles.txt", &run(&["from-regex", "(a*+b)*"], "").unwrap().text);
+    for command in ["determinize", "minimize"] {
+        let output_path = temp_file(&format!("epsilon-cycles-{}.txt", command), &run(&[command, &path], "").unwrap().text);
+        assert_eq!(run(&["check", &output_path, "", "abba"], "").unwrap().success, true);
+        assert_eq!(run(&["equiv", &path, &output_path], "").unwrap().success, true);
+    }
+    let minimized_path = temp_file("epsilon-cycles-min.txt", &run(&["minimize", &path], "").unwrap().text);
+    assert!(run(&["info", &minimized_path], "").unwrap().text.contains("states: 1\n"));
+}
+
+#[test]
+fn export_and_info_commands_test() {
+    let path = temp_file("export.txt", ENDS_WITH_AB);
+    let tikz = run(&["export", "--tikz", &path], "").unwrap().text;
+    assert!(tikz.contains("\\node[state, initial] (q0) at (0.00, -2.50) {$q_0$};"));
+    assert!(tikz.contains("\\path[->] (q0) edge[loop above] node[align=center]{a\\\\b} (q0);"));
+    // The characters that are special in LaTeX are escaped in the labels.
+    let turing_path = temp_file("export-turing.txt", "type turing; initial q0; final q1\nq0 -\"_/_,R\"-> q1\n");
+    let tikz = run(&["export", "--tikz", &turing_path], "").unwrap().text;
+    assert!(tikz.contains("edge node{\\_/\\_,R} (q1);"), "{}", tikz);
+    let dot = run(&["export", "--dot", &path], "").unwrap().text;
+    assert!(dot.starts_with("digraph {"));
+    assert_eq!(run(&["export", &path], "").unwrap_err().message, "export needs either --tikz or --dot");
+    let info = run(&["info", &path], "").unwrap().text;
+    assert_eq!(info, "kind: finite\nstates: 3\ntransitions: 4\ninitial state: q0\nfinal states: q2\ndeterministic: no\nalphabet: a, b\n");
+    assert!(run(&["unknown"], "").is_err());
+    assert!(run(&["check"], "").is_err());
+    assert!(run(&["info", &path, "-x"], "").is_err());
+    assert!(run(&["from-regex", "(a"], "").unwrap_err().message.starts_with("invalid regular expression"));
+}
+
+#[test]
+fn test_command_test() {
+    let path = temp_file("test.txt", ENDS_WITH_AB);
+    let other_path = temp_file("test-other.txt", "initial q0; final q1\nq0 -a,b-> q0; q0 -b-> q1\n");
+    let suite_path = temp_file("test-suite.txt", "accept ab\naccept bab\nreject b\nreject ε\n");
+    let output = run(&["test", &suite_path, &path], "").unwrap();
+    assert_eq!(output, CliOutput { text: format!("{}: 4 of 4 cases passed\n", path), success: true });
+    let output = run(&["test", &suite_path, &path, &other_path], "").unwrap();
+    assert_eq!(output.success, false);
+    assert_eq!(output.text, format!(
+        "{}: 4 of 4 cases passed\n{}: 3 of 4 cases passed\n  line 3: b was accepted, expected reject\n",
+        path, other_path,
+    ));
+    let suite_path = temp_file("test-invalid-suite.txt", "accept ab\nmaybe ba\n");
+    assert_eq!(run(&["test", &suite_path, &path], "").unwrap_err().message, format!("{}: line 2: expected accept or reject, found maybe", suite_path));
+}
@@ -0,0 +1,39 @@
+use crate::cyk::CykTable;
+use crate::grammar::{Grammar, ParseTree};
+
+fn symbols(word: &str) -> Vec<String> {
+    word.chars().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn cyk_table_test() {
+    // Example grammar of the book of Hopcroft, Motwani and Ullman.
+    let grammar = Grammar::parse("S -> AB | BC\nA -> BA | a\nB -> CC | b\nC -> AB | a").unwrap();
+    let table = CykTable::new(&grammar, &symbols("baaba")).unwrap();
+    assert!(table.is_accepted());
+    assert_eq!(table.get_cell(0, 1), &["B".to_string()].into_iter().collect());
+    assert_eq!(table.get_cell(1, 1), &["A".to_string(), "C".to_string()].into_iter().collect());
+    assert_eq!(table.get_cell(0, 2), &["A".to_string(), "S".to_string()].into_iter().collect());
+    assert!(table.get_cell(0, 3).is_empty());
+    assert_eq!(table.get_cell(1, 3), &["B".to_string()].into_iter().collect());
+    assert_eq!(table.get_cell(0, 5), &["A".to_string(), "C".to_string(), "S".to_string()].into_iter().collect());
+    let tree = table.get_parse_tree().unwrap();
+    assert_eq!(tree.symbol, "S");
+    assert_eq!(tree.get_yield(), symbols("baaba"));
+    let table = CykTable::new(&grammar, &symbols("bb")).unwrap();
+    assert!(!table.is_accepted());
+    assert_eq!(table.get_parse_tree(), None);
+    // The grammar must be in Chomsky normal form.
+    assert_eq!(CykTable::new(&Grammar::parse("S -> aSb | ε").unwrap(), &symbols("ab")), None);
+}
+
+#[test]
+fn cyk_empty_word_test() {
+    let grammar = Grammar::parse("S -> aSb | ε").unwrap().to_chomsky_normal_form();
+    let table = CykTable::new(&grammar, &[]).unwrap();
+    assert!(table.is_accepted());
+    assert_eq!(table.get_parse_tree(), Some(&ParseTree::new("S'", vec![ParseTree::leaf("ε")])));
+    assert!(CykTable::new(&grammar, &symbols("aabb")).unwrap().is_accepted());
+    assert!(!CykTable::new(&grammar, &symbols("abb")).unwrap().is_accepted());
+    assert_eq!(ParseTree::new("S", vec![ParseTree::leaf("a")]).to_string(), "S\n└─ a\n");
+}
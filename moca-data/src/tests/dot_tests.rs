@@ -0,0 +1,92 @@
+use crate::dot;
+use crate::finite_automata::FiniteAutomata;
+use crate::pushdown_automata::PushdownAutomata;
+use crate::state_machine::StateMachine;
+
+#[test]
+fn finite_automaton_to_dot_test() {
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(2);
+    automaton.make_initial(0);
+    automaton.make_final(1);
+    automaton.add_transition(0, 1, "b".to_string());
+    automaton.add_transition(0, 1, "a".to_string());
+    automaton.add_transition(1, 1, "a".to_string());
+    let expected = "digraph {
+    rankdir = LR;
+    node [shape = circle];
+    __start [shape = point, style = invis];
+    0 [label = \"q0\"];
+    1 [label = \"q1\", shape = doublecircle];
+    __start -> 0;
+    0 -> 1 [label = \"a\\nb\"];
+    1 -> 1 [label = \"a\"];
+}
+";
+    assert_eq!(automaton.to_dot(), expected);
+    // Reading the graph back gives the same automaton.
+    let read_automaton = FiniteAutomata::from_dot(&automaton.to_dot()).unwrap();
+    assert_eq!(read_automaton.get_states_by_id_ref(), automaton.get_states_by_id_ref());
+    assert_eq!(read_automaton.get_initial_state_id(), &Some(0));
+    assert_eq!(read_automaton.get_final_states(), automaton.get_final_states());
+}
+
+#[test]
+fn parse_dot_test() {
+    // Written like the finite state machine of the Graphviz gallery.
+    let text = r#"
+        digraph finite_state_machine {
+            rankdir=LR;
+            size="8,5"
+            node [shape = doublecircle]; s2;
+            node [shape = circle];
+            "" [shape = none]
+            "" -> s0
+            /* The edges. */
+            s0 -> s1 [ label = "a, λ" ];
+            s1 -> s2 -> s2 [ label = "b" ];  // a chain of edges
+            subgraph cluster { s2 -> s0 }
+        }
+    "#;
+    let graph = dot::parse_dot(text).unwrap();
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(graph.edges.len(), 5);
+    assert!(graph.is_start_node(""));
+    let automaton = FiniteAutomata::from_dot(text).unwrap();
+    let names: Vec<String> = (0..3).map(|id| automaton.get_states_by_id_ref()[&id].name.clone()).collect();
+    assert_eq!(names, vec!["s2", "s0", "s1"]);
+    assert_eq!(automaton.get_initial_state_id(), &Some(1));
+    assert!(automaton.get_final_states().contains(&0));
+    assert_eq!(automaton.check_input(&mut "ab".to_string()), true);
+    assert_eq!(automaton.check_input(&mut "bbb".to_string()), true);
+    assert_eq!(automaton.check_input(&mut "aa".to_string()), false);
+}
+
+#[test]
+fn pushdown_automaton_dot_test() {
+    let mut automaton = PushdownAutomata::new("Z".to_string());
+    automaton.add_n_states(2);
+    automaton.make_initial(0);
+    automaton.make_final(1);
+    automaton.add_transition(0, 0, "a;Z/AZ".to_string());
+    automaton.add_transition(0, 0, "a;A/AA".to_string());
+    automaton.add_transition(0, 1, "b;A/ε".to_string());
+    automaton.add_transition(1, 1, "b;A/ε".to_string());
+    let dot_text = automaton.to_dot();
+    assert!(dot_text.contains("0 -> 0 [label = \"a;A/AA\\na;Z/AZ\"];"));
+    let read_automaton = PushdownAutomata::from_dot(&dot_text, "Z".to_string()).unwrap();
+    assert_eq!(read_automaton.get_states_by_id_ref(), automaton.get_states_by_id_ref());
+    assert_eq!(read_automaton.check_input(&mut "aabb".to_string()), true);
+    assert_eq!(read_automaton.check_input(&mut "abb".to_string()), false);
+}
+
+#[test]
+fn invalid_dot_test() {
+    assert_eq!(dot::parse_dot("graph { a -- b }").unwrap_err().line, 1);
+    let error = dot::parse_dot("digraph {\n a -> b [label = \"x\"]\n").unwrap_err();
+    assert_eq!(error.message, "expected } at the end of the graph");
+    let text = "digraph {\n start [shape = point]\n start -> a\n start -> b\n}";
+    let error = FiniteAutomata::from_dot(text).unwrap_err();
+    assert_eq!(error.line, 4);
+    assert!(error.message.contains("start arrow"));
+}
@@ -0,0 +1,56 @@
+use crate::earley::{EarleyChart, MAX_PARSE_TREES};
+use crate::grammar::{Grammar, ParseTree};
+
+fn symbols(word: &str) -> Vec<String> {
+    word.chars().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn earley_recognizer_test() {
+    // The grammar is not in Chomsky normal form and has ε-productions.
+    let grammar = Grammar::parse("S -> aSb | A\nA -> cA | ε").unwrap();
+    assert!(EarleyChart::new(&grammar, &[]).is_accepted());
+    assert!(EarleyChart::new(&grammar, &symbols("ab")).is_accepted());
+    assert!(EarleyChart::new(&grammar, &symbols("aaccbb")).is_accepted());
+    assert!(!EarleyChart::new(&grammar, &symbols("aacbbb")).is_accepted());
+    assert!(!EarleyChart::new(&grammar, &symbols("ca")).is_accepted());
+    // Nullable nonterminals that are completed in the same set they are predicted.
+    let grammar = Grammar::parse("S -> AAx\nA -> ε").unwrap();
+    assert!(EarleyChart::new(&grammar, &symbols("x")).is_accepted());
+    let chart = EarleyChart::new(&grammar, &symbols("x"));
+    assert_eq!(chart.get_sets().len(), 2);
+    assert_eq!(chart.get_sets()[1][0].to_string(), "S -> AAx·, 0");
+}
+
+#[test]
+fn earley_parse_trees_test() {
+    // Ambiguous grammar, a+a*a has two parse trees.
+    let grammar = Grammar::parse("E -> E+E | E*E | a").unwrap();
+    let chart = EarleyChart::new(&grammar, &symbols("a+a*a"));
+    let list = chart.parse_trees();
+    assert!(!list.truncated);
+    let trees = list.trees;
+    assert_eq!(trees.len(), 2);
+    assert!(trees.iter().all(|tree| tree.get_yield() == symbols("a+a*a")));
+    assert_ne!(trees[0], trees[1]);
+    assert!(EarleyChart::new(&grammar, &symbols("a+")).parse_trees().trees.is_empty());
+    // The ε-productions have an ε leaf.
+    let grammar = Grammar::parse("S -> aS | ε").unwrap();
+    let trees = EarleyChart::new(&grammar, &symbols("a")).parse_trees().trees;
+    assert_eq!(trees, vec![ParseTree::new("S", vec![
+        ParseTree::leaf("a"),
+        ParseTree::new("S", vec![ParseTree::leaf("ε")]),
+    ])]);
+    // The cycles S => S don't give infinitely many trees.
+    let grammar = Grammar::parse("S -> S | a").unwrap();
+    assert_eq!(EarleyChart::new(&grammar, &symbols("a")).parse_trees().trees.len(), 1);
+    // The nonterminal A derives a in 10 ways, so AA derives aa in exactly MAX_PARSE_TREES ways
+    // and the list is only truncated for AAA.
+    let rules = "A -> a | B | C | D | E | F | G | H | I | J\nB -> a\nC -> a\nD -> a\nE -> a\nF -> a\nG -> a\nH -> a\nI -> a\nJ -> a";
+    let grammar = Grammar::parse(&format!("S -> AA\n{}", rules)).unwrap();
+    let list = EarleyChart::new(&grammar, &symbols("aa")).parse_trees();
+    assert_eq!((list.trees.len(), list.truncated), (MAX_PARSE_TREES, false));
+    let grammar = Grammar::parse(&format!("S -> AAA\n{}", rules)).unwrap();
+    let list = EarleyChart::new(&grammar, &symbols("aaa")).parse_trees();
+    assert_eq!((list.trees.len(), list.truncated), (MAX_PARSE_TREES, true));
+}
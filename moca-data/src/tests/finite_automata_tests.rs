@@ -1,5 +1,6 @@
 use crate::state::State;
 use crate::finite_automata::FiniteAutomata;
+use crate::grammar::DerivationSearch;
 use crate::state_machine::StateMachine;
 use crate::state;
 
@@ -444,3 +445,73 @@ fn minimize_test() {
     assert_eq!(debloated_automata.check_input(&mut "abbabb".to_string()),true);
     assert_eq!(debloated_automata.check_input(&mut "abbbbaabb".to_string()),true);
 }
+
+#[test]
+fn to_grammar_test() {
+    // Automaton of the strings over {a, b} with an even number of a.
+    let mut automata = FiniteAutomata::new();
+    automata.add_n_states(2);
+    automata.make_initial(0);
+    automata.make_final(0);
+    automata.add_transition(0, 1, "a".to_string());
+    automata.add_transition(1, 0, "a".to_string());
+    automata.add_transition(0, 0, "b".to_string());
+    automata.add_transition(1, 1, "b".to_string());
+    let grammar = automata.to_grammar();
+    assert_eq!(grammar.get_start_symbol(), "q0");
+    assert_eq!(grammar.to_string(), "q0 -> a q1 | b q0 | ε\nq1 -> a q0 | b q1\n");
+    assert!(grammar.is_right_linear());
+    assert!(grammar.leftmost_derivation("abab").found().is_some());
+    assert!(grammar.leftmost_derivation("ab") == DerivationSearch::NotDerivable);
+    // The grammar gives back an equivalent automaton.
+    let mut automata = grammar.to_finite_automata().unwrap();
+    assert_eq!(automata.check_input(&mut "baab".to_string()), true);
+    assert_eq!(automata.check_input(&mut "bab".to_string()), false);
+    automata.modify_name(1, "q0".to_string());
+    assert_eq!(automata.to_grammar().get_nonterminals(), &vec!["q0".to_string(), "q0'".to_string()]);
+}
+
+#[test]
+fn distinguishing_word_test() {
+    // NFA of the words that end with ab, with an ε-transition.
+    let mut nfa = FiniteAutomata::new();
+    nfa.add_n_states(4);
+    nfa.make_initial(0);
+    nfa.make_final(3);
+    nfa.add_transition(0, 0, "a".to_string());
+    nfa.add_transition(0, 0, "b".to_string());
+    nfa.add_transition(0, 1, "a".to_string());
+    nfa.add_transition(1, 2, "ε".to_string());
+    nfa.add_transition(2, 3, "b".to_string());
+    // DFA of the same language.
+    let mut dfa = FiniteAutomata::new();
+    dfa.add_n_states(3);
+    dfa.make_initial(0);
+    dfa.make_final(2);
+    dfa.add_transition(0, 1, "a".to_string());
+    dfa.add_transition(0, 0, "b".to_string());
+    dfa.add_transition(1, 1, "a".to_string());
+    dfa.add_transition(1, 2, "b".to_string());
+    dfa.add_transition(2, 1, "a".to_string());
+    dfa.add_transition(2, 0, "b".to_string());
+    assert_eq!(nfa.distinguishing_word(&dfa), None);
+    assert_eq!(dfa.distinguishing_word(&nfa), None);
+    // Making the initial state final, the empty word distinguishes them.
+    dfa.make_final(0);
+    assert_eq!(nfa.distinguishing_word(&dfa), Some("".to_string()));
+    // The word returned is one of the shortest, here the automaton of the words that end with b.
+    let mut automata = FiniteAutomata::new();
+    automata.add_n_states(2);
+    automata.make_initial(0);
+    automata.make_final(1);
+    automata.add_transition(0, 0, "a".to_string());
+    automata.add_transition(0, 1, "b".to_string());
+    automata.add_transition(1, 0, "a".to_string());
+    automata.add_transition(1, 1, "b".to_string());
+    assert_eq!(nfa.distinguishing_word(&automata), Some("b".to_string()));
+    // An automaton without initial state accepts no word.
+    let mut empty = FiniteAutomata::new();
+    empty.add_n_states(1);
+    assert_eq!(empty.distinguishing_word(&FiniteAutomata::new()), None);
+    assert_eq!(empty.distinguishing_word(&nfa), Some("ab".to_string()));
+}
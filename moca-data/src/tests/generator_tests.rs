@@ -0,0 +1,44 @@
+use crate::generator::{all_sentences, SentenceGenerator};
+use crate::grammar::{Grammar, Production};
+
+#[test]
+fn all_sentences_test() {
+    let grammar = Grammar::parse("S -> aSb | ε").unwrap();
+    let list = all_sentences(&grammar, 6);
+    assert!(!list.truncated);
+    let sentences: Vec<String> = list.sentences.iter().map(|sentence| sentence.concat()).collect();
+    assert_eq!(sentences, vec!["", "ab", "aabb", "aaabbb"]);
+    // The sentences are ordered by length, the nullable nonterminals don't count for the bound.
+    let grammar = Grammar::parse("S -> SA | A\nA -> a | b | ε").unwrap();
+    let sentences: Vec<String> = all_sentences(&grammar, 2).sentences.iter().map(|sentence| sentence.concat()).collect();
+    assert_eq!(sentences, vec!["", "a", "b", "aa", "ab", "ba", "bb"]);
+    // There are more forms than the search visits, the list is marked as incomplete.
+    let grammar = Grammar::parse("S -> aS | bS | cS | dS | a").unwrap();
+    let list = all_sentences(&grammar, 10);
+    assert!(list.truncated);
+    assert!(list.sentences.iter().all(|sentence| sentence.len() <= 10));
+}
+
+#[test]
+fn sentence_generator_test() {
+    let grammar = Grammar::parse("S -> (S)S | ε").unwrap();
+    let mut generator = SentenceGenerator::new(&grammar, 7);
+    generator.set_max_depth(6);
+    let sentences = generator.generate_many(50);
+    assert_eq!(sentences.len(), 50);
+    // The same seed gives the same sentences.
+    let mut other_generator = SentenceGenerator::new(&grammar, 7);
+    other_generator.set_max_depth(6);
+    assert_eq!(other_generator.generate_many(50), sentences);
+    // Every sentence is accepted by the pushdown automaton of the grammar.
+    let pushdown_automaton = grammar.to_pushdown_automata();
+    for sentence in sentences.iter() {
+        assert_eq!(pushdown_automaton.check_input(&mut sentence.concat()), true);
+    }
+    // A production with weight 0 is only used to end the tree.
+    generator.set_weight(&Production::new("S", vec![]), 0.0);
+    generator.set_max_depth(3);
+    assert_eq!(generator.generate().unwrap().concat(), "(())()");
+    generator.set_max_depth(0);
+    assert!(generator.generate().is_none());
+}
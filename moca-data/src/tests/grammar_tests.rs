@@ -0,0 +1,235 @@
+use crate::grammar::{DerivationSearch, Grammar, Production};
+use crate::state_machine::StateMachine;
+
+#[test]
+fn parse_grammar_test() {
+    let grammar = Grammar::parse("S -> aSb | ε\n  | A\nA → c").unwrap();
+    assert_eq!(grammar.get_start_symbol(), "S");
+    assert_eq!(grammar.get_nonterminals(), &vec!["S".to_string(), "A".to_string()]);
+    assert_eq!(grammar.get_terminals(), ["a", "b", "c"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(grammar.get_productions().len(), 4);
+    assert!(grammar.get_productions().contains(&Production::new("S", vec![])));
+    assert!(grammar.get_productions().contains(&Production::new("S", vec!["a".to_string(), "S".to_string(), "b".to_string()])));
+    // Symbols longer than one character are separated by spaces.
+    let grammar = Grammar::parse("E -> E + T | T\nT -> id").unwrap();
+    assert!(grammar.get_productions().contains(&Production::new("T", vec!["id".to_string()])));
+    assert!(grammar.get_terminals().contains("+"));
+    // Without spaces the brackets are terminals, with them they can enclose a nonterminal.
+    let grammar = Grammar::parse("S -> [S]S | ε").unwrap();
+    assert_eq!(grammar.get_nonterminals(), &vec!["S".to_string()]);
+    assert_eq!(grammar.get_terminals(), ["[", "]"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(grammar.parse_sentential_form("[S]"), vec!["[", "S", "]"]);
+    let grammar = Grammar::parse("S -> a [q,A,p] | [ S ]
+[q,A,p] -> b").unwrap();
+    assert_eq!(grammar.get_nonterminals(), &vec!["S".to_string(), "[q,A,p]".to_string()]);
+    assert_eq!(grammar.get_terminals(), ["[", "]", "a", "b"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(Grammar::parse("S aSb").unwrap_err().line, 1);
+    assert_eq!(Grammar::parse("S -> a\nA B -> b").unwrap_err().line, 2);
+    assert!(Grammar::parse("| a").is_err());
+    assert!(Grammar::parse("").is_err());
+}
+
+#[test]
+fn print_grammar_test() {
+    let text = "S -> aSb | A\nA -> c | ε\n";
+    let grammar = Grammar::parse(text).unwrap();
+    assert_eq!(grammar.to_string(), text);
+    assert_eq!(Grammar::parse(&grammar.to_string()).unwrap(), grammar);
+    let grammar = Grammar::parse("E -> E + T | T\nT -> id").unwrap();
+    assert_eq!(grammar.to_string(), "E -> E + T | T\nT -> id\n");
+    let grammar = Grammar::parse("S -> [S]S | ε").unwrap();
+    assert_eq!(grammar.to_string(), "S -> [S]S | ε\n");
+    let grammar = Grammar::parse("S -> a [q,A,p]\n[q,A,p] -> b").unwrap();
+    assert_eq!(grammar.to_string(), "S -> a [q,A,p]\n[q,A,p] -> b\n");
+    assert_eq!(Grammar::parse(&grammar.to_string()).unwrap(), grammar);
+}
+
+#[test]
+fn check_derivation_test() {
+    let grammar = Grammar::parse("S -> AB\nA -> a\nB -> b").unwrap();
+    let leftmost = grammar.parse_derivation("S => AB => aB => ab");
+    let rightmost = grammar.parse_derivation("S ⇒ AB ⇒ Ab ⇒ ab");
+    assert!(grammar.check_derivation(&leftmost, true));
+    assert!(grammar.check_derivation(&rightmost, false));
+    assert!(!grammar.check_derivation(&rightmost, true));
+    assert!(!grammar.check_derivation(&grammar.parse_derivation("S => ab"), false));
+    assert!(!grammar.check_derivation(&grammar.parse_derivation("A => a"), false));
+}
+
+#[test]
+fn leftmost_derivation_test() {
+    let grammar = Grammar::parse("S -> aSb | SS | ε").unwrap();
+    let derivation = grammar.leftmost_derivation("aabbab").found().unwrap();
+    assert!(grammar.check_derivation(&derivation, true));
+    assert_eq!(derivation.last().unwrap().concat(), "aabbab");
+    assert_eq!(grammar.leftmost_derivation("").found().map(|d| d.len()), Some(2));
+    assert_eq!(grammar.leftmost_derivation("aab"), DerivationSearch::NotDerivable);
+    assert_eq!(grammar.leftmost_derivation("ba"), DerivationSearch::NotDerivable);
+    // The search gives up before exploring every form that could derive the word.
+    let grammar = Grammar::parse("S -> SA | SB | ε\nA -> a | ε\nB -> a | ε").unwrap();
+    assert_eq!(grammar.leftmost_derivation("aaaaaaaaaab"), DerivationSearch::Truncated);
+}
+
+#[test]
+fn to_pushdown_automata_test() {
+    // Grammar of the strings of balanced parentheses.
+    let grammar = Grammar::parse("S -> (S)S | ε").unwrap();
+    let pushdown_automaton = grammar.to_pushdown_automata();
+    assert_eq!(pushdown_automaton.get_states_by_id_ref().len(), 3);
+    assert_eq!(pushdown_automaton.check_input(&mut "".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "(()())()".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "(()".to_string()), false);
+    assert_eq!(pushdown_automaton.check_input(&mut ")(".to_string()), false);
+    // The symbols longer than one character and the initial stack symbol Z.
+    let grammar = Grammar::parse("S0 -> Z S0 id | ε\nZ -> z").unwrap();
+    let pushdown_automaton = grammar.to_pushdown_automata();
+    assert_eq!(pushdown_automaton.get_initial_stack_symbol(), "$");
+    assert_eq!(pushdown_automaton.check_input(&mut "".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "zzidid".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "zzid".to_string()), false);
+    // The separators of the transition labels are written between brackets in the stack.
+    let grammar = Grammar::parse("E -> E / T | T\nT -> a | ; T").unwrap();
+    let pushdown_automaton = grammar.to_pushdown_automata();
+    assert_eq!(pushdown_automaton.check_input(&mut "a/a".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut ";a/;;a".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "a/".to_string()), false);
+    // The brackets are written between brackets too.
+    let grammar = Grammar::parse("S -> [S]S | ε").unwrap();
+    let pushdown_automaton = grammar.to_pushdown_automata();
+    assert!(pushdown_automaton.check_input(&mut "[[][]]".to_string()));
+    assert!(!pushdown_automaton.check_input(&mut "[]]".to_string()));
+}
+
+#[test]
+fn remove_useless_symbols_test() {
+    let grammar = Grammar::parse("S -> AB | a\nA -> b\nC -> c").unwrap();
+    let cleaned = grammar.remove_useless_symbols();
+    assert_eq!(cleaned.get_productions(), &vec![Production::new("S", vec!["a".to_string()])]);
+    assert_eq!(cleaned.get_nonterminals(), &vec!["S".to_string()]);
+}
+
+#[test]
+fn chomsky_normal_form_test() {
+    let grammar = Grammar::parse("S -> ASA | aB\nA -> B | S\nB -> b | ε").unwrap();
+    let steps = grammar.chomsky_normal_form_steps();
+    assert_eq!(steps.len(), 5);
+    assert_eq!(steps[0].grammar.get_start_symbol(), "S'");
+    assert!(steps[1].grammar.get_productions().iter().all(|p| !p.is_epsilon()));
+    assert!(steps[2].grammar.get_productions().iter()
+        .all(|p| p.body.len() != 1 || steps[2].grammar.is_terminal(&p.body[0])));
+    let normal_form = grammar.to_chomsky_normal_form();
+    assert!(!grammar.is_chomsky_normal_form());
+    assert!(normal_form.is_chomsky_normal_form());
+    for word in ["a", "ab", "b", "bab", "aba", "abbab"] {
+        assert_eq!(normal_form.leftmost_derivation(word).found().is_some(), grammar.leftmost_derivation(word).found().is_some());
+    }
+    // The empty string is kept with the production of the start symbol.
+    let grammar = Grammar::parse("S -> aSb | ε").unwrap();
+    let normal_form = grammar.to_chomsky_normal_form();
+    assert!(normal_form.is_chomsky_normal_form());
+    assert!(normal_form.get_productions().contains(&Production::new("S'", vec![])));
+    assert!(normal_form.leftmost_derivation("aabb").found().is_some());
+    assert!(normal_form.leftmost_derivation("aab") == DerivationSearch::NotDerivable);
+    assert!(normal_form.get_nonterminals().contains(&"<a>".to_string()));
+}
+
+#[test]
+fn greibach_normal_form_test() {
+    // Left recursive grammar of the arithmetic expressions.
+    let grammar = Grammar::parse("E -> E+T | T\nT -> T*F | F\nF -> (E) | a").unwrap();
+    let steps = grammar.greibach_normal_form_steps();
+    assert_eq!(steps.len(), 7);
+    assert!(steps[4].grammar.is_chomsky_normal_form());
+    assert!(!steps[5].grammar.has_left_recursion());
+    let normal_form = grammar.to_greibach_normal_form();
+    assert!(!grammar.is_greibach_normal_form());
+    assert!(normal_form.is_greibach_normal_form());
+    for word in ["a", "a+a", "a*a+a", "(a+a)*a", "a+", "(a", "a)"] {
+        assert_eq!(normal_form.leftmost_derivation(word).found().is_some(), grammar.leftmost_derivation(word).found().is_some());
+    }
+    // The automaton with a single state accepts the same strings by empty stack.
+    let pushdown_automaton = normal_form.to_single_state_pushdown_automata().unwrap();
+    assert!(pushdown_automaton.accepts_by_empty_stack());
+    assert_eq!(pushdown_automaton.get_states_by_id_ref().len(), 1);
+    assert_eq!(pushdown_automaton.check_input(&mut "(a+a)*a".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "a+a*".to_string()), false);
+    assert!(grammar.to_single_state_pushdown_automata().is_none());
+    // The empty string is kept with the production of the start symbol.
+    let normal_form = Grammar::parse("S -> aSb | ε").unwrap().to_greibach_normal_form();
+    assert!(normal_form.is_greibach_normal_form());
+    let pushdown_automaton = normal_form.to_single_state_pushdown_automata().unwrap();
+    assert_eq!(pushdown_automaton.check_input(&mut "".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "aabb".to_string()), true);
+    assert_eq!(pushdown_automaton.check_input(&mut "aab".to_string()), false);
+}
+
+#[test]
+fn linear_grammar_to_finite_automata_test() {
+    // Right-linear grammar of the strings over {a, b} that end in ab.
+    let grammar = Grammar::parse("S -> aS | bS | abA\nA -> ε").unwrap();
+    assert!(grammar.is_right_linear());
+    let finite_automaton = grammar.to_finite_automata().unwrap();
+    assert!(finite_automaton.get_states_by_id_ref().values().any(|state| state.name == "S1"));
+    assert_eq!(finite_automaton.check_input(&mut "ab".to_string()), true);
+    assert_eq!(finite_automaton.check_input(&mut "babab".to_string()), true);
+    assert_eq!(finite_automaton.check_input(&mut "aba".to_string()), false);
+    // Left-linear grammar of the strings a^n b with n >= 0.
+    let grammar = Grammar::parse("S -> Ab\nA -> Aa | ε").unwrap();
+    assert!(!grammar.is_right_linear());
+    assert!(grammar.is_left_linear());
+    let finite_automaton = grammar.to_finite_automata().unwrap();
+    assert_eq!(finite_automaton.check_input(&mut "b".to_string()), true);
+    assert_eq!(finite_automaton.check_input(&mut "aaab".to_string()), true);
+    assert_eq!(finite_automaton.check_input(&mut "aba".to_string()), false);
+    assert!(Grammar::parse("S -> aSb | ε").unwrap().to_finite_automata().is_none());
+}
+
+#[test]
+fn left_recursion_test() {
+    let grammar = Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+    assert_eq!(grammar.left_recursive_nonterminals(), ["E", "T"].iter().map(|s| s.to_string()).collect());
+    let without_left_recursion = grammar.eliminate_left_recursion();
+    assert!(!without_left_recursion.has_left_recursion());
+    assert_eq!(without_left_recursion.to_string(),
+               "E -> T E'\nE' -> + T E' | ε\nT -> F T'\nT' -> * F T' | ε\nF -> ( E ) | id\n");
+    // Indirect left recursion, also through a nullable nonterminal.
+    let grammar = Grammar::parse("S -> Aa | b\nA -> Sc | d").unwrap();
+    assert_eq!(grammar.left_recursive_nonterminals().len(), 2);
+    let without_left_recursion = grammar.eliminate_left_recursion();
+    assert!(!without_left_recursion.has_left_recursion());
+    for word in ["b", "da", "bca", "dacaca"] {
+        assert!(without_left_recursion.leftmost_derivation(word).found().is_some());
+    }
+    // The left recursion hidden by a nullable nonterminal and by a cycle.
+    let grammar = Grammar::parse("S -> BSa | b\nB -> ε").unwrap();
+    assert!(grammar.has_left_recursion());
+    assert!(!grammar.report().without_left_recursion.has_left_recursion());
+    let grammar = Grammar::parse("S -> A | a\nA -> S | Sb").unwrap();
+    let without_left_recursion = grammar.eliminate_left_recursion();
+    assert!(!without_left_recursion.has_left_recursion());
+    for word in ["a", "ab", "abbb"] {
+        assert!(without_left_recursion.leftmost_derivation(word).found().is_some());
+    }
+}
+
+#[test]
+fn left_factor_test() {
+    let grammar = Grammar::parse("S -> iEtS | iEtSeS | a\nE -> b").unwrap();
+    let factored = grammar.left_factor();
+    assert_eq!(factored.to_string(), "S -> iEtSS' | a\nS' -> ε | eS\nE -> b\n");
+    let grammar = Grammar::parse("A -> abc | abd | ae | f").unwrap();
+    assert_eq!(grammar.left_factor().to_string(), "A -> aA' | f\nA' -> bA'' | e\nA'' -> c | d\n");
+}
+
+#[test]
+fn grammar_report_test() {
+    let grammar = Grammar::parse("S -> Sa | A | ε\nA -> b\nB -> c\nC -> C").unwrap();
+    let report = grammar.report();
+    assert_eq!(report.nullable, ["S".to_string()].into_iter().collect());
+    assert!(!report.generating.contains("C"));
+    assert!(!report.reachable.contains("B"));
+    assert_eq!(report.useless, ["B", "C"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(report.left_recursive, ["C", "S"].iter().map(|s| s.to_string()).collect());
+    assert!(!report.without_left_recursion.has_left_recursion());
+    assert!(report.to_string().starts_with("Nullable: {S}\nGenerating: {A, B, S}\n"));
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use crate::jflap;
+use crate::machine_file::{FileMachine, MachineFile, MachineFileKind, Position};
+use crate::pushdown_automata::PushdownAutomata;
+use crate::state_machine::StateMachine;
+use crate::turing_machine::HaltingStatus;
+
+// A finite automaton saved by JFLAP 7 that accepts the words a*b, with a λ transition.
+const JFLAP_AUTOMATON: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.--><structure>
+	<type>fa</type>
+	<automaton>
+		<!--The list of states.-->
+		<state id="0" name="q0">
+			<x>66.0</x>
+			<y>103.0</y>
+			<initial/>
+		</state>
+		<state id="1" name="q1">
+			<x>180.0</x>
+			<y>103.0</y>
+		</state>
+		<state id="2" name="q2">
+			<x>294.5</x>
+			<y>103.0</y>
+			<final/>
+		</state>
+		<!--The list of transitions.-->
+		<transition>
+			<from>0</from>
+			<to>0</to>
+			<read>a</read>
+		</transition>
+		<transition>
+			<from>0</from>
+			<to>1</to>
+			<read/>
+		</transition>
+		<transition>
+			<from>1</from>
+			<to>2</to>
+			<read>b</read>
+		</transition>
+	</automaton>
+</structure>"#;
+
+#[test]
+fn read_jflap_automaton_test() {
+    let file = jflap::read_jff(JFLAP_AUTOMATON).unwrap();
+    assert_eq!(file.kind, MachineFileKind::Finite);
+    assert_eq!(file.positions()[&2], Position { x: 294.5, y: 103.0 });
+    assert_eq!(file.transitions[1].label, "ε");
+    let machine = match file.to_machine() {
+        FileMachine::Finite(machine) => machine,
+        other => panic!("expected a finite automaton, got {:?}", other.kind()),
+    };
+    assert_eq!(machine.get_initial_state_id(), &Some(0));
+    assert_eq!(machine.check_input(&mut "aab".to_string()), true);
+    assert_eq!(machine.check_input(&mut "ba".to_string()), false);
+    // Writing the file back gives the same file.
+    let text = jflap::write_jff(&file);
+    assert!(text.contains("<read/>"));
+    assert_eq!(jflap::read_jff(&text).unwrap(), file);
+}
+
+#[test]
+fn jflap_pushdown_and_turing_test() {
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(2);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(1);
+    pushdown_automaton.add_transition(0, 0, "a;Z/AZ".to_string());
+    pushdown_automaton.add_transition(0, 0, "a;A/AA".to_string());
+    pushdown_automaton.add_transition(0, 1, "ε;Z/Z".to_string());
+    pushdown_automaton.add_transition(1, 1, "b;A/ε".to_string());
+    let file = MachineFile::new(&FileMachine::Pushdown(pushdown_automaton), &HashMap::new());
+    let text = jflap::write_jff(&file);
+    assert!(text.contains("<type>pda</type>"));
+    assert!(text.contains("<push/>"));
+    let read_file = jflap::read_jff(&text).unwrap();
+    assert_eq!(read_file.transitions, file.transitions);
+
+    let text = r#"<structure><type>turing</type><automaton>
+        <state id="0" name="q0"><initial/></state><state id="1" name="q1"><final/></state>
+        <transition><from>0</from><to>0</to><read>a</read><write>b</write><move>R</move></transition>
+        <transition><from>0</from><to>1</to><read/><write/><move>S</move></transition>
+        </automaton></structure>"#;
+    let file = jflap::read_jff(text).unwrap();
+    assert_eq!(file.transitions[1].label, "_/_,S");
+    match file.to_machine() {
+        FileMachine::Turing(machine) => {
+            let run = machine.run("aa", 100);
+            assert_eq!(run.status, HaltingStatus::Accept);
+            assert_eq!(run.trace.last().unwrap().tape, vec!["b", "b", "_"]);
+        }
+        other => panic!("expected a Turing machine, got {:?}", other.kind()),
+    }
+    assert!(jflap::write_jff(&file).contains("<write/>"));
+}
+
+#[test]
+fn invalid_jflap_file_test() {
+    assert!(jflap::read_jff("<structure><type>fa</type>").is_err());
+    let error = jflap::read_jff("<structure><type>grammar</type></structure>").unwrap_err();
+    assert!(error.message.contains("grammar"));
+    let error = jflap::read_jff("<structure><type>turing</type><tapes>2</tapes></structure>").unwrap_err();
+    assert!(error.message.contains("one tape"));
+    let text = "<structure><type>fa</type><automaton><transition><from>0</from><to>1</to><read>a</read></transition></automaton></structure>";
+    assert!(jflap::read_jff(text).is_err());
+    // Nothing but comments and whitespace can follow the root element.
+    let error = jflap::read_jff("<structure><type>fa</type></structure>trailing").unwrap_err();
+    assert!(error.message.contains("after its root element"));
+    assert!(jflap::read_jff("<structure><type>fa</type></structure><structure/>").is_err());
+    assert!(jflap::read_jff("<structure><type>fa</type><automaton/></structure>\n<!-- end -->\n").is_ok());
+}
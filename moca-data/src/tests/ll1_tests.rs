@@ -0,0 +1,78 @@
+use crate::grammar::{Grammar, Production};
+use crate::ll1::{LL1Table, PredictiveAction};
+
+fn set(symbols: &[&str]) -> std::collections::BTreeSet<String> {
+    symbols.iter().map(|symbol| symbol.to_string()).collect()
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(|symbol| symbol.to_string()).collect()
+}
+
+// Usual grammar of the arithmetic expressions without left recursion.
+fn expression_grammar() -> Grammar {
+    Grammar::parse("E -> T E'\nE' -> + T E' | ε\nT -> F T'\nT' -> * F T' | ε\nF -> ( E ) | id").unwrap()
+}
+
+#[test]
+fn first_follow_test() {
+    let grammar = expression_grammar();
+    let first = grammar.first_sets();
+    assert_eq!(first["E"], set(&["(", "id"]));
+    assert_eq!(first["E'"], set(&["+", "ε"]));
+    assert_eq!(first["T'"], set(&["*", "ε"]));
+    let follow = grammar.follow_sets();
+    assert_eq!(follow["E"], set(&[")", "$"]));
+    assert_eq!(follow["E'"], set(&[")", "$"]));
+    assert_eq!(follow["T"], set(&["+", ")", "$"]));
+    assert_eq!(follow["F"], set(&["+", "*", ")", "$"]));
+    assert_eq!(grammar.first_of(&words("T' E'")), set(&["*", "+", "ε"]));
+}
+
+#[test]
+fn ll1_table_test() {
+    let table = LL1Table::new(&expression_grammar());
+    assert!(table.is_ll1());
+    assert_eq!(table.get_cell("E'", ")"), &[Production::new("E'", vec![])]);
+    assert_eq!(table.get_cell("F", "id"), &[Production::new("F", words("id"))]);
+    assert!(table.get_cell("F", "+").is_empty());
+    // The left recursion gives conflicts.
+    let table = LL1Table::new(&Grammar::parse("E -> E + T | T\nT -> id").unwrap());
+    assert!(!table.is_ll1());
+    let conflicts = table.get_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].to_string(), "M[E, id]: E -> E+T, E -> T");
+}
+
+#[test]
+fn predictive_parser_test() {
+    let table = LL1Table::new(&expression_grammar());
+    let steps = table.parse(&words("id + id * id"));
+    assert_eq!(steps.last().unwrap().action, PredictiveAction::Accept);
+    assert_eq!(steps[0].stack, words("$ E"));
+    assert_eq!(steps[0].action, PredictiveAction::Expand(Production::new("E", words("T E'"))));
+    assert_eq!(steps[1].stack, words("$ E' T"));
+    assert_eq!(steps[3].action, PredictiveAction::Match("id".to_string()));
+    assert_eq!(steps[4].remaining_input, words("+ id * id $"));
+    assert!(!table.accepts(&words("id + * id")));
+    assert!(!table.accepts(&words("( id")));
+    // The left recursion doesn't make the parser loop forever.
+    let table = LL1Table::new(&Grammar::parse("E -> E + T | T\nT -> id").unwrap());
+    assert!(!table.accepts(&words("id + id")));
+}
+
+#[test]
+fn end_marker_terminal_test() {
+    // The terminal $ is not the end of the input.
+    let grammar = Grammar::parse("S -> a S | $").unwrap();
+    assert_eq!(grammar.end_marker(), "$'");
+    assert_eq!(grammar.follow_sets()["S"], set(&["$'"]));
+    let table = LL1Table::new(&grammar);
+    assert!(table.is_ll1());
+    assert_eq!(table.get_columns(), words("$ a $'"));
+    assert_eq!(table.get_cell("S", "$"), &[Production::new("S", words("$"))]);
+    assert!(table.get_cell("S", "$'").is_empty());
+    assert!(table.accepts(&words("a a $")));
+    assert!(!table.accepts(&words("a a")));
+    assert_eq!(table.parse(&words("$"))[0].remaining_input, words("$ $'"));
+}
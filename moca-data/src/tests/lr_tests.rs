@@ -0,0 +1,74 @@
+use crate::grammar::{Grammar, Production};
+use crate::lr::{LR0Automaton, LRAction, LRTable};
+use crate::state_machine::StateMachine;
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(|symbol| symbol.to_string()).collect()
+}
+
+#[test]
+fn lr0_automaton_test() {
+    // Usual grammar of the arithmetic expressions, its automaton has 12 states.
+    let grammar = Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+    let automaton = LR0Automaton::new(&grammar);
+    assert_eq!(automaton.get_grammar().get_start_symbol(), "E'");
+    assert_eq!(automaton.get_states().len(), 12);
+    assert_eq!(automaton.get_states()[0].len(), 7);
+    assert_eq!(automaton.get_states()[0][0].to_string(), "E' -> ·E");
+    let state = automaton.get_transition(0, "E").unwrap();
+    assert_eq!(automaton.get_states()[state].iter().map(|item| item.to_string()).collect::<Vec<_>>(),
+               vec!["E' -> E·", "E -> E·+T"]);
+    let finite_automaton = automaton.to_finite_automata();
+    assert_eq!(finite_automaton.get_states_by_id_ref().len(), 12);
+    assert_eq!(finite_automaton.get_states_by_id_ref()[&0].name, "I0");
+    assert!(finite_automaton.get_final_states().contains(&(state as u64)));
+    assert!(finite_automaton.is_deterministic());
+}
+
+#[test]
+fn slr_table_test() {
+    let grammar = Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+    let automaton = LR0Automaton::new(&grammar);
+    let table = LRTable::slr(&automaton);
+    assert!(!table.has_conflicts());
+    let state = automaton.get_transition(0, "E").unwrap();
+    assert_eq!(table.get_action(state, "$"), &[LRAction::Accept]);
+    let state = automaton.get_transition(0, "T").unwrap();
+    assert_eq!(table.get_action(state, "+"), &[LRAction::Reduce(Production::new("E", words("T")))]);
+    assert_eq!(table.get_action(state, "*"), &[LRAction::Shift(automaton.get_transition(state, "*").unwrap())]);
+    assert_eq!(table.get_goto(0, "F"), automaton.get_transition(0, "F"));
+    // Ambiguous grammar, with shift/reduce conflicts.
+    let automaton = LR0Automaton::new(&Grammar::parse("E -> E + E | id").unwrap());
+    let conflicts = LRTable::slr(&automaton).get_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert!(conflicts[0].is_shift_reduce());
+    assert_eq!(conflicts[0].symbol, "+");
+}
+
+#[test]
+fn lalr_table_test() {
+    // Grammar that is LALR(1) but not SLR(1).
+    let grammar = Grammar::parse("S -> L = R | R\nL -> * R | id\nR -> L").unwrap();
+    let automaton = LR0Automaton::new(&grammar);
+    let conflicts = LRTable::slr(&automaton).get_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].symbol, "=");
+    assert!(conflicts[0].to_string().contains("shift/reduce"));
+    let table = LRTable::lalr(&automaton);
+    assert!(!table.has_conflicts());
+    let state = automaton.get_transition(0, "L").unwrap();
+    assert_eq!(table.get_action(state, "$"), &[LRAction::Reduce(Production::new("R", words("L")))]);
+    // Grammar that is LR(1) but not LALR(1), merging the states gives a reduce/reduce conflict.
+    let grammar = Grammar::parse("S -> aAd | bBd | aBe | bAe\nA -> c\nB -> c").unwrap();
+    let conflicts = LRTable::lalr(&LR0Automaton::new(&grammar)).get_conflicts();
+    assert_eq!(conflicts.len(), 2);
+    assert!(conflicts.iter().all(|conflict| !conflict.is_shift_reduce()));
+    // The terminal $ is shifted, the input ends with $'.
+    let automaton = LR0Automaton::new(&Grammar::parse("S -> a S | $").unwrap());
+    let table = LRTable::lalr(&automaton);
+    assert!(!table.has_conflicts());
+    let state = automaton.get_transition(0, "$").unwrap();
+    assert_eq!(table.get_action(0, "$"), &[LRAction::Shift(state)]);
+    assert_eq!(table.get_action(state, "$'"), &[LRAction::Reduce(Production::new("S", words("$")))]);
+    assert!(table.to_string().contains("$'"));
+}
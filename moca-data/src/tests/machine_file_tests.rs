@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use crate::finite_automata::FiniteAutomata;
+use crate::machine_file::{FileMachine, MachineFile, MachineFileKind, Position, ProjectFile, ProjectTab, FORMAT_VERSION};
+use crate::moore_machine::MooreMachine;
+use crate::pushdown_automata::PushdownAutomata;
+use crate::state_machine::StateMachine;
+
+#[test]
+fn finite_automaton_file_test() {
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(2);
+    automaton.make_initial(0);
+    automaton.make_final(1);
+    automaton.add_transition(0, 1, "a".to_string());
+    automaton.add_transition(1, 1, "b".to_string());
+    automaton.add_transition(1, 1, "a".to_string());
+    let mut positions = HashMap::new();
+    positions.insert(0, Position { x: 100.0, y: 80.0 });
+    let file = MachineFile::new(&FileMachine::Finite(automaton.clone()), &positions);
+    assert_eq!(file.version, FORMAT_VERSION);
+    assert_eq!(file.kind, MachineFileKind::Finite);
+    let json = file.to_json();
+    assert!(json.contains("\"kind\": \"finite\""));
+    assert!(json.contains("\"final\": true"));
+    // Reading the file back gives the same machine and positions.
+    let read_file = MachineFile::from_json(&json).unwrap();
+    assert_eq!(read_file, file);
+    assert_eq!(read_file.positions(), positions);
+    let machine = match read_file.to_machine() {
+        FileMachine::Finite(machine) => machine,
+        other => panic!("expected a finite automaton, got {:?}", other.kind()),
+    };
+    assert_eq!(machine.get_states_by_id_ref(), automaton.get_states_by_id_ref());
+    assert_eq!(machine.get_initial_state_id(), &Some(0));
+    assert_eq!(machine.check_input(&mut "abab".to_string()), true);
+    assert_eq!(machine.check_input(&mut "b".to_string()), false);
+}
+
+#[test]
+fn pushdown_and_moore_file_test() {
+    let mut pushdown_automaton = PushdownAutomata::new("⊥".to_string());
+    pushdown_automaton.add_n_states(1);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.set_empty_stack_acceptance(true);
+    pushdown_automaton.add_transition(0, 0, "a;⊥/ε".to_string());
+    let json = MachineFile::new(&FileMachine::Pushdown(pushdown_automaton), &HashMap::new()).to_json();
+    match MachineFile::from_json(&json).unwrap().to_machine() {
+        FileMachine::Pushdown(machine) => {
+            assert_eq!(machine.get_initial_stack_symbol(), "⊥");
+            assert!(machine.accepts_by_empty_stack());
+            assert_eq!(machine.check_input(&mut "a".to_string()), true);
+        }
+        other => panic!("expected a pushdown automaton, got {:?}", other.kind()),
+    }
+    let mut moore_machine = MooreMachine::new();
+    moore_machine.add_n_states(2);
+    moore_machine.make_initial(0);
+    moore_machine.set_output(1, "x".to_string());
+    moore_machine.add_transition(0, 1, "a".to_string());
+    let json = MachineFile::new(&FileMachine::Moore(moore_machine), &HashMap::new()).to_json();
+    match MachineFile::from_json(&json).unwrap().to_machine() {
+        FileMachine::Moore(machine) => assert_eq!(machine.run("a"), Some("x".to_string())),
+        other => panic!("expected a Moore machine, got {:?}", other.kind()),
+    }
+}
+
+#[test]
+fn invalid_file_test() {
+    let error = MachineFile::from_json("{ \"version\": 1, \"kind\": \"finite\", ").unwrap_err();
+    assert!(error.message.contains("line 1"));
+    let error = MachineFile::from_json(r#"{ "version": 2, "kind": "finite", "states": [], "transitions": [] }"#).unwrap_err();
+    assert!(error.message.contains("version 2"));
+    let error = MachineFile::from_json(r#"{ "version": 1, "kind": "circuit", "states": [], "transitions": [] }"#).unwrap_err();
+    assert!(error.message.contains("circuit"));
+    let text = r#"{ "version": 1, "kind": "finite", "states": [{ "id": 0, "name": "q0" }],
+        "transitions": [{ "from": 0, "to": 1, "label": "a" }] }"#;
+    assert!(MachineFile::from_json(text).is_err());
+    let text = r#"{ "version": 1, "kind": "finite", "states": [{ "id": 0, "name": "q0" }, { "id": 0, "name": "q1" }],
+        "transitions": [] }"#;
+    assert_eq!(MachineFile::from_json(text).unwrap_err().message, "the state id 0 is repeated");
+}
+
+#[test]
+fn project_file_test() {
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(1);
+    automaton.make_initial(0);
+    let mut positions = HashMap::new();
+    positions.insert(0, Position { x: 50.0, y: 60.0 });
+    let machine = MachineFile::new(&FileMachine::Finite(automaton), &positions);
+    let tabs = vec![
+        ProjectTab { name: "Machine 1".to_string(), machine: machine.clone() },
+        ProjectTab { name: "Machine 2".to_string(), machine },
+    ];
+    let project = ProjectFile::new(tabs, 1);
+    let read_project = ProjectFile::from_json(&project.to_json()).unwrap();
+    assert_eq!(read_project, project);
+    assert_eq!(read_project.active_tab, 1);
+    assert_eq!(read_project.tabs[1].machine.positions(), positions);
+    // The machine of every tab is checked.
+    let text = r#"{ "version": 1, "tabs": [{ "name": "A", "machine": { "version": 1, "kind": "finite",
+        "states": [{ "id": 0, "name": "q0", "initial": true }, { "id": 1, "name": "q1", "initial": true }], "transitions": [] } }] }"#;
+    assert_eq!(ProjectFile::from_json(text).unwrap_err().message, "tab A: the machine has more than one initial state");
+}
@@ -0,0 +1,81 @@
+use crate::mealy_machine::{self, MealyMachine};
+use crate::state_machine::StateMachine;
+
+// Machine that writes 1 when the symbol read is equal to the previous one, and 0 otherwise.
+// The states q1 and q3 are equivalent, and q4 is unreachable.
+fn repeated_symbol_machine() -> MealyMachine {
+    let mut mealy_machine = MealyMachine::new();
+    mealy_machine.add_n_states(5);
+    mealy_machine.make_initial(0);
+    mealy_machine.add_transition(0, 1, "0/0".to_string());
+    mealy_machine.add_transition(0, 2, "1/0".to_string());
+    mealy_machine.add_transition(1, 1, "0/1".to_string());
+    mealy_machine.add_transition(1, 2, "1/0".to_string());
+    mealy_machine.add_transition(2, 3, "0/0".to_string());
+    mealy_machine.add_transition(2, 2, "1/1".to_string());
+    mealy_machine.add_transition(3, 1, "0/1".to_string());
+    mealy_machine.add_transition(3, 2, "1/0".to_string());
+    mealy_machine.add_transition(4, 0, "0/1".to_string());
+    mealy_machine
+}
+
+// Returns every word over 0 and 1 with at most max_length symbols.
+fn binary_words(max_length: usize) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut last = vec![String::new()];
+    for _ in 0..max_length {
+        last = last.iter().flat_map(|word| [format!("{}0", word), format!("{}1", word)]).collect();
+        words.extend(last.iter().cloned());
+    }
+    words
+}
+
+#[test]
+fn parse_mealy_transition_label_test() {
+    assert_eq!(mealy_machine::parse_transition_label("a/01"), Some(("a".to_string(), "01".to_string())));
+    assert_eq!(mealy_machine::parse_transition_label(" a / ε "), Some(("a".to_string(), "".to_string())));
+    assert_eq!(mealy_machine::parse_transition_label("a/"), Some(("a".to_string(), "".to_string())));
+    assert_eq!(mealy_machine::parse_transition_label("ab/1"), None);
+    assert_eq!(mealy_machine::parse_transition_label("a"), None);
+}
+
+#[test]
+fn run_mealy_test() {
+    let mut mealy_machine = repeated_symbol_machine();
+    assert!(mealy_machine.is_deterministic());
+    assert_eq!(mealy_machine.run(""), Some("".to_string()));
+    assert_eq!(mealy_machine.run("0010"), Some("0100".to_string()));
+    assert_eq!(mealy_machine.run("01100"), Some("00101".to_string()));
+    assert_eq!(mealy_machine.run("012"), None);
+    mealy_machine.add_transition(0, 2, "0/1".to_string());
+    assert!(!mealy_machine.is_deterministic());
+}
+
+#[test]
+fn minimize_mealy_test() {
+    let mealy_machine = repeated_symbol_machine();
+    let minimized_machine = mealy_machine.minimize();
+    assert_eq!(minimized_machine.get_states_by_id_ref().len(), 3);
+    assert_eq!(minimized_machine.get_initial_state_id(), &Some(0));
+    for word in binary_words(6) {
+        assert_eq!(minimized_machine.run(&word), mealy_machine.run(&word), "{}", word);
+    }
+    // The equivalent states are merged in one state.
+    let labels: Vec<Vec<u64>> = (0..3)
+        .map(|id| minimized_machine.get_states_by_id_ref()[&id].label.iter().cloned().collect())
+        .collect();
+    assert_eq!(labels, vec![vec![0], vec![1, 3], vec![2]]);
+}
+
+#[test]
+fn mealy_to_moore_test() {
+    let mealy_machine = repeated_symbol_machine();
+    let moore_machine = mealy_machine.to_moore();
+    // The states q1 and q2 are entered writing 0 and 1, q3 only writing 0.
+    assert_eq!(moore_machine.get_states_by_id_ref().len(), 6);
+    for word in binary_words(6) {
+        assert_eq!(moore_machine.run(&word), mealy_machine.run(&word), "{}", word);
+    }
+    let names: Vec<String> = (0..6).map(|id| moore_machine.get_states_by_id_ref()[&id].name.clone()).collect();
+    assert_eq!(names, vec!["q0", "q1/0", "q2/0", "q1/1", "q3/0", "q2/1"]);
+}
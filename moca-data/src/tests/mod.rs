@@ -1,2 +1,20 @@
+mod ambiguity_tests;
+mod automaton_text_tests;
+mod cli_tests;
+mod cyk_tests;
+mod dot_tests;
+mod earley_tests;
 mod finite_automata_tests;
+mod generator_tests;
+mod grammar_tests;
+mod jflap_tests;
+mod ll1_tests;
+mod lr_tests;
+mod machine_file_tests;
+mod mealy_machine_tests;
+mod moore_machine_tests;
+mod multi_tape_turing_machine_tests;
 mod pushdown_automata_tests;
+mod regular_expression_tests;
+mod test_suite_tests;
+mod turing_machine_tests;
@@ -0,0 +1,48 @@
+use crate::moore_machine::MooreMachine;
+use crate::state_machine::StateMachine;
+
+// Machine that writes the number of 1 read so far modulo 3, starting with 0.
+fn counter_machine() -> MooreMachine {
+    let mut moore_machine = MooreMachine::new();
+    moore_machine.add_n_states(3);
+    moore_machine.make_initial(0);
+    for id in 0..3 {
+        moore_machine.set_output(id, id.to_string());
+        moore_machine.add_transition(id, id, "0".to_string());
+        moore_machine.add_transition(id, (id + 1) % 3, "1".to_string());
+    }
+    moore_machine
+}
+
+#[test]
+fn run_moore_test() {
+    let mut moore_machine = counter_machine();
+    assert!(moore_machine.is_deterministic());
+    assert_eq!(moore_machine.run(""), Some("0".to_string()));
+    assert_eq!(moore_machine.run("1101"), Some("01220".to_string()));
+    assert_eq!(moore_machine.run("12"), None);
+    // A state without output writes nothing.
+    moore_machine.set_output(1, "ε".to_string());
+    assert_eq!(moore_machine.get_output(1), "");
+    assert_eq!(moore_machine.run("1101"), Some("0220".to_string()));
+    // Transitions must read a single symbol.
+    moore_machine.add_transition(0, 1, "01".to_string());
+    assert!(moore_machine.transition(0, "01").is_none());
+    moore_machine.add_transition(0, 2, "0".to_string());
+    assert!(!moore_machine.is_deterministic());
+}
+
+#[test]
+fn moore_to_mealy_test() {
+    let moore_machine = counter_machine();
+    let mealy_machine = moore_machine.to_mealy();
+    assert_eq!(mealy_machine.get_states_by_id_ref().len(), 3);
+    for word in ["", "0", "1", "1101", "011011", "111"] {
+        let moore_output = moore_machine.run(word).unwrap();
+        // The output of the initial state is not written by the Mealy machine.
+        assert_eq!(mealy_machine.run(word).unwrap(), moore_output[1..], "{}", word);
+    }
+    // Back to a Moore machine, the initial state writes nothing.
+    let back_machine = mealy_machine.to_moore();
+    assert_eq!(back_machine.run("1101"), Some("1220".to_string()));
+}
@@ -0,0 +1,97 @@
+use crate::multi_tape_turing_machine::{self, MultiTapeTuringMachine};
+use crate::state_machine::StateMachine;
+use crate::turing_machine::{HaltingStatus, TapeMove};
+
+// Machine with two tapes that accepts the strings a^n b^n with n >= 1, copying
+// the a's to the second tape and crossing them out with the b's.
+fn anbn_machine() -> MultiTapeTuringMachine {
+    let mut turing_machine = MultiTapeTuringMachine::new("_".to_string(), 2);
+    turing_machine.add_n_states(3);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(2);
+    turing_machine.add_transition(0, 0, "a/a,R|_/a,R".to_string());
+    turing_machine.add_transition(0, 1, "b/b,S|_/_,L".to_string());
+    turing_machine.add_transition(1, 1, "b/b,R|a/a,L".to_string());
+    turing_machine.add_transition(1, 2, "_/_,S|_/_,S".to_string());
+    turing_machine
+}
+
+#[test]
+fn parse_multi_tape_transition_label_test() {
+    assert_eq!(multi_tape_turing_machine::parse_transition_label("a/b,R|_/a,L"), Some(vec![
+        ("a".to_string(), "b".to_string(), TapeMove::Right),
+        ("_".to_string(), "a".to_string(), TapeMove::Left),
+    ]));
+    assert_eq!(multi_tape_turing_machine::parse_transition_label("a/b,R|_/a"), None);
+    // Labels without a part for every tape are not added.
+    let mut turing_machine = anbn_machine();
+    turing_machine.add_transition(0, 2, "a/a,R".to_string());
+    assert!(turing_machine.get_states_by_id_ref()[&0].iter_by_transition().all(|(to_id, _)| *to_id != 2));
+}
+
+#[test]
+fn run_multi_tape_test() {
+    let turing_machine = anbn_machine();
+    assert!(turing_machine.is_deterministic());
+    assert_eq!(turing_machine.check_input(&mut "ab".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "aaabbb".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "".to_string()), false);
+    assert_eq!(turing_machine.check_input(&mut "aab".to_string()), false);
+    assert_eq!(turing_machine.check_input(&mut "abb".to_string()), false);
+    assert_eq!(turing_machine.check_input(&mut "ba".to_string()), false);
+    let run = turing_machine.run("ab", 100);
+    assert_eq!(run.status, HaltingStatus::Accept);
+    let descriptions: Vec<String> = run.trace.iter()
+        .map(|configuration| turing_machine.format_configuration(configuration))
+        .collect();
+    assert_eq!(descriptions, vec!["q0: [a]b | [_]", "q0: a[b] | a[_]", "q1: a[b] | [a]", "q1: ab[_] | [_]a", "q2: ab[_] | [_]a"]);
+    assert_eq!(turing_machine.run("aab", 100).status, HaltingStatus::Reject);
+}
+
+#[test]
+fn to_single_tape_test() {
+    let turing_machine = anbn_machine();
+    let single_tape = turing_machine.to_single_tape();
+    assert!(single_tape.is_deterministic());
+    for word in ["", "a", "b", "ab", "ba", "aab", "abb", "aabb", "abab", "aaabbb"] {
+        assert_eq!(single_tape.check_input(&mut word.to_string()), turing_machine.check_input(&mut word.to_string()), "{}", word);
+    }
+    // The simulation keeps the names of the states at the start of every step.
+    let run = single_tape.run("ab", 1000);
+    let last = run.trace.last().unwrap();
+    assert_eq!(single_tape.get_states_by_id_ref()[&last.state_id].name, "q2");
+    assert_eq!(single_tape.tape_contents(last), "[_|_^][a|a][b|_][_^|_]");
+}
+
+#[test]
+fn run_nondeterministic_test() {
+    // Machine that guesses where the string ab starts, copying the symbols before it to the second tape.
+    let mut turing_machine = MultiTapeTuringMachine::new("_".to_string(), 2);
+    turing_machine.add_n_states(3);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(2);
+    turing_machine.add_transition(0, 0, "a/a,R|_/a,R".to_string());
+    turing_machine.add_transition(0, 0, "b/b,R|_/b,R".to_string());
+    turing_machine.add_transition(0, 1, "a/a,R|_/_,S".to_string());
+    turing_machine.add_transition(1, 2, "b/b,R|_/_,S".to_string());
+    let run = turing_machine.run("bbab", 100);
+    assert_eq!(run.status, HaltingStatus::Accept);
+    assert_eq!(run.trace.len(), 5);
+    assert_eq!(turing_machine.format_configuration(run.trace.last().unwrap()), "q2: bbab[_] | bb[_]");
+    assert!(turing_machine.check_input(&mut "bbab".to_string()));
+    assert!(!turing_machine.check_input(&mut "ba".to_string()));
+    assert_eq!(turing_machine.run("ba", 100).status, HaltingStatus::Reject);
+    // A machine that can always take another step runs out of the budget.
+    turing_machine.add_transition(0, 0, "_/_,R|_/_,R".to_string());
+    assert_eq!(turing_machine.run("ba", 100).status, HaltingStatus::Timeout);
+    // Two runs that meet in the same configuration and halt without accepting.
+    let mut turing_machine = MultiTapeTuringMachine::new("_".to_string(), 2);
+    turing_machine.add_n_states(5);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(4);
+    turing_machine.add_transition(0, 1, "a/a,R|_/x,S".to_string());
+    turing_machine.add_transition(0, 2, "a/a,R|_/x,S".to_string());
+    turing_machine.add_transition(1, 3, "b/b,S|x/x,S".to_string());
+    turing_machine.add_transition(2, 3, "b/b,S|x/x,S".to_string());
+    assert_eq!(turing_machine.run("ab", 100).status, HaltingStatus::Reject);
+}
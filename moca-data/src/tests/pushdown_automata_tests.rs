@@ -1,7 +1,10 @@
+use crate::earley::EarleyChart;
+use crate::grammar::{DerivationSearch, Grammar};
 use crate::state::State;
 use crate::pushdown_automata::{self, PushdownAutomata};
 use crate::state_machine::StateMachine;
 use crate::state;
+use crate::turing_machine::HaltingStatus;
 
 /* Several methods and functions are the same as the finite automaton
  * So the tests are only for the different methods. */
@@ -46,3 +49,152 @@ fn check_input_dpa_test() {
     pushdown_automaton.add_transition(4, 2, ";c/Z".to_string());
     assert_eq!(pushdown_automaton.check_input(&mut "ab".to_string()), true);
 }
+
+#[test]
+fn check_input_epsilon_pop_test() {
+    // This automata is used as an example Q) in https://www.geeksforgeeks.org/construct-pushdown-automata-given-languages/
+    // It accepts the strings with as many a as b. The example writes the empty stack string as Z,
+    // which here is a stack symbol, so it is written as ε.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(5);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(2);
+    pushdown_automaton.add_transition(0, 1, "ε;ε/c".to_string());
+    pushdown_automaton.add_transition(1, 2, ";c/ε".to_string());
+    pushdown_automaton.add_transition(1, 3, "a;ε/a".to_string());
+    pushdown_automaton.add_transition(1, 4, "b;ε/b".to_string());
+    pushdown_automaton.add_transition(3, 3, "a;ε/a".to_string());
+    pushdown_automaton.add_transition(3, 3, "b;a/ε".to_string());
+    pushdown_automaton.add_transition(3, 4, "b;c/bc".to_string());
+    pushdown_automaton.add_transition(4, 4, "a;b/ε".to_string());
+    pushdown_automaton.add_transition(4, 4, "b;ε/b".to_string());
+    pushdown_automaton.add_transition(4, 3, "a;c/ac".to_string());
+    pushdown_automaton.add_transition(3, 2, ";c/ε".to_string());
+    pushdown_automaton.add_transition(4, 2, ";c/ε".to_string());
+    assert!(pushdown_automaton.check_input(&mut "ab".to_string()));
+    assert!(pushdown_automaton.check_input(&mut "abba".to_string()));
+    assert!(!pushdown_automaton.check_input(&mut "aab".to_string()));
+}
+
+#[test]
+fn run_trace_test() {
+    // Automaton that recognizes strings of the form a^n b^n with n >= 1.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(3);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(2);
+    pushdown_automaton.add_transition(0, 0, "a;Z/AZ".to_string());
+    pushdown_automaton.add_transition(0, 0, "a;A/AA".to_string());
+    pushdown_automaton.add_transition(0, 1, "b;A/ε".to_string());
+    pushdown_automaton.add_transition(1, 1, "b;A/ε".to_string());
+    pushdown_automaton.add_transition(1, 2, "ε;Z/Z".to_string());
+    assert_eq!(pushdown_automaton.run("aabb").trace.len(), 6);
+    let run = pushdown_automaton.run("aab");
+    assert_eq!(run.status, HaltingStatus::Reject);
+    assert_eq!(run.trace, Vec::new());
+    let trace = pushdown_automaton.run("ab").trace;
+    assert_eq!(trace[1].stack, vec!["Z".to_string(), "A".to_string()]);
+    assert_eq!(trace[2].remaining_input, "");
+    assert_eq!(trace[3].state_id, 2);
+    // Without initial state every input is rejected.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(1);
+    assert_eq!(pushdown_automaton.run("").status, HaltingStatus::Reject);
+}
+
+#[test]
+fn run_bounds_test() {
+    // The automata built from grammars with ε-moves that push forever drop the stacks that the
+    // input left can't pop.
+    let pushdown_automaton = Grammar::parse("E -> E+T | T\nT -> a").unwrap().to_pushdown_automata();
+    assert_eq!(pushdown_automaton.run("a+").status, HaltingStatus::Reject);
+    assert_eq!(pushdown_automaton.run("a+a+a").status, HaltingStatus::Accept);
+    let pushdown_automaton = Grammar::parse("S -> SS").unwrap().to_pushdown_automata();
+    assert_eq!(pushdown_automaton.run("aa").status, HaltingStatus::Reject);
+    let pushdown_automaton = Grammar::parse("S -> SS | a").unwrap().to_pushdown_automata();
+    assert_eq!(pushdown_automaton.run("aaaa").status, HaltingStatus::Accept);
+    assert_eq!(pushdown_automaton.run("aaab").status, HaltingStatus::Reject);
+    // Otherwise the search gives up.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(2);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(1);
+    pushdown_automaton.add_transition(0, 0, "ε;ε/A".to_string());
+    pushdown_automaton.add_transition(0, 1, "a;ε/ε".to_string());
+    let run = pushdown_automaton.run("b");
+    assert_eq!(run.status, HaltingStatus::Timeout);
+    assert_eq!(pushdown_automaton.check_input(&mut "b".to_string()), false);
+}
+
+#[test]
+fn parse_transition_label_test() {
+    assert_eq!(pushdown_automata::parse_transition_label("a;Z/AZ"),
+               Some(("a".to_string(), "Z".to_string(), "AZ".to_string())));
+    assert_eq!(pushdown_automata::parse_transition_label(";c/Z"),
+               Some(("ε".to_string(), "c".to_string(), "Z".to_string())));
+    assert!(pushdown_automata::parse_transition_label("ε").is_some());
+    assert_eq!(pushdown_automata::parse_transition_label("a"), None);
+    assert_eq!(pushdown_automata::parse_transition_label("a;Z"), None);
+    assert_eq!(pushdown_automata::parse_transition_label("a;/Z"), None);
+    assert_eq!(pushdown_automata::parse_transition_label("/;[/]/ε"),
+               Some(("/".to_string(), "[/]".to_string(), "ε".to_string())));
+    assert_eq!(pushdown_automata::parse_transition_label(";;[;]/[;]A"),
+               Some((";".to_string(), "[;]".to_string(), "[;]A".to_string())));
+    assert_eq!(pushdown_automata::split_stack_symbols("AZ"), vec!["A", "Z"]);
+    assert_eq!(pushdown_automata::split_stack_symbols("[pAq]A'"), vec!["[pAq]", "A'"]);
+    assert_eq!(pushdown_automata::split_stack_symbols("S0 a S0"), vec!["S0", "a", "S0"]);
+    assert!(pushdown_automata::split_stack_symbols("ε").is_empty());
+}
+
+#[test]
+fn to_grammar_test() {
+    // Automaton that recognizes strings of the form a^n b^n with n >= 1.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(3);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(2);
+    pushdown_automaton.add_transition(0, 0, "a;Z/AZ".to_string());
+    pushdown_automaton.add_transition(0, 0, "a;A/AA".to_string());
+    pushdown_automaton.add_transition(0, 1, "b;A/ε".to_string());
+    pushdown_automaton.add_transition(1, 1, "b;A/ε".to_string());
+    pushdown_automaton.add_transition(1, 2, "ε;Z/Z".to_string());
+    let grammar = pushdown_automaton.to_grammar();
+    assert!(grammar.get_nonterminals().iter().all(|nonterminal| nonterminal == "S" || nonterminal.starts_with('[')));
+    assert!(grammar.leftmost_derivation("ab").found().is_some());
+    assert!(grammar.leftmost_derivation("aabb").found().is_some());
+    assert!(grammar.leftmost_derivation("") == DerivationSearch::NotDerivable);
+    assert!(grammar.leftmost_derivation("aab") == DerivationSearch::NotDerivable);
+    assert!(grammar.leftmost_derivation("ba") == DerivationSearch::NotDerivable);
+    // The transitions that don't pop or pop several symbols.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(2);
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(1);
+    pushdown_automaton.add_transition(0, 0, "a;ε/A".to_string());
+    pushdown_automaton.add_transition(0, 1, "b;AA/ε".to_string());
+    let grammar = pushdown_automaton.to_grammar();
+    assert!(grammar.leftmost_derivation("aab").found().is_some());
+    assert!(grammar.leftmost_derivation("aaab").found().is_some());
+    assert!(grammar.leftmost_derivation("ab") == DerivationSearch::NotDerivable);
+    // Without an initial state the language is empty.
+    assert!(PushdownAutomata::new("Z".to_string()).to_grammar().get_productions().is_empty());
+}
+
+#[test]
+fn to_grammar_duplicate_names_test() {
+    // Two states named q, the automaton only accepts a.
+    let mut pushdown_automaton = PushdownAutomata::new("Z".to_string());
+    pushdown_automaton.add_n_states(2);
+    pushdown_automaton.modify_name(0, "q".to_string());
+    pushdown_automaton.modify_name(1, "q".to_string());
+    pushdown_automaton.make_initial(0);
+    pushdown_automaton.make_final(1);
+    pushdown_automaton.add_transition(0, 1, "a;Z/Z".to_string());
+    let grammar = pushdown_automaton.to_grammar();
+    for word in ["", "a", "aa", "aaa"] {
+        let symbols: Vec<String> = word.chars().map(|c| c.to_string()).collect();
+        assert_eq!(EarleyChart::new(&grammar, &symbols).is_accepted(),
+                   pushdown_automaton.check_input(&mut word.to_string()), "{}", word);
+    }
+    assert!(grammar.get_nonterminals().iter().any(|nonterminal| nonterminal.starts_with("[q',")));
+}
@@ -0,0 +1,61 @@
+use crate::finite_automata::FiniteAutomata;
+use crate::regular_expression::{RegexParseError, RegularExpression};
+use crate::state_machine::StateMachine;
+
+#[test]
+fn parse_regular_expression_test() {
+    let expression = RegularExpression::parse("(a | b)* a b b").unwrap();
+    assert_eq!(expression.to_string(), "(a+b)*abb");
+    assert_eq!(RegularExpression::parse(&expression.to_string()).unwrap(), expression);
+    assert_eq!(RegularExpression::parse("a+ε").unwrap().to_string(), "a+ε");
+    assert_eq!(RegularExpression::parse("λ").unwrap(), RegularExpression::Epsilon);
+    // The characters of the syntax can be escaped to be read as symbols.
+    let expression = RegularExpression::parse("\\+\\*").unwrap();
+    assert_eq!(expression, RegularExpression::Concat(
+        Box::new(RegularExpression::Symbol("+".to_string())),
+        Box::new(RegularExpression::Symbol("*".to_string())),
+    ));
+    assert_eq!(expression.to_string(), "\\+\\*");
+    assert_eq!(RegularExpression::parse("(ab").unwrap_err(), RegexParseError { position: 3, message: "expected )".to_string() });
+    assert_eq!(RegularExpression::parse("a+*").unwrap_err().position, 2);
+    assert_eq!(RegularExpression::parse("ab)").unwrap_err().position, 2);
+    assert!(RegularExpression::parse("  ").is_err());
+}
+
+#[test]
+fn regular_expression_to_finite_automata_test() {
+    let automaton = RegularExpression::parse("(a+b)*abb").unwrap().to_finite_automata();
+    assert_eq!(automaton.is_deterministic(), false);
+    for word in ["abb", "aabb", "babb", "ababb"] {
+        assert_eq!(automaton.check_input(&mut word.to_string()), true, "{}", word);
+    }
+    for word in ["", "ab", "abba", "bbb"] {
+        assert_eq!(automaton.check_input(&mut word.to_string()), false, "{}", word);
+    }
+    let automaton = RegularExpression::parse("∅").unwrap().to_finite_automata();
+    assert_eq!(automaton.check_input(&mut "".to_string()), false);
+    let automaton = RegularExpression::parse("a*").unwrap().to_finite_automata();
+    assert_eq!(automaton.check_input(&mut "".to_string()), true);
+    assert_eq!(automaton.check_input(&mut "aaa".to_string()), true);
+}
+
+#[test]
+fn finite_automata_to_regular_expression_test() {
+    // Automaton of the words with an even number of a.
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(2);
+    automaton.make_initial(0);
+    automaton.make_final(0);
+    automaton.add_transition(0, 0, "b".to_string());
+    automaton.add_transition(0, 1, "a".to_string());
+    automaton.add_transition(1, 1, "b".to_string());
+    automaton.add_transition(1, 0, "a".to_string());
+    let expression = RegularExpression::from_finite_automata(&automaton);
+    assert_eq!(expression.to_string(), "b*+b*a(b+ab*a)*ab*");
+    assert_eq!(expression.to_finite_automata().distinguishing_word(&automaton), None);
+    // Without final states the language is empty.
+    let mut automaton = FiniteAutomata::new();
+    automaton.add_n_states(1);
+    automaton.make_initial(0);
+    assert_eq!(RegularExpression::from_finite_automata(&automaton), RegularExpression::Empty);
+}
@@ -0,0 +1,34 @@
+use crate::automaton_text;
+use crate::test_suite::{Expectation, TestCase, TestSuite, TestSuiteError};
+
+#[test]
+fn parse_test_suite_test() {
+    let text = "# Words with an even number of a.\naccept ε\n\n  accept abba  \nreject ab\nreject\n";
+    let suite = TestSuite::parse(text).unwrap();
+    assert_eq!(suite.cases[0], TestCase { word: "".to_string(), expected: Expectation::Accept, line: 2 });
+    assert_eq!(suite.cases[1], TestCase { word: "abba".to_string(), expected: Expectation::Accept, line: 4 });
+    assert_eq!(suite.cases[3], TestCase { word: "".to_string(), expected: Expectation::Reject, line: 6 });
+    assert_eq!(suite.to_string(), "accept ε\naccept abba\nreject ab\nreject ε\n");
+    assert_eq!(TestSuite::parse(&suite.to_string()).unwrap().cases.len(), 4);
+    assert_eq!(TestSuite::parse("accept a\naccepts b").unwrap_err(), TestSuiteError {
+        line: 2,
+        message: "expected accept or reject, found accepts".to_string(),
+    });
+    assert_eq!(TestSuite::parse("reject a b").unwrap_err().line, 1);
+}
+
+#[test]
+fn run_test_suite_test() {
+    let file = automaton_text::parse_automaton("initial q0; final q0\nq0 -b-> q0; q0 -a-> q1\nq1 -b-> q1; q1 -a-> q0").unwrap();
+    let suite = TestSuite::parse("accept ε\naccept abba\nreject ab\naccept a\nreject aa").unwrap();
+    let report = suite.run(&file.to_machine());
+    let passed: Vec<bool> = report.results.iter().map(|result| result.passed()).collect();
+    assert_eq!(passed, vec![true, true, true, false, false]);
+    assert_eq!(report.passed_count(), 3);
+    assert_eq!(report.all_passed(), false);
+    assert_eq!(report.to_string(), "3 of 5 cases passed\nline 4: a was rejected, expected accept\nline 5: aa was accepted, expected reject\n");
+    // A machine without initial state accepts no word.
+    let file = automaton_text::parse_automaton("final q0").unwrap();
+    let report = TestSuite::parse("reject ε\nreject a").unwrap().run(&file.to_machine());
+    assert_eq!(report.all_passed(), true);
+}
@@ -0,0 +1,109 @@
+use crate::state_machine::StateMachine;
+use crate::turing_machine::{self, HaltingStatus, TapeMove, TuringMachine};
+
+// Machine that accepts the strings a^n b^n, marking every a with X and its b with Y.
+fn anbn_machine() -> TuringMachine {
+    let mut turing_machine = TuringMachine::new("_".to_string());
+    turing_machine.add_n_states(5);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(4);
+    turing_machine.add_transition(0, 1, "a/X,R".to_string());
+    turing_machine.add_transition(0, 3, "Y/Y,R".to_string());
+    turing_machine.add_transition(0, 4, "_/_,S".to_string());
+    turing_machine.add_transition(1, 1, "a/a,R".to_string());
+    turing_machine.add_transition(1, 1, "Y/Y,R".to_string());
+    turing_machine.add_transition(1, 2, "b/Y,L".to_string());
+    turing_machine.add_transition(2, 2, "a/a,L".to_string());
+    turing_machine.add_transition(2, 2, "Y/Y,L".to_string());
+    turing_machine.add_transition(2, 0, "X/X,R".to_string());
+    turing_machine.add_transition(3, 3, "Y/Y,R".to_string());
+    turing_machine.add_transition(3, 4, "_/_,S".to_string());
+    turing_machine
+}
+
+#[test]
+fn parse_transition_label_test() {
+    assert_eq!(turing_machine::parse_transition_label("a/b,R"), Some(("a".to_string(), "b".to_string(), TapeMove::Right)));
+    assert_eq!(turing_machine::parse_transition_label(" _ / 1 , L "), Some(("_".to_string(), "1".to_string(), TapeMove::Left)));
+    assert_eq!(turing_machine::parse_transition_label("a/a,S").map(|(_, _, direction)| direction), Some(TapeMove::Stay));
+    assert_eq!(turing_machine::parse_transition_label("a/b"), None);
+    assert_eq!(turing_machine::parse_transition_label("a,R"), None);
+    assert_eq!(turing_machine::parse_transition_label("a/b,X"), None);
+    assert_eq!(turing_machine::parse_transition_label("/b,R"), None);
+}
+
+#[test]
+fn check_input_turing_machine_test() {
+    let turing_machine = anbn_machine();
+    assert!(turing_machine.is_deterministic());
+    assert_eq!(turing_machine.check_input(&mut "".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "ab".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "aaabbb".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "aab".to_string()), false);
+    assert_eq!(turing_machine.check_input(&mut "abab".to_string()), false);
+    assert_eq!(turing_machine.check_input(&mut "ba".to_string()), false);
+}
+
+#[test]
+fn run_trace_test() {
+    let turing_machine = anbn_machine();
+    let run = turing_machine.run("ab", 100);
+    assert_eq!(run.status, HaltingStatus::Accept);
+    let descriptions: Vec<String> = run.trace.iter()
+        .map(|configuration| turing_machine.format_configuration(configuration))
+        .collect();
+    assert_eq!(descriptions, vec!["q0 ab", "X q1 b", "q2 XY", "X q0 Y", "XY q3 _", "XY q4 _"]);
+    let last = run.trace.last().unwrap();
+    assert_eq!(last.head, 2);
+    assert_eq!(last.read(), "_");
+    assert_eq!(turing_machine.tape_contents(last), "XY");
+    assert_eq!(turing_machine.run("aab", 100).status, HaltingStatus::Reject);
+    // The run stops after the step bound.
+    let run = turing_machine.run("aaaabbbb", 10);
+    assert_eq!(run.status, HaltingStatus::Timeout);
+    assert_eq!(run.trace.len(), 11);
+    // A machine that moves to the left forever never halts.
+    let mut turing_machine = TuringMachine::new("_".to_string());
+    turing_machine.add_n_states(2);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(1);
+    turing_machine.add_transition(0, 0, "_/1,L".to_string());
+    let run = turing_machine.run("", 20);
+    assert_eq!(run.status, HaltingStatus::Timeout);
+    assert_eq!(turing_machine.tape_contents(run.trace.last().unwrap()), "1".repeat(20));
+    turing_machine.add_transition(0, 1, "_/_,S".to_string());
+    assert!(!turing_machine.is_deterministic());
+}
+
+#[test]
+fn run_nondeterministic_test() {
+    // Machine that guesses where the string ab starts.
+    let mut turing_machine = TuringMachine::new("_".to_string());
+    turing_machine.add_n_states(3);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(2);
+    turing_machine.add_transition(0, 0, "a/a,R".to_string());
+    turing_machine.add_transition(0, 0, "b/b,R".to_string());
+    turing_machine.add_transition(0, 1, "a/a,R".to_string());
+    turing_machine.add_transition(1, 2, "b/b,R".to_string());
+    assert!(!turing_machine.is_deterministic());
+    let run = turing_machine.run_nondeterministic("bbab", 100);
+    assert_eq!(run.status, HaltingStatus::Accept);
+    assert_eq!(run.trace.len(), 5);
+    assert_eq!(turing_machine.check_input(&mut "bbab".to_string()), true);
+    assert_eq!(turing_machine.check_input(&mut "ba".to_string()), false);
+    assert_eq!(turing_machine.run_nondeterministic("ba", 100).status, HaltingStatus::Reject);
+    // A machine that can always take another step runs out of the budget.
+    turing_machine.add_transition(0, 0, "_/_,R".to_string());
+    assert_eq!(turing_machine.run_nondeterministic("ba", 100).status, HaltingStatus::Timeout);
+    // Two runs that meet in the same configuration and halt without accepting.
+    let mut turing_machine = TuringMachine::new("_".to_string());
+    turing_machine.add_n_states(5);
+    turing_machine.make_initial(0);
+    turing_machine.make_final(4);
+    turing_machine.add_transition(0, 1, "a/a,R".to_string());
+    turing_machine.add_transition(0, 2, "a/a,R".to_string());
+    turing_machine.add_transition(1, 3, "b/b,S".to_string());
+    turing_machine.add_transition(2, 3, "b/b,S".to_string());
+    assert_eq!(turing_machine.run_nondeterministic("ab", 100).status, HaltingStatus::Reject);
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::machine_file::{MachineFile, Position};
+use crate::state::StateID;
+
+/* Function that writes a machine as TikZ code with the styles of the
+ * automata library, used by the LaTeX export of the editor. The states are
+ * drawn where they were saved, scaled to a picture 5cm wide, and if some
+ * state has no position all of them are placed on a circle. The labels of
+ * the transitions between two states are stacked on one edge, with the
+ * characters that are special in LaTeX escaped. */
+pub fn to_tikz(file: &MachineFile) -> String {
+    let positions = state_positions(file);
+    let (min_x, min_y, max_x, max_y) = positions.values().fold(
+        (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        |(min_x, min_y, max_x, max_y), position| {
+            (min_x.min(position.x), min_y.min(position.y), max_x.max(position.x), max_y.max(position.y))
+        },
+    );
+    let width = (max_x - min_x).max(1.0);
+    let height = (max_y - min_y).max(1.0);
+    let scale = 5.0 / width.max(height);
+
+    let node_names: HashMap<StateID, String> = file.states.iter().enumerate()
+        .map(|(index, state)| (state.id, format!("q{}", index)))
+        .collect();
+
+    let mut tikz = String::new();
+    tikz.push_str("% Paste this into your LaTeX document\n");
+    tikz.push_str("% Requires: \\usepackage{tikz} and \\usetikzlibrary{arrows.meta, automata, positioning}\n");
+    tikz.push_str("\\begin{center}\n");
+    tikz.push_str("\\begin{tikzpicture}[shorten >=1pt, node distance=2cm, on grid, initial text=, auto]\n");
+    for state in file.states.iter() {
+        let position = positions[&state.id];
+        let mut style = String::from("state");
+        if state.initial {
+            style.push_str(", initial");
+        }
+        if state.is_final {
+            style.push_str(", accepting");
+        }
+        tikz.push_str(&format!(
+            "  \\node[{}] ({}) at ({:.2}, {:.2}) {{${}$}};\n",
+            style,
+            node_names[&state.id],
+            (position.x - min_x) * scale,
+            (min_y - position.y) * scale,
+            latex_state_name(&state.name),
+        ));
+    }
+
+    let mut labels_by_edge: BTreeMap<(StateID, StateID), Vec<String>> = BTreeMap::new();
+    for transition in file.transitions.iter() {
+        let label = if transition.label.trim().is_empty() {
+            "$\\varepsilon$".to_string()
+        } else {
+            latex_label(transition.label.trim())
+        };
+        labels_by_edge.entry((transition.from, transition.to)).or_default().push(label);
+    }
+    for ((from_id, to_id), labels) in labels_by_edge.iter() {
+        let edge = if from_id == to_id {
+            "edge[loop above]"
+        } else if labels_by_edge.contains_key(&(*to_id, *from_id)) {
+            "edge[bend left]"
+        } else {
+            "edge"
+        };
+        let node = if labels.len() == 1 { "node" } else { "node[align=center]" };
+        tikz.push_str(&format!(
+            "  \\path[->] ({}) {} {}{{{}}} ({});\n",
+            node_names[from_id],
+            edge,
+            node,
+            labels.join("\\\\"),
+            node_names[to_id],
+        ));
+    }
+    tikz.push_str("\\end{tikzpicture}\n");
+    tikz.push_str("\\end{center}\n");
+    tikz
+}
+
+// Auxiliar function that returns the saved positions of the states, or positions on a circle
+// if some state doesn't have one.
+fn state_positions(file: &MachineFile) -> HashMap<StateID, Position> {
+    let positions = file.positions();
+    if positions.len() == file.states.len() {
+        return positions;
+    }
+    let radius = 100.0;
+    let count = file.states.len().max(1) as f32;
+    file.states.iter().enumerate()
+        .map(|(index, state)| {
+            let angle = std::f32::consts::PI - 2.0 * std::f32::consts::PI * index as f32 / count;
+            (state.id, Position { x: radius * angle.cos(), y: -radius * angle.sin() })
+        })
+        .collect()
+}
+
+// Writes a transition label for text mode, escaping the characters that are special in LaTeX,
+// e.g. _/_,R as \_/\_,R, and writing ε in math mode.
+fn latex_label(label: &str) -> String {
+    let mut latex = String::new();
+    for c in label.chars() {
+        match c {
+            'ε' => latex.push_str("$\\varepsilon$"),
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                latex.push('\\');
+                latex.push(c);
+            }
+            '\\' => latex.push_str("\\textbackslash{}"),
+            '^' => latex.push_str("\\textasciicircum{}"),
+            '~' => latex.push_str("\\textasciitilde{}"),
+            '<' => latex.push_str("\\textless{}"),
+            '>' => latex.push_str("\\textgreater{}"),
+            _ => latex.push(c),
+        }
+    }
+    latex
+}
+
+// Writes the name of a state for math mode with the digits as a subscript, e.g. q12 as q_{12}.
+fn latex_state_name(name: &str) -> String {
+    match name.char_indices().find(|(_, c)| c.is_ascii_digit()) {
+        Some((index, _)) => {
+            let (prefix, digits) = name.split_at(index);
+            if digits.chars().count() == 1 {
+                format!("{}_{}", prefix, digits)
+            } else {
+                format!("{}_{{{}}}", prefix, digits)
+            }
+        }
+        None => name.to_string(),
+    }
+}
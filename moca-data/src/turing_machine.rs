@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use crate::state::{Input, State, StateID};
+use crate::state_machine::StateMachine;
+
+/* Maximum number of steps of a run when checking an input. */
+pub const MAX_STEPS: usize = 10_000;
+
+/* Structure that represents a Turing machine with a single tape that is
+ * infinite in both directions. The transitions are labeled as
+ * read/write,move, e.g. a/b,R reads a, writes b and moves the head to the
+ * right, the moves are L, R and S (stay). The cells that were never written
+ * have the blank symbol. The machine halts and accepts as soon as it enters
+ * a final state, and halts and rejects when there is no transition for the
+ * symbol under the head. */
+#[derive(Debug, Clone)]
+pub struct TuringMachine {
+    states_by_id: HashMap<StateID, State>,
+    initial_state_id: Option<StateID>,
+    final_states: HashSet<StateID>,
+    blank_symbol: String,
+    deterministic: bool,
+}
+
+impl TuringMachine {
+    pub fn new(blank_symbol: String) -> Self {
+        TuringMachine {
+            states_by_id: HashMap::new(),
+            initial_state_id: None,
+            final_states: HashSet::new(),
+            blank_symbol,
+            deterministic: true,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.states_by_id.clear();
+        self.initial_state_id = None;
+        self.final_states.clear();
+        self.deterministic = true;
+    }
+
+    // Getter for the symbol of the cells that were never written.
+    pub fn get_blank_symbol(&self) -> &str {
+        &self.blank_symbol
+    }
+
+    pub fn set_blank_symbol(&mut self, blank_symbol: String) {
+        self.blank_symbol = blank_symbol;
+    }
+
+    /* Function to check if a given input string is accepted by the machine,
+     * i.e. it reaches a final state in at most MAX_STEPS steps. If the
+     * machine is not deterministic every possible run is tried. */
+    pub fn check_input(&self, input: &mut Input) -> bool {
+        let run = if self.deterministic {
+            self.run(input, MAX_STEPS)
+        } else {
+            self.run_nondeterministic(input, MAX_STEPS)
+        };
+        run.status == HaltingStatus::Accept
+    }
+
+    /* Returns the configuration before reading the input: the machine is in
+     * the initial state with the head on the first symbol of the input, every
+     * character is a symbol. Returns None if there is no initial state. */
+    pub fn initial_configuration(&self, input: &str) -> Option<TuringConfiguration> {
+        let mut tape: Vec<String> = input.chars().map(|c| c.to_string()).collect();
+        if tape.is_empty() {
+            tape.push(self.blank_symbol.clone());
+        }
+        Some(TuringConfiguration {
+            state_id: self.initial_state_id?,
+            tape,
+            head: 0,
+        })
+    }
+
+    /* Returns the configuration after one move, or None if the machine halts.
+     * If the machine is not deterministic any of the possible moves is taken. */
+    pub fn step(&self, configuration: &TuringConfiguration) -> Option<TuringConfiguration> {
+        self.parsed_transitions().iter()
+            .find_map(|transition| transition.apply(configuration, &self.blank_symbol))
+    }
+
+    /* Function that runs the machine on the input until it halts or it makes
+     * max_steps moves, returning the halting status and every configuration
+     * of the run. */
+    pub fn run(&self, input: &str, max_steps: usize) -> TuringRun {
+        let mut configuration = match self.initial_configuration(input) {
+            Some(configuration) => configuration,
+            None => return TuringRun { status: HaltingStatus::Reject, trace: Vec::new() },
+        };
+        let transitions = self.parsed_transitions();
+        let mut trace = vec![configuration.clone()];
+        loop {
+            if self.final_states.contains(&configuration.state_id) {
+                return TuringRun { status: HaltingStatus::Accept, trace };
+            }
+            if trace.len() > max_steps {
+                return TuringRun { status: HaltingStatus::Timeout, trace };
+            }
+            match transitions.iter().find_map(|transition| transition.apply(&configuration, &self.blank_symbol)) {
+                Some(next) => {
+                    trace.push(next.clone());
+                    configuration = next;
+                }
+                None => return TuringRun { status: HaltingStatus::Reject, trace },
+            }
+        }
+    }
+
+    /* Function that runs a nondeterministic machine on the input exploring
+     * its configurations breadth first, at most max_steps of them. The trace
+     * is the run that reaches a final state if the input is accepted,
+     * otherwise the run to the last configuration explored. */
+    pub fn run_nondeterministic(&self, input: &str, max_steps: usize) -> TuringRun {
+        let initial_configuration = match self.initial_configuration(input) {
+            Some(configuration) => configuration,
+            None => return TuringRun { status: HaltingStatus::Reject, trace: Vec::new() },
+        };
+        let transitions = self.parsed_transitions();
+        let (status, trace) = breadth_first_run(initial_configuration, max_steps,
+            |configuration| self.final_states.contains(&configuration.state_id),
+            |configuration| transitions.iter()
+                .filter_map(|transition| transition.apply(configuration, &self.blank_symbol))
+                .collect());
+        TuringRun { status, trace }
+    }
+
+    /* Function that writes a configuration as an instantaneous description,
+     * the tape with the name of the state before the symbol under the head,
+     * e.g. ab q1 cd. The blanks at both ends of the tape are omitted, except
+     * the one under the head. */
+    pub fn format_configuration(&self, configuration: &TuringConfiguration) -> String {
+        let state_name = self.states_by_id.get(&configuration.state_id)
+            .map_or(configuration.state_id.to_string(), |state| state.name.clone());
+        let (start, end) = self.written_range(configuration);
+        let start = start.min(configuration.head);
+        let end = end.max(configuration.head + 1);
+        let left = configuration.tape[start..configuration.head].concat();
+        let right = configuration.tape[configuration.head..end].concat();
+        format!("{} {} {}", left, state_name, right).trim().to_string()
+    }
+
+    // Returns the content of the tape without the blanks at both ends.
+    pub fn tape_contents(&self, configuration: &TuringConfiguration) -> String {
+        let (start, end) = self.written_range(configuration);
+        configuration.tape[start..end.max(start)].concat()
+    }
+
+    // Auxiliar function that returns the range of the tape between the first
+    // and the last symbols that are not blank.
+    fn written_range(&self, configuration: &TuringConfiguration) -> (usize, usize) {
+        let is_written = |symbol: &String| *symbol != self.blank_symbol;
+        let start = configuration.tape.iter().position(is_written).unwrap_or(configuration.tape.len());
+        let end = configuration.tape.iter().rposition(is_written).map_or(0, |index| index + 1);
+        (start, end)
+    }
+
+    // Auxiliar function that reads every transition label of the machine.
+    // The labels that are not valid are ignored.
+    fn parsed_transitions(&self) -> Vec<TuringTransition> {
+        let mut transitions = Vec::new();
+        for (from_id, state) in self.states_by_id.iter() {
+            for (to_id, labels) in state.iter_by_transition() {
+                for label in labels {
+                    if let Some((read, write, direction)) = parse_transition_label(label) {
+                        transitions.push(TuringTransition {
+                            from_id: *from_id,
+                            to_id: *to_id,
+                            read,
+                            write,
+                            direction,
+                        });
+                    }
+                }
+            }
+        }
+        transitions
+    }
+}
+
+/* The moves of the head of a Turing machine. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TapeMove {
+    Left,
+    Right,
+    Stay,
+}
+
+impl TapeMove {
+    // Reads a move written as L, R or S.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim() {
+            "L" => Some(TapeMove::Left),
+            "R" => Some(TapeMove::Right),
+            "S" => Some(TapeMove::Stay),
+            _ => None,
+        }
+    }
+}
+
+impl TapeMove {
+    /* Function that moves a head over the tape and returns its new position.
+     * The tape grows with a blank when the head moves past one of its ends. */
+    pub fn apply(&self, tape: &mut Vec<String>, head: usize, blank_symbol: &str) -> usize {
+        match self {
+            TapeMove::Left if head == 0 => {
+                tape.insert(0, blank_symbol.to_string());
+                0
+            }
+            TapeMove::Left => head - 1,
+            TapeMove::Right => {
+                if head + 1 == tape.len() {
+                    tape.push(blank_symbol.to_string());
+                }
+                head + 1
+            }
+            TapeMove::Stay => head,
+        }
+    }
+}
+
+impl fmt::Display for TapeMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TapeMove::Left => write!(f, "L"),
+            TapeMove::Right => write!(f, "R"),
+            TapeMove::Stay => write!(f, "S"),
+        }
+    }
+}
+
+/* A configuration of a Turing machine, the state, the written part of the
+ * tape and the position of the head in it. */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TuringConfiguration {
+    pub state_id: StateID,
+    pub tape: Vec<String>,
+    pub head: usize,
+}
+
+impl TuringConfiguration {
+    // Returns the symbol under the head.
+    pub fn read(&self) -> &str {
+        &self.tape[self.head]
+    }
+}
+
+/* The ways a run of a Turing machine can end: it halts in a final state,
+ * it halts in a state that is not final, or it reaches the step bound. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HaltingStatus {
+    Accept,
+    Reject,
+    Timeout,
+}
+
+impl fmt::Display for HaltingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HaltingStatus::Accept => write!(f, "accepted"),
+            HaltingStatus::Reject => write!(f, "rejected"),
+            HaltingStatus::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+/* The result of running a Turing machine, the configurations are in the
+ * order of the run starting with the initial one. */
+#[derive(Debug, Clone)]
+pub struct TuringRun {
+    pub status: HaltingStatus,
+    pub trace: Vec<TuringConfiguration>,
+}
+
+/* Transition label already split in its three parts. */
+struct TuringTransition {
+    from_id: StateID,
+    to_id: StateID,
+    read: String,
+    write: String,
+    direction: TapeMove,
+}
+
+impl TuringTransition {
+    // Returns the configuration reached by taking the transition, if it can be taken.
+    fn apply(&self, configuration: &TuringConfiguration, blank_symbol: &str) -> Option<TuringConfiguration> {
+        if configuration.state_id != self.from_id || configuration.read() != self.read {
+            return None;
+        }
+        let mut tape = configuration.tape.clone();
+        tape[configuration.head] = self.write.clone();
+        let head = self.direction.apply(&mut tape, configuration.head, blank_symbol);
+        Some(TuringConfiguration {
+            state_id: self.to_id,
+            tape,
+            head,
+        })
+    }
+}
+
+/* Function that explores breadth first the configurations reachable from
+ * the initial one, used to simulate the nondeterministic machines. Returns
+ * Accept and the run to the first accepting configuration found, Timeout if
+ * max_steps configurations are explored first, or Reject if every
+ * configuration reachable was explored without accepting. A repeated
+ * configuration is not explored again, since the runs through it were
+ * already explored from its first visit. Without acceptance the run
+ * returned ends in the last configuration explored. */
+pub fn breadth_first_run<C: Clone + Eq + Hash>(initial_configuration: C, max_steps: usize,
+                                               accepts: impl Fn(&C) -> bool,
+                                               successors: impl Fn(&C) -> Vec<C>) -> (HaltingStatus, Vec<C>) {
+    // Every configuration stores the index of its predecessor to rebuild the run.
+    let mut configurations: Vec<(C, Option<usize>)> = vec![(initial_configuration.clone(), None)];
+    let mut visited: HashSet<C> = HashSet::new();
+    visited.insert(initial_configuration);
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    queue.push_back(0);
+    let mut last = 0;
+    let run_to = |configurations: &[(C, Option<usize>)], index: usize| {
+        let mut run = Vec::new();
+        let mut current = Some(index);
+        while let Some(current_index) = current {
+            run.push(configurations[current_index].0.clone());
+            current = configurations[current_index].1;
+        }
+        run.reverse();
+        run
+    };
+    let mut explored = 0;
+    while let Some(index) = queue.pop_front() {
+        last = index;
+        if accepts(&configurations[index].0) {
+            return (HaltingStatus::Accept, run_to(&configurations, index));
+        }
+        if explored == max_steps {
+            return (HaltingStatus::Timeout, run_to(&configurations, index));
+        }
+        explored += 1;
+        for next in successors(&configurations[index].0) {
+            if visited.contains(&next) {
+                continue;
+            }
+            visited.insert(next.clone());
+            configurations.push((next, Some(index)));
+            queue.push_back(configurations.len() - 1);
+        }
+    }
+    (HaltingStatus::Reject, run_to(&configurations, last))
+}
+
+/* Function that splits a transition label of the form read/write,move in
+ * its three parts, e.g. a/b,R. Returns None if the label does not have
+ * that form. */
+pub fn parse_transition_label(label: &str) -> Option<(String, String, TapeMove)> {
+    let (symbols, direction) = label.trim().rsplit_once(',')?;
+    let (read, write) = symbols.split_once('/')?;
+    let (read, write) = (read.trim(), write.trim());
+    if read.is_empty() || write.is_empty() || write.contains('/') {
+        return None;
+    }
+    Some((read.to_string(), write.to_string(), TapeMove::parse(direction)?))
+}
+
+impl StateMachine for TuringMachine {
+    fn get_states_by_id_mut_ref(&mut self) -> &mut HashMap<StateID, State> {
+        &mut self.states_by_id
+    }
+
+    fn get_states_by_id_ref(&self) -> &HashMap<StateID, State> {
+        &self.states_by_id
+    }
+
+    fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    fn get_final_states(&self) -> &HashSet<StateID> {
+        &self.final_states
+    }
+
+    fn get_initial_state_id(&self) -> &Option<StateID> {
+        &self.initial_state_id
+    }
+
+    /* The machine stops being deterministic when a state has two
+     * transitions that read the same symbol. */
+    fn add_transition(&mut self, state_id1: StateID, state_id2: StateID, input: Input) {
+        // Labels that are not of the form read/write,move are not added.
+        let read = match parse_transition_label(&input) {
+            Some((read, _, _)) => read,
+            None => return,
+        };
+        let state = match self.states_by_id.get_mut(&state_id1) {
+            Some(state) => state,
+            None => return,
+        };
+        // The same transition added again is not another move.
+        let repeated = state.iter_by_transition()
+            .flat_map(|(to_id, labels)| labels.iter().map(move |label| (*to_id, label)))
+            .filter(|(to_id, label)| *to_id != state_id2 || **label != input)
+            .filter_map(|(_, label)| parse_transition_label(label))
+            .any(|(other_read, _, _)| other_read == read);
+        if repeated {
+            self.deterministic = false;
+        }
+        state.add_transition(state_id2, input);
+    }
+
+    fn make_initial(&mut self, state_id: StateID) {
+        if !self.states_by_id.contains_key(&state_id) {
+            return;
+        }
+        if let Some(old_initial_state) = self.initial_state_id.and_then(|old_id| self.states_by_id.get_mut(&old_id)) {
+            old_initial_state.initial_flag = false;
+        }
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.initial_flag = true;
+            self.initial_state_id = Some(state_id);
+        }
+    }
+
+    /* Function to make a state final. */
+    fn make_final(&mut self, state_id: StateID) {
+        if let Some(state) = self.states_by_id.get_mut(&state_id) {
+            state.final_flag = true;
+            self.final_states.insert(state_id);
+        }
+    }
+}
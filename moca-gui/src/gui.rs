@@ -1,14 +1,36 @@
 use iced::keyboard;
-use iced::widget::{button, container, horizontal_space, hover, row, text, column, stack};
+use iced::widget::{button, container, horizontal_space, hover, row, text, column, stack, text_editor};
 use iced::{Element, Alignment, Event, Subscription, Task, Length};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use indexmap::IndexSet;
 
 use crate::state_machine;
 use crate::tikz_export;
 
+use moca_data::ambiguity::{AmbiguitySearch, AutomatonAmbiguity, GrammarAmbiguity};
+use moca_data::automaton_text;
+use moca_data::cyk::CykTable;
+use moca_data::earley::{self, EarleyChart};
+use moca_data::file_format::FileFormat;
 use moca_data::finite_automata::FiniteAutomata;
+use moca_data::generator::{self, SentenceGenerator};
+use moca_data::grammar::{format_symbols, Grammar, MAX_SENTENTIAL_FORMS};
+use moca_data::ll1::LL1Table;
+use moca_data::machine_file::{FileMachine, MachineFile, Position, ProjectFile, ProjectTab};
+use moca_data::lr::{LR0Automaton, LRTable};
+use moca_data::pushdown_automata::{self, PushdownAutomata, PushdownConfiguration};
 use moca_data::state_machine::StateMachine;
+use moca_data::test_suite::{TestSuite, TestSuiteReport};
+use moca_data::tikz;
+use moca_data::turing_machine::{self, HaltingStatus, TuringMachine, TuringRun};
+
+/* Number of random sentences shown by the sentence generator. */
+const RANDOM_SENTENCES: usize = 10;
+
+/* Time between two autosaves of the session. */
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -20,6 +42,8 @@ pub enum Message {
     FinishEditing,
     CancelEditing,
     ToggleOperationsMenu,
+    ToggleMachineMenu,
+    NewMachineTab(MachineKind),
     CheckInput,
     DfaToNfa,
     Minimize,
@@ -40,6 +64,245 @@ pub enum Message {
     DeleteEditTransitionLabel(usize),
     AddEditTransitionLabel,
     CancelEditTransitionLabels,
+    SimulationStepBack,
+    SimulationStepForward,
+    CloseSimulation,
+    CloseItemSets,
+    TuringStep,
+    TuringStepBack,
+    TuringRunToEnd,
+    TuringReset,
+    CloseTuringSimulation,
+    OpenGrammarDialog(GrammarOperation),
+    GrammarTextEdited(text_editor::Action),
+    GrammarWordChanged(String),
+    SubmitGrammar,
+    CancelGrammar,
+    OpenTestSuite,
+    TestSuiteEdited(text_editor::Action),
+    RunTestSuite,
+    CloseTestSuite,
+    OpenAutomatonText,
+    AutomatonTextEdited(text_editor::Action),
+    SubmitAutomatonText,
+    CancelAutomatonText,
+    MachineToText,
+    MachineToGrammar,
+    OpenAmbiguityDialog,
+    AmbiguityLengthChanged(String),
+    FindAmbiguity,
+    CancelAmbiguityDialog,
+    CopyTextResult,
+    CloseTextResult,
+    ExportCykTable,
+    CloseCykTable,
+    OpenFileDialog(FileOperation),
+    FilePathChanged(String),
+    SubmitFileDialog,
+    CancelFileDialog,
+    Autosave,
+}
+
+/* The operations of the menu bar that read or write a machine file, or a
+ * project file with every tab, and the ones of the test suite panel. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOperation {
+    Open,
+    Save,
+    OpenProject,
+    SaveProject,
+    OpenTestSuite,
+    SaveTestSuite,
+}
+
+impl FileOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            FileOperation::Open => "Open",
+            FileOperation::Save => "Save",
+            FileOperation::OpenProject => "Open Project",
+            FileOperation::SaveProject => "Save Project",
+            FileOperation::OpenTestSuite => "Open",
+            FileOperation::SaveTestSuite => "Save",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            FileOperation::Open => "Open Machine File:",
+            FileOperation::Save => "Save Machine File:",
+            FileOperation::OpenProject => "Open Project File:",
+            FileOperation::SaveProject => "Save Project File:",
+            FileOperation::OpenTestSuite => "Open Test Suite:",
+            FileOperation::SaveTestSuite => "Save Test Suite:",
+        }
+    }
+
+    fn placeholder(&self) -> &'static str {
+        match self {
+            FileOperation::Open | FileOperation::Save => "Path, e.g. automaton.json, automaton.jff, automaton.dot or automaton.txt",
+            FileOperation::OpenProject | FileOperation::SaveProject => "Path, e.g. project.json",
+            FileOperation::OpenTestSuite | FileOperation::SaveTestSuite => "Path, e.g. tests.txt",
+        }
+    }
+}
+
+/* The operations that start from a grammar written in the grammar dialog. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrammarOperation {
+    ToFinite,
+    ToPushdown,
+    ToChomskyNormalForm,
+    ToGreibachNormalForm,
+    Analysis,
+    Ambiguity,
+    Generate,
+    Cyk,
+    Earley,
+    LL1,
+    LR,
+}
+
+impl GrammarOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            GrammarOperation::ToFinite => "Grammar to FA",
+            GrammarOperation::ToPushdown => "Grammar to PDA",
+            GrammarOperation::ToChomskyNormalForm => "Grammar to CNF",
+            GrammarOperation::ToGreibachNormalForm => "Grammar to GNF",
+            GrammarOperation::Analysis => "Grammar Analysis",
+            GrammarOperation::Ambiguity => "Grammar Ambiguity",
+            GrammarOperation::Generate => "Generate Sentences",
+            GrammarOperation::Cyk => "CYK",
+            GrammarOperation::Earley => "Earley",
+            GrammarOperation::LL1 => "LL(1)",
+            GrammarOperation::LR => "LR(0) Automaton",
+        }
+    }
+
+    // Returns true if the operation also needs a word besides the grammar.
+    fn needs_word(&self) -> bool {
+        matches!(self, GrammarOperation::Cyk | GrammarOperation::Earley | GrammarOperation::LL1
+            | GrammarOperation::Ambiguity | GrammarOperation::Generate)
+    }
+
+    // Placeholder of the word input, the ambiguity search and the generator read the maximum length instead.
+    fn word_placeholder(&self) -> &'static str {
+        match self {
+            GrammarOperation::Ambiguity | GrammarOperation::Generate => "Maximum length, e.g. 6",
+            _ => "Word, e.g. aabb",
+        }
+    }
+}
+
+/* The kinds of abstract machines a tab can edit. */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MachineKind {
+    #[default]
+    Finite,
+    Pushdown,
+    Turing,
+}
+
+impl MachineKind {
+    fn name(&self) -> &'static str {
+        match self {
+            MachineKind::Finite => "Finite Automaton",
+            MachineKind::Pushdown => "Pushdown Automaton",
+            MachineKind::Turing => "Turing Machine",
+        }
+    }
+
+    fn tab_name(&self) -> &'static str {
+        match self {
+            MachineKind::Finite => "Machine",
+            MachineKind::Pushdown => "PDA",
+            MachineKind::Turing => "TM",
+        }
+    }
+}
+
+/* The machine of a tab, it decides how the transition labels are read
+ * and which algorithm answers "Check Input". */
+#[derive(Debug, Clone)]
+enum Machine {
+    Finite(FiniteAutomata),
+    Pushdown(PushdownAutomata),
+    Turing(TuringMachine),
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Machine::Finite(FiniteAutomata::default())
+    }
+}
+
+impl Machine {
+    fn new(kind: MachineKind) -> Self {
+        match kind {
+            MachineKind::Finite => Machine::Finite(FiniteAutomata::new()),
+            MachineKind::Pushdown => Machine::Pushdown(PushdownAutomata::new("Z".to_string())),
+            MachineKind::Turing => Machine::Turing(TuringMachine::new("_".to_string())),
+        }
+    }
+
+    fn as_state_machine(&self) -> &dyn StateMachine {
+        match self {
+            Machine::Finite(machine) => machine,
+            Machine::Pushdown(machine) => machine,
+            Machine::Turing(machine) => machine,
+        }
+    }
+
+    fn as_state_machine_mut(&mut self) -> &mut dyn StateMachine {
+        match self {
+            Machine::Finite(machine) => machine,
+            Machine::Pushdown(machine) => machine,
+            Machine::Turing(machine) => machine,
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Machine::Finite(machine) => machine.clear(),
+            Machine::Pushdown(machine) => machine.clear(),
+            Machine::Turing(machine) => machine.clear(),
+        }
+    }
+
+    // Copy of the machine to be written in a machine file.
+    fn to_file_machine(&self) -> FileMachine {
+        match self {
+            Machine::Finite(machine) => FileMachine::Finite(machine.clone()),
+            Machine::Pushdown(machine) => FileMachine::Pushdown(machine.clone()),
+            Machine::Turing(machine) => FileMachine::Turing(machine.clone()),
+        }
+    }
+
+    // Machine read from a machine file, with the kind of tab that edits it.
+    fn from_file_machine(machine: FileMachine) -> Result<(MachineKind, Machine), String> {
+        match machine {
+            FileMachine::Finite(machine) => Ok((MachineKind::Finite, Machine::Finite(machine))),
+            FileMachine::Pushdown(machine) => Ok((MachineKind::Pushdown, Machine::Pushdown(machine))),
+            FileMachine::Turing(machine) => Ok((MachineKind::Turing, Machine::Turing(machine))),
+            FileMachine::Mealy(_) | FileMachine::Moore(_) => Err("Cannot open the file: Mealy and Moore machines can't be edited yet.".to_string()),
+        }
+    }
+
+    /* Checks that a transition label can be read by the machine. */
+    fn validate_transition_label(&self, label: &str) -> Result<(), String> {
+        match self {
+            Machine::Finite(_) => Ok(()),
+            Machine::Pushdown(_) => match pushdown_automata::parse_transition_label(label) {
+                Some(_) => Ok(()),
+                None => Err(format!("Invalid transition label \"{}\". Pushdown transitions are written as input;pop/push, e.g. a;Z/AZ.", label)),
+            },
+            Machine::Turing(_) => match turing_machine::parse_transition_label(label) {
+                Some(_) => Ok(()),
+                None => Err(format!("Invalid transition label \"{}\". Turing machine transitions are written as read/write,move with move L, R or S, e.g. a/X,R.", label)),
+            },
+        }
+    }
 }
 
 #[derive(Default)]
@@ -48,7 +311,7 @@ struct Tab {
     transitions: HashMap<(usize, usize), IndexSet<String>>,
     states: Vec<state_machine::StateNode>,
     state_id_to_index: HashMap<usize, usize>,
-    machine: FiniteAutomata,
+    machine: Machine,
     initial_state: Option<usize>, 
     final_states: std::collections::HashSet<usize>, 
     editing_state: Option<usize>,
@@ -58,7 +321,7 @@ struct Tab {
     check_input_dialog_open: bool,
     check_input_text: String,
     check_result_popup_open: bool,
-    check_input_result: Option<bool>,
+    check_input_result: Option<HaltingStatus>,
     deletion_mode: bool,
     name: String, 
     pending_transition: Option<(usize, usize, iced::Point, iced::Point)>,
@@ -68,18 +331,30 @@ struct Tab {
     editing_transition_labels: Vec<String>,
     editing_transition_dialog_open: bool,
     editing_transition_label_inputs: Vec<String>,
+    simulation_trace: Option<Vec<PushdownConfiguration>>,
+    simulation_step: usize,
+    // The items of every state of an LR(0) automaton, shown next to the canvas.
+    item_sets: Option<Vec<(String, Vec<String>)>>,
+    turing_run: Option<TuringRun>,
+    turing_step: usize,
 }
 
 impl Tab {
     fn new() -> Self {
         let mut tab = Self::default();
         tab.state_machine.reset_id_counter();
-        tab.machine = FiniteAutomata::default(); 
+        tab.machine = Machine::default(); 
         tab.name = "Machine".to_string(); 
         tab.transitions = HashMap::new();
         tab
     }
 
+    fn new_with_kind(kind: MachineKind) -> Self {
+        let mut tab = Self::new_with_name(kind.tab_name().to_string());
+        tab.machine = Machine::new(kind);
+        tab
+    }
+
     fn new_with_name(name: String) -> Self {
         let mut tab = Self::new();
         tab.name = name;
@@ -93,19 +368,62 @@ pub struct App {
     tabs: Vec<Box<Tab>>,
     active_tab: usize,
     error_message: Option<String>, 
+    machine_menu_open: bool,
+    grammar_dialog: Option<GrammarOperation>,
+    grammar_text: text_editor::Content,
+    grammar_word: String,
+    automaton_text_dialog_open: bool,
+    automaton_text: text_editor::Content,
+    ambiguity_dialog_open: bool,
+    // Maximum length of the words tried when searching two accepting runs.
+    ambiguity_length: String,
+    test_suite_open: bool,
+    test_suite_text: text_editor::Content,
+    // Results of the last run of the test suite, cleared when the suite is edited.
+    test_suite_report: Option<TestSuiteReport>,
+    cyk_table: Option<CykTable>,
     latex_export_dialog_open: bool,
     latex_export_code: Option<String>,
+    // Title and text of the results that are shown as text, e.g. a grammar.
+    text_result: Option<(String, String)>,
+    file_dialog: Option<FileOperation>,
+    file_path: String,
+    // Set when an autosave fails, so the error is shown only once.
+    autosave_failed: bool,
+    // Set when the last session can't be restored nor moved away, so the autosave doesn't replace it.
+    autosave_disabled: bool,
 }
 
 impl App {
+    /* The last session is restored when there is one, otherwise the editor
+     * starts with an empty tab. A session that can't be restored is moved to
+     * session.json.bak, so the autosave doesn't replace it. */
     pub fn new() -> (Self, Task<Message>) {
         let mut app = Self::default();
-        app.tabs.push(Box::new(Tab::new()));
+        match session_path() {
+            Some(path) if path.exists() => {
+                if let Err(error) = app.load_project_file(&path) {
+                    let backup_path = path.with_extension("json.bak");
+                    app.error_message = Some(match std::fs::rename(&path, &backup_path) {
+                        Ok(()) => format!("Cannot restore the last session: {}. It was kept in {}", error, backup_path.display()),
+                        Err(rename_error) => {
+                            app.autosave_disabled = true;
+                            format!("Cannot restore the last session: {}. It could not be moved to {} ({}), so the session won't be autosaved",
+                                    error, backup_path.display(), rename_error)
+                        }
+                    });
+                }
+            }
+            _ => (),
+        }
+        if app.tabs.is_empty() {
+            app.tabs.push(Box::new(Tab::new()));
+        }
         (app, Task::none())
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
-        iced::event::listen_with(|event, _status, _| match event {
+        let keyboard = iced::event::listen_with(|event, _status, _| match event {
             Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
                 Some(Message::KeyPressed(key))
             }
@@ -113,7 +431,8 @@ impl App {
                 Some(Message::KeyReleased(key))
             }
             _ => None,
-        })
+        });
+        Subscription::batch([keyboard, Subscription::run(autosave_ticks)])
     }
 
     fn get_active_tab(&self) -> &Tab {
@@ -284,7 +603,11 @@ impl App {
                 self.get_active_tab_mut().state_id_to_index.clear();
                 self.get_active_tab_mut().initial_state = None;
                 self.get_active_tab_mut().final_states.clear();
-                self.get_active_tab_mut().machine = FiniteAutomata::default();
+                self.get_active_tab_mut().machine.clear();
+                self.get_active_tab_mut().simulation_trace = None;
+                self.get_active_tab_mut().item_sets = None;
+                self.get_active_tab_mut().turing_run = None;
+                self.get_active_tab_mut().state_machine.set_highlighted_state(None);
                 self.get_active_tab_mut().check_input_dialog_open = false;
                 self.get_active_tab_mut().check_input_text.clear();
                 self.get_active_tab_mut().check_result_popup_open = false;
@@ -300,6 +623,14 @@ impl App {
                 Task::none()
             }
             Message::FinishEditing => {
+                if self.get_active_tab().pending_transition_dialog_open {
+                    let label = self.get_active_tab().pending_transition_label.clone();
+                    let label = if label.trim().is_empty() { "ε".to_string() } else { label };
+                    if let Err(error) = self.get_active_tab().machine.validate_transition_label(&label) {
+                        self.error_message = Some(error);
+                        return Task::none();
+                    }
+                }
                 let active_tab = self.get_active_tab_mut();
                 // Handle pending transition dialog
                 if active_tab.pending_transition_dialog_open {
@@ -352,9 +683,21 @@ impl App {
                 Task::none()
             }
             Message::ToggleOperationsMenu => {
+                self.machine_menu_open = false;
                 self.get_active_tab_mut().operations_menu_open = !self.get_active_tab_mut().operations_menu_open;
                 Task::none()
             }
+            Message::ToggleMachineMenu => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.machine_menu_open = !self.machine_menu_open;
+                Task::none()
+            }
+            Message::NewMachineTab(kind) => {
+                self.machine_menu_open = false;
+                self.tabs.push(Box::new(Tab::new_with_kind(kind)));
+                self.active_tab = self.tabs.len() - 1;
+                Task::none()
+            }
             Message::CheckInput => {
                 self.get_active_tab_mut().operations_menu_open = false;
                 self.get_active_tab_mut().check_input_dialog_open = true;
@@ -364,41 +707,55 @@ impl App {
             Message::DfaToNfa => {
                 self.get_active_tab_mut().operations_menu_open = false;
                 
-                self.sync_gui_to_finite_automata();
+                self.sync_gui_to_machine();
                 
-                if self.get_active_tab().machine.is_deterministic() {
+                let machine = match &self.get_active_tab().machine {
+                    Machine::Finite(machine) => machine,
+                    _ => {
+                        self.error_message = Some("Cannot convert: The operation is only defined for finite automata.".to_string());
+                        return Task::none();
+                    }
+                };
+                if machine.is_deterministic() {
                     self.error_message = Some("Cannot convert: The automaton is already deterministic.".to_string());
                     return Task::none();
                 }
 
-                let dfa = self.get_active_tab().machine.to_dfa();
+                let dfa = machine.to_dfa();
                 
                 let mut new_tab = Tab::new_with_name("DFA".to_string());
-                new_tab.machine = dfa;
+                new_tab.machine = Machine::Finite(dfa);
                 self.tabs.push(Box::new(new_tab));
                 self.active_tab = self.tabs.len() - 1;
                 
-                self.load_finite_automata_to_gui();
+                self.load_machine_to_gui();
                 Task::none()
             }
             Message::Minimize => {
                 self.get_active_tab_mut().operations_menu_open = false;
                 
-                self.sync_gui_to_finite_automata();
+                self.sync_gui_to_machine();
                 
-                if !self.get_active_tab().machine.is_deterministic() {
+                let machine = match &self.get_active_tab().machine {
+                    Machine::Finite(machine) => machine,
+                    _ => {
+                        self.error_message = Some("Cannot minimize: The operation is only defined for finite automata.".to_string());
+                        return Task::none();
+                    }
+                };
+                if !machine.is_deterministic() {
                     self.error_message = Some("Cannot minimize: The automaton must be deterministic.".to_string());
                     return Task::none();
                 }
 
-                let minimized = self.get_active_tab().machine.minimize();
+                let minimized = machine.minimize();
                 
                 let mut new_tab = Tab::new_with_name("Minimized".to_string());
-                new_tab.machine = minimized;
+                new_tab.machine = Machine::Finite(minimized);
                 self.tabs.push(Box::new(new_tab));
                 self.active_tab = self.tabs.len() - 1;
                 
-                self.load_finite_automata_to_gui();
+                self.load_machine_to_gui();
                 Task::none()
             }
             Message::CheckInputTextChanged(text) => {
@@ -408,8 +765,36 @@ impl App {
             Message::SubmitCheckInput => {
                 let mut input = self.get_active_tab().check_input_text.clone();
                 // Allow blank inputs to be processed (don't convert to epsilon)
-                self.sync_gui_to_finite_automata();
-                let result = self.get_active_tab().machine.check_input(&mut input);
+                self.sync_gui_to_machine();
+                if self.get_active_tab().initial_state.is_none() {
+                    self.get_active_tab_mut().check_input_dialog_open = false;
+                    self.error_message = Some("Cannot check the input: The automaton has no initial state.".to_string());
+                    return Task::none();
+                }
+                let (result, trace, turing_run) = match &self.get_active_tab().machine {
+                    Machine::Finite(machine) => {
+                        let status = if machine.check_input(&mut input) { HaltingStatus::Accept } else { HaltingStatus::Reject };
+                        (status, None, None)
+                    }
+                    Machine::Pushdown(machine) => {
+                        let run = machine.run(&input);
+                        let trace = (run.status == HaltingStatus::Accept).then_some(run.trace);
+                        (run.status, trace, None)
+                    }
+                    Machine::Turing(machine) => {
+                        let run = if machine.is_deterministic() {
+                            machine.run(&input, turing_machine::MAX_STEPS)
+                        } else {
+                            machine.run_nondeterministic(&input, turing_machine::MAX_STEPS)
+                        };
+                        (run.status, None, Some(run))
+                    }
+                };
+                self.get_active_tab_mut().simulation_trace = trace;
+                self.get_active_tab_mut().simulation_step = 0;
+                self.get_active_tab_mut().turing_run = turing_run;
+                self.get_active_tab_mut().turing_step = 0;
+                self.update_highlighted_state();
                 self.get_active_tab_mut().check_input_result = Some(result);
                 self.get_active_tab_mut().check_result_popup_open = true;
                 self.get_active_tab_mut().check_input_dialog_open = false;
@@ -448,25 +833,8 @@ impl App {
                 Task::none()
             }
             Message::OpenLatexExport => {
-                // Convert transitions HashMap to Vec<Transition> for export
-                let mut export_transitions = Vec::new();
-                for (&(from, to), labels) in &self.get_active_tab().transitions {
-                    for label in labels {
-                        export_transitions.push(state_machine::Transition {
-                            from_state_id: from,
-                            to_state_id: to,
-                            from_point: iced::Point::ORIGIN, // dummy
-                            to_point: iced::Point::ORIGIN,   // dummy
-                            label: Box::leak(label.clone().into_boxed_str()),
-                        });
-                    }
-                }
-                let code = tikz_export::export_to_tikz(
-                    &self.get_active_tab().states,
-                    &export_transitions,
-                    self.get_active_tab().initial_state,
-                    &self.get_active_tab().final_states,
-                );
+                let file = Self::tab_to_machine_file(self.get_active_tab());
+                let code = tikz::to_tikz(&file);
                 self.latex_export_code = Some(code);
                 self.latex_export_dialog_open = true;
                 Task::none()
@@ -500,6 +868,13 @@ impl App {
                 Task::none()
             }
             Message::SaveEditTransitionLabels => {
+                for label in self.get_active_tab().editing_transition_label_inputs.iter() {
+                    let label = if label.trim().is_empty() { "ε" } else { label.as_str() };
+                    if let Err(error) = self.get_active_tab().machine.validate_transition_label(label) {
+                        self.error_message = Some(error);
+                        return Task::none();
+                    }
+                }
                 let active_tab = self.get_active_tab_mut();
                 if let Some((from, to)) = active_tab.editing_transition_pair {
                     // Convert empty strings to "ε" and filter out completely empty labels
@@ -547,238 +922,898 @@ impl App {
                 active_tab.editing_transition_dialog_open = false;
                 Task::none()
             }
-        }
-    }
-
-    fn set_initial_state(&mut self, state_id: usize) {
-        if self.get_active_tab_mut().initial_state == Some(state_id) {
-            self.get_active_tab_mut().initial_state = None;
-        } else {
-            self.get_active_tab_mut().initial_state = Some(state_id);
-        }
-        self.get_active_tab_mut().state_machine.request_redraw();
-    }
-
-    fn toggle_final_state(&mut self, state_id: usize) {
-        if self.get_active_tab_mut().final_states.contains(&state_id) {
-            self.get_active_tab_mut().final_states.remove(&state_id);
-        } else {
-            self.get_active_tab_mut().final_states.insert(state_id);
-        }
-        self.get_active_tab_mut().state_machine.request_redraw();
-    }
-
-    fn sync_gui_to_finite_automata(&mut self) {
-        let active_tab = self.get_active_tab_mut();
-        active_tab.machine.clear();
-
-        // Add all states
-        for state_node in &active_tab.states {
-            active_tab.machine.add_state_with_id_label(state_node.id as u64, state_node.label);
-        }
-
-        // Add all transitions (multi-label)
-        for (&(from, to), labels) in &active_tab.transitions {
-            for label in labels {
-                let label = if label.trim().is_empty() || label == "ε" { "ε" } else { label };
-                active_tab.machine.add_transition(from as u64, to as u64, label.to_string());
+            Message::SimulationStepBack => {
+                let active_tab = self.get_active_tab_mut();
+                active_tab.simulation_step = active_tab.simulation_step.saturating_sub(1);
+                Task::none()
             }
-        }
-
-        // Set final states
-        for &state_id in &active_tab.final_states {
-            active_tab.machine.make_final(state_id as u64);
-        }
-
-        // Set initial state
-        if let Some(initial_id) = active_tab.initial_state {
-            active_tab.machine.make_initial(initial_id as u64);
-        }
-    }
-
-    fn load_finite_automata_to_gui(&mut self) {
-        let active_tab = self.get_active_tab_mut();
-        
-        active_tab.states.clear();
-        active_tab.transitions.clear();
-        active_tab.state_id_to_index.clear();
-        active_tab.initial_state = None;
-        active_tab.final_states.clear();
-
-        let mut max_id_after_load = 0;
-        for (id, state) in active_tab.machine.get_states_by_id_ref() {
-            let state_node = state_machine::StateNode::new(
-                *id as usize,
-                iced::Point::new(100.0, 100.0), 
-                30.0, 
-                Box::leak(state.name.clone().into_boxed_str())
-            );
-            let index = active_tab.states.len();
-            active_tab.states.push(state_node);
-            active_tab.state_id_to_index.insert(*id as usize, index);
-            max_id_after_load = max_id_after_load.max(*id as usize);
-        }
-
-        active_tab.state_machine.next_id = max_id_after_load + 1;
-
-        // Add all transitions (multi-label)
-        for (from_id, state) in active_tab.machine.get_states_by_id_ref() {
-            for (to_id, inputs) in state.iter_by_transition() {
-                let key = (*from_id as usize, *to_id as usize);
-                let entry = active_tab.transitions.entry(key).or_insert_with(indexmap::IndexSet::new);
-                for label in inputs {
-                    let label = if label.trim().is_empty() || label == "ε" { "ε".to_string() } else { label.clone() };
-                    entry.insert(label);
+            Message::SimulationStepForward => {
+                let active_tab = self.get_active_tab_mut();
+                if let Some(trace) = &active_tab.simulation_trace {
+                    if active_tab.simulation_step + 1 < trace.len() {
+                        active_tab.simulation_step += 1;
+                    }
                 }
+                Task::none()
             }
-        }
-
-        if let Some(initial_id) = active_tab.machine.get_initial_state_id() {
-            if let Some(state) = active_tab.states.iter()
-                .find(|s| s.id == *initial_id as usize) {
-                active_tab.initial_state = Some(state.id);
+            Message::CloseSimulation => {
+                let active_tab = self.get_active_tab_mut();
+                active_tab.simulation_trace = None;
+                active_tab.simulation_step = 0;
+                Task::none()
             }
-        }
-
-        for final_id in active_tab.machine.get_final_states() {
-            if let Some(state) = active_tab.states.iter()
-                .find(|s| s.id == *final_id as usize) {
-                active_tab.final_states.insert(state.id);
+            Message::CloseItemSets => {
+                self.get_active_tab_mut().item_sets = None;
+                Task::none()
             }
-        }
-
-        if active_tab.initial_state.is_some() {
-            Self::apply_tree_layout_to_tab(active_tab);
-        } else {
-            Self::apply_grid_layout_to_tab(active_tab);
-        }
-
-        active_tab.state_machine.request_redraw();
-    }
-
-    fn apply_tree_layout_to_tab(active_tab: &mut Tab) {
-        use std::collections::{HashMap, HashSet};
-
-        let mut children_map: HashMap<usize, Vec<usize>> = HashMap::new();
-        let mut parent_map: HashMap<usize, usize> = HashMap::new();
-        let mut all_ids: HashSet<usize> = HashSet::new();
-        for state in &active_tab.states {
-            all_ids.insert(state.id);
-        }
-        for (&(from, to), _) in &active_tab.transitions {
-            if !parent_map.contains_key(&to) {
-                children_map.entry(from).or_default().push(to);
-                parent_map.insert(to, from);
+            Message::TuringStep => {
+                let active_tab = self.get_active_tab_mut();
+                if let Some(run) = &active_tab.turing_run {
+                    if active_tab.turing_step + 1 < run.trace.len() {
+                        active_tab.turing_step += 1;
+                    }
+                }
+                self.update_highlighted_state();
+                Task::none()
             }
-        }
-
-        let root_id = match active_tab.initial_state {
-            Some(id) => id,
-            None => return,
-        };
-
-        let mut x_counter = 0.0;
-        let x_spacing = 90.0;
-        let y_spacing = 120.0;
-        let start_x = 100.0;
-        let start_y = 100.0;
-
-        fn assign_positions(
-            node_id: usize,
-            depth: usize,
-            children_map: &HashMap<usize, Vec<usize>>,
-            state_map: &mut HashMap<usize, &mut state_machine::StateNode>,
-            x_counter: &mut f32,
-            x_spacing: f32,
-            y_spacing: f32,
-            start_x: f32,
-            start_y: f32,
-        ) -> f32 {
-            let children = children_map.get(&node_id);
-            let mut x = 0.0;
-            if let Some(children) = children {
-                let mut child_xs = Vec::new();
-                for &child_id in children {
-                    let cx = assign_positions(child_id, depth + 1, children_map, state_map, x_counter, x_spacing, y_spacing, start_x, start_y);
-                    child_xs.push(cx);
+            Message::TuringStepBack => {
+                let active_tab = self.get_active_tab_mut();
+                active_tab.turing_step = active_tab.turing_step.saturating_sub(1);
+                self.update_highlighted_state();
+                Task::none()
+            }
+            Message::TuringRunToEnd => {
+                let active_tab = self.get_active_tab_mut();
+                if let Some(run) = &active_tab.turing_run {
+                    active_tab.turing_step = run.trace.len().saturating_sub(1);
                 }
-                if !child_xs.is_empty() {
-                    x = (child_xs[0] + child_xs[child_xs.len() - 1]) / 2.0;
-                } else {
-                    x = *x_counter;
-                    *x_counter += x_spacing;
+                self.update_highlighted_state();
+                Task::none()
+            }
+            Message::TuringReset => {
+                self.get_active_tab_mut().turing_step = 0;
+                self.update_highlighted_state();
+                Task::none()
+            }
+            Message::CloseTuringSimulation => {
+                let active_tab = self.get_active_tab_mut();
+                active_tab.turing_run = None;
+                active_tab.turing_step = 0;
+                self.update_highlighted_state();
+                Task::none()
+            }
+            Message::OpenGrammarDialog(operation) => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.grammar_dialog = Some(operation);
+                Task::none()
+            }
+            Message::GrammarTextEdited(action) => {
+                self.grammar_text.perform(action);
+                Task::none()
+            }
+            Message::GrammarWordChanged(word) => {
+                self.grammar_word = word;
+                Task::none()
+            }
+            Message::SubmitGrammar => {
+                let operation = match self.grammar_dialog {
+                    Some(operation) => operation,
+                    None => return Task::none(),
+                };
+                let grammar = match Grammar::parse(&self.grammar_text.text()) {
+                    Ok(grammar) => grammar,
+                    Err(error) => {
+                        self.error_message = Some(format!("Invalid grammar, {}.", error));
+                        return Task::none();
+                    }
+                };
+                self.grammar_dialog = None;
+                match operation {
+                    GrammarOperation::ToFinite => {
+                        let finite_automaton = match grammar.to_finite_automata() {
+                            Some(finite_automaton) => finite_automaton,
+                            None => {
+                                self.grammar_dialog = Some(operation);
+                                self.error_message = Some("Cannot convert: The grammar must be right-linear or left-linear.".to_string());
+                                return Task::none();
+                            }
+                        };
+                        let mut new_tab = Tab::new_with_kind(MachineKind::Finite);
+                        new_tab.machine = Machine::Finite(finite_automaton);
+                        self.tabs.push(Box::new(new_tab));
+                        self.active_tab = self.tabs.len() - 1;
+                        self.load_machine_to_gui();
+                    }
+                    GrammarOperation::ToPushdown => {
+                        let mut new_tab = Tab::new_with_kind(MachineKind::Pushdown);
+                        new_tab.machine = Machine::Pushdown(grammar.to_pushdown_automata());
+                        self.tabs.push(Box::new(new_tab));
+                        self.active_tab = self.tabs.len() - 1;
+                        self.load_machine_to_gui();
+                    }
+                    GrammarOperation::ToChomskyNormalForm => {
+                        let steps: Vec<String> = grammar.chomsky_normal_form_steps().iter()
+                            .enumerate()
+                            .map(|(index, step)| format!("{}. {}:\n{}", index + 1, step.name, step.grammar))
+                            .collect();
+                        self.text_result = Some((operation.name().to_string(), steps.join("\n")));
+                    }
+                    GrammarOperation::ToGreibachNormalForm => {
+                        let steps = grammar.greibach_normal_form_steps();
+                        let text: Vec<String> = steps.iter()
+                            .enumerate()
+                            .map(|(index, step)| format!("{}. {}:\n{}", index + 1, step.name, step.grammar))
+                            .collect();
+                        self.text_result = Some((operation.name().to_string(), text.join("\n")));
+                        // The grammar in Greibach normal form is also opened as a single-state PDA
+                        if let Some(pushdown_automaton) = steps.last().and_then(|step| step.grammar.to_single_state_pushdown_automata()) {
+                            let mut new_tab = Tab::new_with_kind(MachineKind::Pushdown);
+                            new_tab.machine = Machine::Pushdown(pushdown_automaton);
+                            self.tabs.push(Box::new(new_tab));
+                            self.active_tab = self.tabs.len() - 1;
+                            self.load_machine_to_gui();
+                        }
+                    }
+                    GrammarOperation::Analysis => {
+                        self.text_result = Some((operation.name().to_string(), grammar.report().to_string()));
+                    }
+                    GrammarOperation::Ambiguity | GrammarOperation::Generate => {
+                        let max_length = match self.grammar_word.trim().parse::<usize>() {
+                            Ok(max_length) => max_length,
+                            Err(_) => {
+                                self.grammar_dialog = Some(operation);
+                                self.error_message = Some("Invalid maximum length, it must be a non-negative number.".to_string());
+                                return Task::none();
+                            }
+                        };
+                        let result = if operation == GrammarOperation::Ambiguity {
+                            match GrammarAmbiguity::find(&grammar, max_length) {
+                                AmbiguitySearch::Found(ambiguity) => ambiguity.to_string(),
+                                AmbiguitySearch::NotFound => format!("No word of length at most {} has two leftmost derivations.", max_length),
+                                AmbiguitySearch::Truncated => format!(
+                                    "No word with two leftmost derivations was found, but the search stopped after {} sentential forms \
+                                     before trying every word of length at most {}, so the grammar may still be ambiguous.",
+                                    MAX_SENTENTIAL_FORMS, max_length),
+                            }
+                        } else {
+                            let mut result = format!("Sentences of length at most {}:\n", max_length);
+                            let list = generator::all_sentences(&grammar, max_length);
+                            for sentence in list.sentences.iter() {
+                                result.push_str(&format!("  {}\n", format_symbols(sentence)));
+                            }
+                            if list.truncated {
+                                result.push_str(&format!(
+                                    "  The search stopped after {} sentential forms, so some sentences may be missing.\n",
+                                    MAX_SENTENTIAL_FORMS));
+                            }
+                            // The seed changes every time, it is shown so the sentences can be generated again
+                            let seed = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .map_or(0, |duration| duration.as_secs());
+                            let mut sentence_generator = SentenceGenerator::new(&grammar, seed);
+                            result.push_str(&format!("\nRandom sentences (seed {}, maximum depth {}):\n",
+                                seed, sentence_generator.get_max_depth()));
+                            for sentence in sentence_generator.generate_many(RANDOM_SENTENCES) {
+                                result.push_str(&format!("  {}\n", format_symbols(&sentence)));
+                            }
+                            result
+                        };
+                        self.text_result = Some((operation.name().to_string(), result));
+                    }
+                    GrammarOperation::Cyk => {
+                        // The table is only defined for grammars in Chomsky normal form
+                        let grammar = if grammar.is_chomsky_normal_form() {
+                            grammar
+                        } else {
+                            grammar.to_chomsky_normal_form()
+                        };
+                        let word = grammar.parse_sentential_form(&self.grammar_word);
+                        self.cyk_table = CykTable::new(&grammar, &word);
+                    }
+                    GrammarOperation::Earley => {
+                        let word = grammar.parse_sentential_form(&self.grammar_word);
+                        let chart = EarleyChart::new(&grammar, &word);
+                        let list = chart.parse_trees();
+                        let mut result = if !chart.is_accepted() {
+                            "The word is rejected".to_string()
+                        } else if list.truncated {
+                            format!("The word is accepted with more than {} parse trees, only the first {} are shown",
+                                earley::MAX_PARSE_TREES, earley::MAX_PARSE_TREES)
+                        } else {
+                            format!("The word is accepted with {} parse tree(s)", list.trees.len())
+                        };
+                        result.push_str("\n\n");
+                        for tree in list.trees.iter() {
+                            result.push_str(&format!("{}\n", tree));
+                        }
+                        for (index, set) in chart.get_sets().iter().enumerate() {
+                            result.push_str(&format!("Set {}:\n", index));
+                            for item in set.iter() {
+                                result.push_str(&format!("  {}\n", item));
+                            }
+                        }
+                        self.text_result = Some((operation.name().to_string(), result));
+                    }
+                    GrammarOperation::LL1 => {
+                        let word = grammar.parse_sentential_form(&self.grammar_word);
+                        let table = LL1Table::new(&grammar);
+                        let mut result = String::from("FIRST:\n");
+                        for (nonterminal, first) in table.get_first_sets() {
+                            result.push_str(&format!("  {}: {{{}}}\n", nonterminal, first.iter().cloned().collect::<Vec<_>>().join(", ")));
+                        }
+                        result.push_str("FOLLOW:\n");
+                        for (nonterminal, follow) in table.get_follow_sets() {
+                            result.push_str(&format!("  {}: {{{}}}\n", nonterminal, follow.iter().cloned().collect::<Vec<_>>().join(", ")));
+                        }
+                        result.push_str(&format!("\n{}\n", table));
+                        let conflicts = table.get_conflicts();
+                        if conflicts.is_empty() {
+                            result.push_str("The grammar is LL(1)\n");
+                        } else {
+                            result.push_str("The grammar is not LL(1), conflicts:\n");
+                            for conflict in conflicts.iter() {
+                                result.push_str(&format!("  {}\n", conflict));
+                            }
+                        }
+                        result.push_str("\nStack | Input | Action\n");
+                        for step in table.parse(&word) {
+                            result.push_str(&format!("{} | {} | {}\n",
+                                format_symbols(&step.stack), format_symbols(&step.remaining_input), step.action));
+                        }
+                        self.text_result = Some((operation.name().to_string(), result));
+                    }
+                    GrammarOperation::LR => {
+                        let automaton = LR0Automaton::new(&grammar);
+                        let mut result = format!("{}", automaton);
+                        for table in [LRTable::slr(&automaton), LRTable::lalr(&automaton)] {
+                            result.push_str(&format!("\n{} table:\n{}", table.get_kind().name(), table));
+                            let conflicts = table.get_conflicts();
+                            if conflicts.is_empty() {
+                                result.push_str(&format!("The grammar is {}\n", table.get_kind().name()));
+                            } else {
+                                result.push_str(&format!("The grammar is not {}, conflicts:\n", table.get_kind().name()));
+                                for conflict in conflicts.iter() {
+                                    result.push_str(&format!("  {}\n", conflict));
+                                }
+                            }
+                        }
+                        let mut new_tab = Tab::new_with_name("LR(0)".to_string());
+                        new_tab.machine = Machine::Finite(automaton.to_finite_automata());
+                        // The states are named I0, I1, ... in the canvas, their items are listed next to it
+                        new_tab.item_sets = Some(automaton.get_states().iter().enumerate()
+                            .map(|(index, items)| (format!("I{}", index), items.iter().map(|item| item.to_string()).collect()))
+                            .collect());
+                        self.tabs.push(Box::new(new_tab));
+                        self.active_tab = self.tabs.len() - 1;
+                        self.load_machine_to_gui();
+                        self.text_result = Some((operation.name().to_string(), result));
+                    }
                 }
-            } else {
-                x = *x_counter;
-                *x_counter += x_spacing;
+                Task::none()
             }
-            if let Some(state) = state_map.get_mut(&node_id) {
-                state.position = iced::Point::new(start_x + x, start_y + (depth as f32) * y_spacing);
+            Message::CancelGrammar => {
+                self.grammar_dialog = None;
+                Task::none()
+            }
+            Message::OpenTestSuite => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.test_suite_open = true;
+                self.test_suite_report = None;
+                Task::none()
+            }
+            Message::TestSuiteEdited(action) => {
+                if action.is_edit() {
+                    self.test_suite_report = None;
+                }
+                self.test_suite_text.perform(action);
+                Task::none()
+            }
+            Message::RunTestSuite => {
+                let suite = match TestSuite::parse(&self.test_suite_text.text()) {
+                    Ok(suite) => suite,
+                    Err(error) => {
+                        self.error_message = Some(format!("Invalid test suite, {}.", error));
+                        return Task::none();
+                    }
+                };
+                self.sync_gui_to_machine();
+                if self.get_active_tab().initial_state.is_none() {
+                    self.error_message = Some("Cannot run the test suite: The automaton has no initial state.".to_string());
+                    return Task::none();
+                }
+                self.test_suite_report = Some(suite.run(&self.get_active_tab().machine.to_file_machine()));
+                Task::none()
+            }
+            Message::CloseTestSuite => {
+                self.test_suite_open = false;
+                Task::none()
+            }
+            Message::OpenAutomatonText => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.automaton_text_dialog_open = true;
+                Task::none()
+            }
+            Message::AutomatonTextEdited(action) => {
+                self.automaton_text.perform(action);
+                Task::none()
+            }
+            Message::SubmitAutomatonText => {
+                let file = match automaton_text::parse_automaton(&self.automaton_text.text()) {
+                    Ok(file) => file,
+                    Err(error) => {
+                        self.error_message = Some(format!("Invalid automaton, {}.", error));
+                        return Task::none();
+                    }
+                };
+                match Self::tab_from_machine_file(None, &file) {
+                    Ok(new_tab) => {
+                        self.automaton_text_dialog_open = false;
+                        self.tabs.push(Box::new(new_tab));
+                        self.active_tab = self.tabs.len() - 1;
+                    }
+                    Err(error) => self.error_message = Some(error),
+                }
+                Task::none()
+            }
+            Message::CancelAutomatonText => {
+                self.automaton_text_dialog_open = false;
+                Task::none()
+            }
+            Message::MachineToText => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                let file = Self::tab_to_machine_file(self.get_active_tab());
+                self.text_result = Some(("Machine to Text".to_string(), automaton_text::format_automaton(&file)));
+                Task::none()
+            }
+            Message::MachineToGrammar => {
+                self.get_active_tab_mut().operations_menu_open = false;
+
+                self.sync_gui_to_machine();
+
+                let machine = &self.get_active_tab().machine;
+                if machine.as_state_machine().get_initial_state_id().is_none() {
+                    self.error_message = Some("Cannot convert: The automaton has no initial state.".to_string());
+                    return Task::none();
+                }
+                let (title, grammar) = match machine {
+                    Machine::Finite(machine) => ("FA to Grammar", machine.to_grammar()),
+                    Machine::Pushdown(machine) => ("PDA to Grammar", machine.to_grammar()),
+                    Machine::Turing(_) => {
+                        self.error_message = Some("Cannot convert: The operation is only defined for finite and pushdown automata.".to_string());
+                        return Task::none();
+                    }
+                };
+                self.text_result = Some((title.to_string(), grammar.to_string()));
+                Task::none()
+            }
+            Message::OpenAmbiguityDialog => {
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.ambiguity_dialog_open = true;
+                Task::none()
+            }
+            Message::AmbiguityLengthChanged(text) => {
+                self.ambiguity_length = text;
+                Task::none()
+            }
+            Message::CancelAmbiguityDialog => {
+                self.ambiguity_dialog_open = false;
+                Task::none()
+            }
+            Message::FindAmbiguity => {
+                let max_length = match self.ambiguity_length.trim().parse::<usize>() {
+                    Ok(max_length) => max_length,
+                    Err(_) => {
+                        self.error_message = Some("Invalid maximum length, it must be a non-negative number.".to_string());
+                        return Task::none();
+                    }
+                };
+                self.ambiguity_dialog_open = false;
+
+                self.sync_gui_to_machine();
+
+                let finite_automaton = match &self.get_active_tab().machine {
+                    Machine::Finite(machine) => machine,
+                    _ => {
+                        self.error_message = Some("Cannot search: The ambiguity search only works with finite automata.".to_string());
+                        return Task::none();
+                    }
+                };
+                if finite_automaton.get_initial_state_id().is_none() {
+                    self.error_message = Some("Cannot search: The automaton has no initial state.".to_string());
+                    return Task::none();
+                }
+                let result = match AutomatonAmbiguity::find(finite_automaton, max_length) {
+                    AmbiguitySearch::Found(ambiguity) => ambiguity.to_string(),
+                    AmbiguitySearch::NotFound => format!("No word of length at most {} has two accepting runs.", max_length),
+                    AmbiguitySearch::Truncated => format!(
+                        "No word with two accepting runs was found, but the search stopped after {} configurations \
+                         before trying every word of length at most {}, so the automaton may still be ambiguous.",
+                        pushdown_automata::MAX_CONFIGURATIONS, max_length),
+                };
+                self.text_result = Some(("NFA Ambiguity".to_string(), result));
+                Task::none()
+            }
+            Message::CopyTextResult => {
+                if let Some((_, text)) = &self.text_result {
+                    return iced::clipboard::write(text.clone());
+                }
+                Task::none()
+            }
+            Message::CloseTextResult => {
+                self.text_result = None;
+                Task::none()
+            }
+            Message::ExportCykTable => {
+                if let Some(table) = &self.cyk_table {
+                    let code = tikz_export::export_cyk_table_to_tikz(table);
+                    self.text_result = Some(("LaTeX (TikZ) code for the CYK table:".to_string(), code));
+                }
+                Task::none()
+            }
+            Message::CloseCykTable => {
+                self.cyk_table = None;
+                Task::none()
+            }
+            Message::OpenFileDialog(operation) => {
+                self.machine_menu_open = false;
+                self.get_active_tab_mut().operations_menu_open = false;
+                self.file_dialog = Some(operation);
+                Task::none()
+            }
+            Message::FilePathChanged(path) => {
+                self.file_path = path;
+                Task::none()
+            }
+            Message::SubmitFileDialog => {
+                let path = self.file_path.trim().to_string();
+                match self.file_dialog.take() {
+                    Some(FileOperation::Save) => self.save_active_tab(&path),
+                    Some(FileOperation::Open) => self.open_machine_file(&path),
+                    Some(FileOperation::SaveProject) => {
+                        if let Err(error) = self.save_project_file(Path::new(&path)) {
+                            self.error_message = Some(format!("Cannot save the project {}: {}", path, error));
+                        }
+                    }
+                    Some(FileOperation::OpenProject) => {
+                        if let Err(error) = self.load_project_file(Path::new(&path)) {
+                            self.error_message = Some(format!("Cannot open the project {}: {}", path, error));
+                        }
+                    }
+                    Some(FileOperation::OpenTestSuite) => match std::fs::read_to_string(&path) {
+                        Ok(text) => {
+                            self.test_suite_text = text_editor::Content::with_text(&text);
+                            self.test_suite_report = None;
+                        }
+                        Err(error) => self.error_message = Some(format!("Cannot open the test suite {}: {}", path, error)),
+                    },
+                    Some(FileOperation::SaveTestSuite) => {
+                        if let Err(error) = std::fs::write(&path, self.test_suite_text.text()) {
+                            self.error_message = Some(format!("Cannot save the test suite {}: {}", path, error));
+                        }
+                    }
+                    None => (),
+                }
+                Task::none()
+            }
+            Message::CancelFileDialog => {
+                self.file_dialog = None;
+                Task::none()
+            }
+            Message::Autosave => {
+                if self.autosave_disabled {
+                    return Task::none();
+                }
+                if let Some(path) = session_path() {
+                    let result = path.parent()
+                        .map_or(Ok(()), std::fs::create_dir_all)
+                        .map_err(|error| error.to_string())
+                        .and_then(|_| self.save_project_file(&path));
+                    match result {
+                        Ok(()) => self.autosave_failed = false,
+                        Err(error) => {
+                            if !self.autosave_failed {
+                                self.error_message = Some(format!("Cannot autosave the session in {}: {}", path.display(), error));
+                            }
+                            self.autosave_failed = true;
+                        }
+                    }
+                }
+                Task::none()
             }
-            x
         }
+    }
 
-        let mut state_map: HashMap<usize, &mut state_machine::StateNode> =
-            active_tab.states.iter_mut().map(|s| (s.id, s)).collect();
-        assign_positions(
-            root_id,
-            0,
-            &children_map,
-            &mut state_map,
-            &mut x_counter,
-            x_spacing,
-            y_spacing,
-            start_x,
-            start_y,
-        );
+    fn set_initial_state(&mut self, state_id: usize) {
+        if self.get_active_tab_mut().initial_state == Some(state_id) {
+            self.get_active_tab_mut().initial_state = None;
+        } else {
+            self.get_active_tab_mut().initial_state = Some(state_id);
+        }
+        self.get_active_tab_mut().state_machine.request_redraw();
+    }
 
-        let placed: HashSet<usize> = state_map.keys().copied().collect();
-        let unreachable: Vec<usize> = all_ids.difference(&placed).copied().collect();
-        let unreachable_y = start_y + 4.0 * y_spacing;
-        for (i, id) in unreachable.iter().enumerate() {
-            if let Some(state) = state_map.get_mut(id) {
-                state.position = iced::Point::new(start_x + (i as f32) * x_spacing, unreachable_y);
+    fn toggle_final_state(&mut self, state_id: usize) {
+        if self.get_active_tab_mut().final_states.contains(&state_id) {
+            self.get_active_tab_mut().final_states.remove(&state_id);
+        } else {
+            self.get_active_tab_mut().final_states.insert(state_id);
+        }
+        self.get_active_tab_mut().state_machine.request_redraw();
+    }
+
+    // Highlights on the canvas the state of the Turing machine configuration being shown.
+    fn update_highlighted_state(&mut self) {
+        let active_tab = self.get_active_tab_mut();
+        let state_id = active_tab.turing_run.as_ref()
+            .and_then(|run| run.trace.get(active_tab.turing_step))
+            .map(|configuration| configuration.state_id as usize);
+        active_tab.state_machine.set_highlighted_state(state_id);
+    }
+
+    /* Function that writes the machine of the active tab, with the
+     * positions of its states, in a machine file, or in a JFLAP file, a DOT
+     * graph or the text format depending on the extension of the path. */
+    fn save_active_tab(&mut self, path: &str) {
+        let file = Self::tab_to_machine_file(self.get_active_tab());
+        let text = match FileFormat::of_path(path).write(&file) {
+            Ok(text) => text,
+            Err(error) => {
+                self.error_message = Some(format!("Cannot save the file {}: {}", path, error));
+                return;
             }
+        };
+        if let Err(error) = std::fs::write(path, text) {
+            self.error_message = Some(format!("Cannot save the file {}: {}", path, error));
         }
+    }
 
-        // No-op: no from_point/to_point to update
+    /* Function that opens the machine of a machine file, or of a JFLAP file,
+     * a DOT graph or the text format depending on the extension of the path, in a new tab
+     * named after the file. The states are placed where they were saved, the
+     * ones without a position are placed by the layout. */
+    fn open_machine_file(&mut self, path: &str) {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) => {
+                self.error_message = Some(format!("Cannot open the file {}: {}", path, error));
+                return;
+            }
+        };
+        let file = match FileFormat::of_path(path).read(&text) {
+            Ok(file) => file,
+            Err(error) => {
+                self.error_message = Some(format!("Cannot open the file {}: {}", path, error));
+                return;
+            }
+        };
+        let name = Path::new(path).file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+        match Self::tab_from_machine_file(name, &file) {
+            Ok(new_tab) => {
+                self.tabs.push(Box::new(new_tab));
+                self.active_tab = self.tabs.len() - 1;
+            }
+            Err(error) => self.error_message = Some(error),
+        }
     }
 
-    fn apply_grid_layout_to_tab(active_tab: &mut Tab) {
-        let states = &mut active_tab.states;
-        
-        if states.is_empty() {
-            return;
+    // Machine file of the machine of a tab, with the positions of its states. The tab is not changed.
+    fn tab_to_machine_file(tab: &Tab) -> MachineFile {
+        let machine = Self::synced_machine(tab);
+        let positions: HashMap<u64, Position> = tab.states.iter()
+            .map(|state| (state.id as u64, Position { x: state.position.x, y: state.position.y }))
+            .collect();
+        MachineFile::new(&machine.to_file_machine(), &positions)
+    }
+
+    /* Function that builds a tab that edits the machine of a machine file,
+     * named after the kind of the machine if there is no name. The states are
+     * placed where they were saved, the ones without a position are placed by
+     * the layout. */
+    fn tab_from_machine_file(name: Option<String>, file: &MachineFile) -> Result<Tab, String> {
+        let (kind, machine) = Machine::from_file_machine(file.to_machine())?;
+        let mut tab = Tab::new_with_kind(kind);
+        if let Some(name) = name {
+            tab.name = name;
         }
+        tab.machine = machine;
+        Self::load_machine_to_tab(&mut tab);
 
-        let grid_size = (states.len() as f32).sqrt().ceil() as usize;
-        let spacing = 150.0; 
-        let start_x = 100.0;
-        let start_y = 100.0;
+        let positions = file.positions();
+        for state in tab.states.iter_mut() {
+            if let Some(position) = positions.get(&(state.id as u64)) {
+                state.position = iced::Point::new(position.x, position.y);
+            }
+        }
+        tab.state_machine.request_redraw();
+        Ok(tab)
+    }
 
-        for (i, state) in states.iter_mut().enumerate() {
-            let row = i / grid_size;
-            let col = i % grid_size;
-            state.position = iced::Point::new(
-                start_x + (col as f32 * spacing),
-                start_y + (row as f32 * spacing)
-            );
+    /* Function that writes every tab, with its name, its machine and the
+     * positions of its states, in a project file. */
+    fn save_project_file(&self, path: &Path) -> Result<(), String> {
+        let tabs = self.tabs.iter()
+            .map(|tab| ProjectTab { name: tab.name.clone(), machine: Self::tab_to_machine_file(tab) })
+            .collect();
+        let project = ProjectFile::new(tabs, self.active_tab);
+        // The file is written next to the destination and then renamed, so an interrupted write
+        // doesn't leave it truncated.
+        let mut temporary_name = path.file_name().unwrap_or_default().to_os_string();
+        temporary_name.push(".tmp");
+        let temporary_path = path.with_file_name(temporary_name);
+        std::fs::write(&temporary_path, project.to_json())
+            .and_then(|_| std::fs::rename(&temporary_path, path))
+            .map_err(|error| error.to_string())
+    }
+
+    /* Function that replaces the tabs with the ones of a project file. The
+     * tabs are kept if the file can't be read. */
+    fn load_project_file(&mut self, path: &Path) -> Result<(), String> {
+        let text = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let project = ProjectFile::from_json(&text).map_err(|error| error.to_string())?;
+        let mut tabs = Vec::new();
+        for project_tab in project.tabs.iter() {
+            tabs.push(Box::new(Self::tab_from_machine_file(Some(project_tab.name.clone()), &project_tab.machine)?));
         }
+        if tabs.is_empty() {
+            tabs.push(Box::new(Tab::new()));
+        }
+        self.active_tab = project.active_tab.min(tabs.len() - 1);
+        self.tabs = tabs;
+        Ok(())
+    }
 
-        // No-op: no from_point/to_point to update
+    fn sync_gui_to_machine(&mut self) {
+        Self::sync_tab_to_machine(self.get_active_tab_mut());
     }
 
-    fn create_menu_bar(&self) -> Element<Message> {
-        let abstract_machine_button = button(text("Abstract Machine"))
-            .style(|_theme: &iced::Theme, _status| {
-                button::Style {
-                    background: Some(iced::Color::from_rgba(0.176, 0.172, 0.176, 1.0).into()), 
-                    text_color: iced::Color::from_rgba(0.6, 0.6, 0.6, 1.0), 
-                    border: iced::Border::default(),
-                    ..Default::default()
-                }
-            })
-            .padding([4, 12]);
+    fn sync_tab_to_machine(active_tab: &mut Tab) {
+        active_tab.machine = Self::synced_machine(active_tab);
+    }
+
+    // Returns a copy of the machine of a tab with the states and transitions of its canvas.
+    fn synced_machine(active_tab: &Tab) -> Machine {
+        let mut synced = active_tab.machine.clone();
+        // The labels of the states, e.g. the subsets of the subset construction, are not edited
+        // in the canvas, so they are kept from the previous machine.
+        let state_labels: HashMap<u64, BTreeSet<u64>> = synced.as_state_machine()
+            .get_states_by_id_ref()
+            .iter()
+            .map(|(id, state)| (*id, state.label.clone()))
+            .collect();
+        synced.clear();
+        let machine = synced.as_state_machine_mut();
+
+        // Add all states
+        for state_node in &active_tab.states {
+            machine.add_state_with_id_label(state_node.id as u64, state_node.label);
+        }
+        for (id, state) in machine.get_states_by_id_mut_ref().iter_mut() {
+            if let Some(label) = state_labels.get(id) {
+                state.label = label.clone();
+            }
+        }
+
+        // Add all transitions (multi-label)
+        for (&(from, to), labels) in &active_tab.transitions {
+            for label in labels {
+                let label = if label.trim().is_empty() || label == "ε" { "ε" } else { label };
+                machine.add_transition(from as u64, to as u64, label.to_string());
+            }
+        }
+
+        // Set final states
+        for &state_id in &active_tab.final_states {
+            machine.make_final(state_id as u64);
+        }
+
+        // Set initial state
+        if let Some(initial_id) = active_tab.initial_state {
+            machine.make_initial(initial_id as u64);
+        }
+        synced
+    }
+
+    fn load_machine_to_gui(&mut self) {
+        Self::load_machine_to_tab(self.get_active_tab_mut());
+    }
+
+    fn load_machine_to_tab(active_tab: &mut Tab) {
+        let machine = active_tab.machine.as_state_machine();
+        
+        active_tab.states.clear();
+        active_tab.transitions.clear();
+        active_tab.state_id_to_index.clear();
+        active_tab.initial_state = None;
+        active_tab.final_states.clear();
+
+        let mut max_id_after_load = 0;
+        for (id, state) in machine.get_states_by_id_ref() {
+            let state_node = state_machine::StateNode::new(
+                *id as usize,
+                iced::Point::new(100.0, 100.0), 
+                30.0, 
+                Box::leak(state.name.clone().into_boxed_str())
+            );
+            let index = active_tab.states.len();
+            active_tab.states.push(state_node);
+            active_tab.state_id_to_index.insert(*id as usize, index);
+            max_id_after_load = max_id_after_load.max(*id as usize);
+        }
+
+        active_tab.state_machine.next_id = max_id_after_load + 1;
+
+        // Add all transitions (multi-label)
+        for (from_id, state) in machine.get_states_by_id_ref() {
+            for (to_id, inputs) in state.iter_by_transition() {
+                let key = (*from_id as usize, *to_id as usize);
+                let entry = active_tab.transitions.entry(key).or_insert_with(indexmap::IndexSet::new);
+                for label in inputs {
+                    let label = if label.trim().is_empty() || label == "ε" { "ε".to_string() } else { label.clone() };
+                    entry.insert(label);
+                }
+            }
+        }
+
+        if let Some(initial_id) = machine.get_initial_state_id() {
+            if let Some(state) = active_tab.states.iter()
+                .find(|s| s.id == *initial_id as usize) {
+                active_tab.initial_state = Some(state.id);
+            }
+        }
+
+        for final_id in machine.get_final_states() {
+            if let Some(state) = active_tab.states.iter()
+                .find(|s| s.id == *final_id as usize) {
+                active_tab.final_states.insert(state.id);
+            }
+        }
+
+        if active_tab.initial_state.is_some() {
+            Self::apply_tree_layout_to_tab(active_tab);
+        } else {
+            Self::apply_grid_layout_to_tab(active_tab);
+        }
+
+        active_tab.state_machine.request_redraw();
+    }
+
+    fn apply_tree_layout_to_tab(active_tab: &mut Tab) {
+        use std::collections::{HashMap, HashSet};
+
+        let mut children_map: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut parent_map: HashMap<usize, usize> = HashMap::new();
+        let mut all_ids: HashSet<usize> = HashSet::new();
+        for state in &active_tab.states {
+            all_ids.insert(state.id);
+        }
+        for (&(from, to), _) in &active_tab.transitions {
+            if !parent_map.contains_key(&to) {
+                children_map.entry(from).or_default().push(to);
+                parent_map.insert(to, from);
+            }
+        }
+
+        let root_id = match active_tab.initial_state {
+            Some(id) => id,
+            None => return,
+        };
+
+        let mut x_counter = 0.0;
+        let x_spacing = 90.0;
+        let y_spacing = 120.0;
+        let start_x = 100.0;
+        let start_y = 100.0;
+
+        fn assign_positions(
+            node_id: usize,
+            depth: usize,
+            children_map: &HashMap<usize, Vec<usize>>,
+            state_map: &mut HashMap<usize, &mut state_machine::StateNode>,
+            x_counter: &mut f32,
+            x_spacing: f32,
+            y_spacing: f32,
+            start_x: f32,
+            start_y: f32,
+        ) -> f32 {
+            let children = children_map.get(&node_id);
+            let mut x = 0.0;
+            if let Some(children) = children {
+                let mut child_xs = Vec::new();
+                for &child_id in children {
+                    let cx = assign_positions(child_id, depth + 1, children_map, state_map, x_counter, x_spacing, y_spacing, start_x, start_y);
+                    child_xs.push(cx);
+                }
+                if !child_xs.is_empty() {
+                    x = (child_xs[0] + child_xs[child_xs.len() - 1]) / 2.0;
+                } else {
+                    x = *x_counter;
+                    *x_counter += x_spacing;
+                }
+            } else {
+                x = *x_counter;
+                *x_counter += x_spacing;
+            }
+            if let Some(state) = state_map.get_mut(&node_id) {
+                state.position = iced::Point::new(start_x + x, start_y + (depth as f32) * y_spacing);
+            }
+            x
+        }
+
+        let mut state_map: HashMap<usize, &mut state_machine::StateNode> =
+            active_tab.states.iter_mut().map(|s| (s.id, s)).collect();
+        assign_positions(
+            root_id,
+            0,
+            &children_map,
+            &mut state_map,
+            &mut x_counter,
+            x_spacing,
+            y_spacing,
+            start_x,
+            start_y,
+        );
+
+        let placed: HashSet<usize> = state_map.keys().copied().collect();
+        let unreachable: Vec<usize> = all_ids.difference(&placed).copied().collect();
+        let unreachable_y = start_y + 4.0 * y_spacing;
+        for (i, id) in unreachable.iter().enumerate() {
+            if let Some(state) = state_map.get_mut(id) {
+                state.position = iced::Point::new(start_x + (i as f32) * x_spacing, unreachable_y);
+            }
+        }
+
+        // No-op: no from_point/to_point to update
+    }
+
+    fn apply_grid_layout_to_tab(active_tab: &mut Tab) {
+        let states = &mut active_tab.states;
+        
+        if states.is_empty() {
+            return;
+        }
+
+        let grid_size = (states.len() as f32).sqrt().ceil() as usize;
+        let spacing = 150.0; 
+        let start_x = 100.0;
+        let start_y = 100.0;
+
+        for (i, state) in states.iter_mut().enumerate() {
+            let row = i / grid_size;
+            let col = i % grid_size;
+            state.position = iced::Point::new(
+                start_x + (col as f32 * spacing),
+                start_y + (row as f32 * spacing)
+            );
+        }
+
+        // No-op: no from_point/to_point to update
+    }
+
+    fn create_menu_bar(&self) -> Element<Message> {
+        let abstract_machine_button = button(text("Abstract Machine"))
+            .on_press(Message::ToggleMachineMenu)
+            .style(|_theme: &iced::Theme, status| {
+                let background_color = iced::Color::from_rgba(0.176, 0.172, 0.176, 1.0); 
+                let hover_color = iced::Color::from_rgba(0.25, 0.24, 0.25, 1.0); 
+                let text_color = iced::Color::WHITE;
+                
+                match status {
+                    button::Status::Hovered => button::Style {
+                        background: Some(hover_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    },
+                    _ => button::Style {
+                        background: Some(background_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                }
+            })
+            .padding([4, 12]);
 
         let operations_button = button(text("Operation"))
             .on_press(Message::ToggleOperationsMenu)
@@ -817,127 +1852,809 @@ impl App {
                         border: iced::Border::default(),
                         ..Default::default()
                     },
-                    _ => button::Style {
-                        background: Some(background_color.into()),
-                        text_color,
-                        border: iced::Border::default(),
-                        ..Default::default()
-                    }
+                    _ => button::Style {
+                        background: Some(background_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                }
+            })
+            .padding([4, 12]);
+
+        let open_button = Self::create_menu_bar_button(FileOperation::Open.name(), Message::OpenFileDialog(FileOperation::Open));
+        let save_button = Self::create_menu_bar_button(FileOperation::Save.name(), Message::OpenFileDialog(FileOperation::Save));
+        let open_project_button = Self::create_menu_bar_button(FileOperation::OpenProject.name(), Message::OpenFileDialog(FileOperation::OpenProject));
+        let save_project_button = Self::create_menu_bar_button(FileOperation::SaveProject.name(), Message::OpenFileDialog(FileOperation::SaveProject));
+
+        let menu_bar = container(
+            row![
+                open_button,
+                save_button,
+                open_project_button,
+                save_project_button,
+                abstract_machine_button,
+                operations_button,
+                latex_button,
+                horizontal_space(),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center)
+        )
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.176, 0.172, 0.176, 1.0).into()), 
+                border: iced::Border {
+                    color: iced::Color::from_rgba(0.3, 0.3, 0.3, 1.0), 
+                    width: 1.0,
+                    radius: 0.0.into(),
+                },
+                ..Default::default()
+            }
+        })
+        .padding([4, 8])
+        .width(Length::Fill);
+
+        menu_bar.into()
+    }
+
+    // Button of the menu bar that sends the message when it is pressed.
+    fn create_menu_bar_button(name: &str, message: Message) -> button::Button<'_, Message> {
+        button(text(name))
+            .on_press(message)
+            .style(|_theme: &iced::Theme, status| {
+                let background_color = iced::Color::from_rgba(0.176, 0.172, 0.176, 1.0);
+                let hover_color = iced::Color::from_rgba(0.25, 0.24, 0.25, 1.0);
+                let text_color = iced::Color::WHITE;
+                match status {
+                    button::Status::Hovered => button::Style {
+                        background: Some(hover_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    },
+                    _ => button::Style {
+                        background: Some(background_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                }
+            })
+            .padding([4, 12])
+    }
+
+    fn create_operations_menu(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0); 
+
+        let menu_items = column![
+            Self::create_menu_item("Check Input", Message::CheckInput),
+            Self::create_menu_item("Test Suite", Message::OpenTestSuite),
+            Self::create_menu_item("DFA to NFA", Message::DfaToNfa),
+            Self::create_menu_item("Minimize", Message::Minimize),
+            Self::create_menu_item("Machine to Grammar", Message::MachineToGrammar),
+            Self::create_menu_item("Machine to Text", Message::MachineToText),
+            Self::create_menu_item("Text to Machine", Message::OpenAutomatonText),
+            Self::create_menu_item("NFA Ambiguity", Message::OpenAmbiguityDialog),
+            Self::create_menu_item(GrammarOperation::ToFinite.name(), Message::OpenGrammarDialog(GrammarOperation::ToFinite)),
+            Self::create_menu_item(GrammarOperation::ToPushdown.name(), Message::OpenGrammarDialog(GrammarOperation::ToPushdown)),
+            Self::create_menu_item(GrammarOperation::ToChomskyNormalForm.name(), Message::OpenGrammarDialog(GrammarOperation::ToChomskyNormalForm)),
+            Self::create_menu_item(GrammarOperation::ToGreibachNormalForm.name(), Message::OpenGrammarDialog(GrammarOperation::ToGreibachNormalForm)),
+            Self::create_menu_item(GrammarOperation::Analysis.name(), Message::OpenGrammarDialog(GrammarOperation::Analysis)),
+            Self::create_menu_item(GrammarOperation::Ambiguity.name(), Message::OpenGrammarDialog(GrammarOperation::Ambiguity)),
+            Self::create_menu_item(GrammarOperation::Generate.name(), Message::OpenGrammarDialog(GrammarOperation::Generate)),
+            Self::create_menu_item(GrammarOperation::Cyk.name(), Message::OpenGrammarDialog(GrammarOperation::Cyk)),
+            Self::create_menu_item(GrammarOperation::Earley.name(), Message::OpenGrammarDialog(GrammarOperation::Earley)),
+            Self::create_menu_item(GrammarOperation::LL1.name(), Message::OpenGrammarDialog(GrammarOperation::LL1)),
+            Self::create_menu_item(GrammarOperation::LR.name(), Message::OpenGrammarDialog(GrammarOperation::LR)),
+        ]
+        .spacing(2)
+        .width(150);
+
+        container(menu_items)
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .padding(4)
+            .into()
+    }
+
+    // A button of the dropdown menus.
+    fn create_menu_item(label: &'static str, message: Message) -> Element<'static, Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0); 
+        let menu_button_hover_color = iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0); 
+        let text_color = iced::Color::WHITE;
+
+        button(text(label))
+            .on_press(message)
+            .width(Length::Fill)
+            .style(move |_theme: &iced::Theme, status| {
+                match status {
+                    button::Status::Hovered => button::Style {
+                        background: Some(menu_button_hover_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    },
+                    _ => button::Style {
+                        background: Some(menu_background_color.into()),
+                        text_color,
+                        border: iced::Border::default(),
+                        ..Default::default()
+                    }
+                }
+            })
+            .padding([4, 8])
+            .into()
+    }
+
+    fn create_machine_menu(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0); 
+
+        let mut menu_items = column![].spacing(2).width(170);
+        for kind in [MachineKind::Finite, MachineKind::Pushdown, MachineKind::Turing] {
+            menu_items = menu_items.push(Self::create_menu_item(kind.name(), Message::NewMachineTab(kind)));
+        }
+
+        container(menu_items)
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .padding(4)
+            .into()
+    }
+
+    /* Panel shown next to the canvas after checking an input on a pushdown
+     * automaton, it shows one configuration of the accepting run at a time
+     * with the stack drawn from the top to the bottom. */
+    fn create_item_sets_panel(&self) -> Element<Message> {
+        let panel_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+
+        let mut item_sets = column![].spacing(8);
+        for (name, items) in self.get_active_tab().item_sets.iter().flatten() {
+            let mut item_column = column![text(name.clone()).size(15).color(text_color)].spacing(2);
+            for item in items.iter() {
+                item_column = item_column.push(text(item.clone()).size(14).font(iced::Font::MONOSPACE).color(text_color));
+            }
+            item_sets = item_sets.push(item_column);
+        }
+
+        let content = column![
+            text("LR(0) Items").size(17).color(text_color),
+            iced::widget::scrollable(item_sets).height(Length::Fill),
+            button("Close").on_press(Message::CloseItemSets).padding([4, 8]),
+        ]
+        .spacing(8);
+
+        container(content.padding(12))
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(panel_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .width(260)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn create_simulation_panel(&self) -> Element<Message> {
+        let panel_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let active_tab = self.get_active_tab();
+
+        let mut content = column![
+            text("Simulation").size(17).color(text_color),
+        ]
+        .spacing(8);
+
+        if let Some(configuration) = active_tab.simulation_trace.as_ref()
+            .and_then(|trace| trace.get(active_tab.simulation_step)) {
+            let trace_len = active_tab.simulation_trace.as_ref().map_or(0, |trace| trace.len());
+            let state_name = active_tab.states.iter()
+                .find(|s| s.id == configuration.state_id as usize)
+                .map_or(configuration.state_id.to_string(), |s| s.label.to_string());
+            let remaining_input = if configuration.remaining_input.is_empty() {
+                "ε".to_string()
+            } else {
+                configuration.remaining_input.clone()
+            };
+            content = content
+                .push(text(format!("Step {} of {}", active_tab.simulation_step + 1, trace_len)).color(text_color))
+                .push(text(format!("State: {}", state_name)).color(text_color))
+                .push(text(format!("Input: {}", remaining_input)).color(text_color))
+                .push(text("Stack:").color(text_color));
+
+            let mut stack_column = column![].spacing(2);
+            for symbol in configuration.stack.iter().rev() {
+                stack_column = stack_column.push(
+                    container(text(symbol.clone()).color(text_color))
+                        .center_x(Length::Fill)
+                        .padding([2, 8])
+                        .style(move |_theme: &iced::Theme| {
+                            container::Style {
+                                background: Some(iced::Color::from_rgba(0.25, 0.24, 0.25, 1.0).into()),
+                                border: iced::Border {
+                                    color: border_color,
+                                    width: 1.0,
+                                    radius: 2.0.into(),
+                                },
+                                ..Default::default()
+                            }
+                        })
+                );
+            }
+            content = content.push(iced::widget::scrollable(stack_column).height(Length::Fill));
+        }
+
+        content = content.push(
+            row![
+                button("Prev").on_press(Message::SimulationStepBack).padding([4, 8]),
+                button("Next").on_press(Message::SimulationStepForward).padding([4, 8]),
+                button("Close").on_press(Message::CloseSimulation).padding([4, 8]),
+            ]
+            .spacing(4)
+        );
+
+        container(content.padding(12))
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(panel_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .width(220)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /* Panel shown beneath the canvas after checking an input on a Turing
+     * machine, it shows the tape of one configuration of the run with the
+     * head position, and the controls to step through the run. */
+    fn create_tape_panel(&self) -> Element<Message> {
+        let panel_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let active_tab = self.get_active_tab();
+        let blank_symbol = match &active_tab.machine {
+            Machine::Turing(machine) => machine.get_blank_symbol(),
+            _ => "_",
+        };
+
+        let mut content = column![].spacing(8);
+        if let Some(run) = &active_tab.turing_run {
+            let mut status = row![].spacing(16).align_y(Alignment::Center);
+            if let Some(configuration) = run.trace.get(active_tab.turing_step) {
+                let state_name = active_tab.states.iter()
+                    .find(|s| s.id == configuration.state_id as usize)
+                    .map_or(configuration.state_id.to_string(), |s| s.label.to_string());
+                content = content.push(state_machine::Tape::new(&configuration.tape, configuration.head, blank_symbol).view());
+                status = status
+                    .push(text(format!("Step {} of {}", active_tab.turing_step + 1, run.trace.len())).color(text_color))
+                    .push(text(format!("State: {}", state_name)).color(text_color));
+            }
+            // The result of the run is only shown once its last configuration is reached.
+            if active_tab.turing_step + 1 >= run.trace.len() {
+                status = status.push(text(format!("The input is {}", run.status)).color(text_color));
+            }
+            content = content.push(
+                row![
+                    status,
+                    horizontal_space(),
+                    button("Reset").on_press(Message::TuringReset).padding([4, 8]),
+                    button("Prev").on_press(Message::TuringStepBack).padding([4, 8]),
+                    button("Step").on_press(Message::TuringStep).padding([4, 8]),
+                    button("Run").on_press(Message::TuringRunToEnd).padding([4, 8]),
+                    button("Close").on_press(Message::CloseTuringSimulation).padding([4, 8]),
+                ]
+                .spacing(4)
+                .align_y(Alignment::Center)
+            );
+        }
+
+        container(content.padding(12))
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(panel_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn create_grammar_dialog(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let title = self.grammar_dialog.map_or("", |operation| operation.name());
+        let word_input: Element<Message> = if self.grammar_dialog.is_some_and(|operation| operation.needs_word()) {
+            iced::widget::text_input(self.grammar_dialog.map_or("", |operation| operation.word_placeholder()), &self.grammar_word)
+                .on_input(Message::GrammarWordChanged)
+                .on_submit(Message::SubmitGrammar)
+                .padding(8)
+                .style(|_theme: &iced::Theme, _status| {
+                    iced::widget::text_input::Style {
+                        background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                        border: iced::Border {
+                            color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                            width: 2.0,
+                            radius: 4.0.into(),
+                        },
+                        icon: iced::Color::WHITE,
+                        placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                        value: iced::Color::WHITE,
+                        selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                    }
+                })
+                .into()
+        } else {
+            horizontal_space().height(0).into()
+        };
+
+        let dialog = container(
+            container(
+                iced::widget::column![
+                    iced::widget::text(title)
+                        .size(17)
+                        .color(text_color),
+                    iced::widget::text("Write one nonterminal per line, e.g. S -> aSb | ε")
+                        .size(13)
+                        .color(iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0)),
+                    text_editor(&self.grammar_text)
+                        .on_action(Message::GrammarTextEdited)
+                        .height(160)
+                        .style(|_theme: &iced::Theme, _status| {
+                            text_editor::Style {
+                                background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                                border: iced::Border {
+                                    color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                                    width: 2.0,
+                                    radius: 4.0.into(),
+                                },
+                                icon: iced::Color::WHITE,
+                                placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                                value: iced::Color::WHITE,
+                                selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                            }
+                        }),
+                    word_input,
+                    row![
+                        button("Accept")
+                            .on_press(Message::SubmitGrammar)
+                            .padding([4, 8]),
+                        button("Cancel")
+                            .on_press(Message::CancelGrammar)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(350)
+            )
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            }
+        });
+
+        dialog.into()
+    }
+
+    /* Dialog where an automaton is written or pasted as text, see
+     * automaton_text::parse_automaton, to open it in a new tab. */
+    fn create_automaton_text_dialog(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+
+        let dialog = container(
+            container(
+                iced::widget::column![
+                    iced::widget::text("Text to Machine")
+                        .size(17)
+                        .color(text_color),
+                    iced::widget::text("One statement per line or separated by ;, e.g. initial q0; final q2; q0 -a-> q1; q1 -b,c-> q2")
+                        .size(13)
+                        .color(iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0)),
+                    text_editor(&self.automaton_text)
+                        .on_action(Message::AutomatonTextEdited)
+                        .height(200)
+                        .style(|_theme: &iced::Theme, _status| {
+                            text_editor::Style {
+                                background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                                border: iced::Border {
+                                    color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                                    width: 2.0,
+                                    radius: 4.0.into(),
+                                },
+                                icon: iced::Color::WHITE,
+                                placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                                value: iced::Color::WHITE,
+                                selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                            }
+                        }),
+                    row![
+                        button("Open")
+                            .on_press(Message::SubmitAutomatonText)
+                            .padding([4, 8]),
+                        button("Cancel")
+                            .on_press(Message::CancelAutomatonText)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(400)
+            )
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            }
+        });
+
+        dialog.into()
+    }
+
+    /* Panel where a test suite is written, see test_suite::TestSuite, and
+     * run on the machine of the active tab. After a run every case is shown
+     * in a row, green if the machine gave the expected answer and red
+     * otherwise. */
+    fn create_test_suite_panel(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let pass_color = iced::Color::from_rgb(0.0, 0.8, 0.0);
+        let fail_color = iced::Color::from_rgb(0.8, 0.0, 0.0);
+
+        let mut results = column![].spacing(4);
+        if let Some(report) = &self.test_suite_report {
+            let summary_color = if report.all_passed() { pass_color } else { fail_color };
+            results = results.push(
+                text(format!("{} of {} cases passed", report.passed_count(), report.results.len()))
+                    .size(15)
+                    .color(summary_color)
+            );
+            let mut rows = column![].spacing(2);
+            for result in report.results.iter() {
+                let (mark, color) = if result.passed() { ("pass", pass_color) } else { ("fail", fail_color) };
+                let word = if result.case.word.is_empty() { "ε".to_string() } else { result.case.word.clone() };
+                let answer = if result.accepted { "accepted" } else { "rejected" };
+                rows = rows.push(
+                    row![
+                        text(mark).size(14).color(color).width(40),
+                        text(word).size(14).font(iced::Font::MONOSPACE).color(text_color).width(Length::Fill),
+                        text(format!("expected {}", result.case.expected)).size(14).color(text_color).width(120),
+                        text(answer).size(14).color(color).width(80),
+                    ]
+                    .spacing(8)
+                );
+            }
+            results = results.push(iced::widget::scrollable(rows).height(Length::Shrink));
+        }
+
+        let dialog = container(
+            container(
+                column![
+                    text("Test Suite").size(17).color(text_color),
+                    text("One case per line, e.g. accept abb or reject ε, the lines that start with # are comments")
+                        .size(13)
+                        .color(iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0)),
+                    text_editor(&self.test_suite_text)
+                        .on_action(Message::TestSuiteEdited)
+                        .height(160)
+                        .style(|_theme: &iced::Theme, _status| {
+                            text_editor::Style {
+                                background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                                border: iced::Border {
+                                    color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                                    width: 2.0,
+                                    radius: 4.0.into(),
+                                },
+                                icon: iced::Color::WHITE,
+                                placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                                value: iced::Color::WHITE,
+                                selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                            }
+                        }),
+                    row![
+                        button("Run")
+                            .on_press(Message::RunTestSuite)
+                            .padding([4, 8]),
+                        button("Open")
+                            .on_press(Message::OpenFileDialog(FileOperation::OpenTestSuite))
+                            .padding([4, 8]),
+                        button("Save")
+                            .on_press(Message::OpenFileDialog(FileOperation::SaveTestSuite))
+                            .padding([4, 8]),
+                        button("Close")
+                            .on_press(Message::CloseTestSuite)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8),
+                    results
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(500)
+            )
+            .max_height(600)
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            }
+        });
+
+        dialog.into()
+    }
+
+    /* Panel with the table of the CYK algorithm, drawn as a triangle with
+     * the cells of the whole word on top and the word at the bottom, and
+     * the parse tree of the word when it is accepted. */
+    fn create_cyk_panel(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let cell_width = 80.0;
+        let cell_height = 32.0;
+        let table = match &self.cyk_table {
+            Some(table) => table,
+            None => return horizontal_space().into(),
+        };
+        let word = table.get_word();
+
+        let mut rows = column![].spacing(0);
+        for length in (1..=word.len()).rev() {
+            let mut cells = row![horizontal_space().width((length - 1) as f32 * cell_width / 2.0)];
+            for start in 0..=word.len() - length {
+                let cell = table.get_cell(start, length);
+                let label = if cell.is_empty() {
+                    "∅".to_string()
+                } else {
+                    cell.iter().cloned().collect::<Vec<_>>().join(", ")
+                };
+                cells = cells.push(
+                    container(text(label).size(13).color(text_color))
+                        .center_x(cell_width)
+                        .center_y(cell_height)
+                        .style(move |_theme: &iced::Theme| {
+                            container::Style {
+                                border: iced::Border {
+                                    color: border_color,
+                                    width: 1.0,
+                                    radius: 0.0.into(),
+                                },
+                                ..Default::default()
+                            }
+                        })
+                );
+            }
+            rows = rows.push(cells);
+        }
+        let mut word_row = row![];
+        for symbol in word.iter() {
+            word_row = word_row.push(
+                container(text(symbol.clone()).size(15).color(text_color))
+                    .center_x(cell_width)
+                    .center_y(cell_height)
+            );
+        }
+        rows = rows.push(word_row);
+
+        let (result, result_color) = if table.is_accepted() {
+            ("The word is accepted", iced::Color::from_rgb(0.0, 0.8, 0.0))
+        } else {
+            ("The word is rejected", iced::Color::from_rgb(0.8, 0.0, 0.0))
+        };
+        let parse_tree = table.get_parse_tree().map_or(String::new(), |tree| tree.to_string());
+
+        let dialog = container(
+            container(
+                column![
+                    text("CYK table").size(17).color(text_color),
+                    text(result).size(15).color(result_color),
+                    iced::widget::scrollable(rows)
+                        .direction(iced::widget::scrollable::Direction::Both {
+                            vertical: iced::widget::scrollable::Scrollbar::default(),
+                            horizontal: iced::widget::scrollable::Scrollbar::default(),
+                        })
+                        .height(Length::Shrink)
+                        .width(Length::Fill),
+                    iced::widget::scrollable(
+                        text(parse_tree)
+                            .size(14)
+                            .font(iced::Font::MONOSPACE)
+                            .color(text_color)
+                    )
+                    .height(Length::Shrink),
+                    row![
+                        button("LaTeX")
+                            .on_press(Message::ExportCykTable)
+                            .padding([4, 8]),
+                        button("Close")
+                            .on_press(Message::CloseCykTable)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(600)
+            )
+            .max_height(600)
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
                 }
             })
-            .padding([4, 12]);
-
-        let menu_bar = container(
-            row![
-                abstract_machine_button,
-                operations_button,
-                latex_button,
-                horizontal_space(),
-            ]
-            .spacing(4)
-            .align_y(Alignment::Center)
         )
+        .center(iced::Length::Fill)
         .style(|_theme: &iced::Theme| {
             container::Style {
-                background: Some(iced::Color::from_rgba(0.176, 0.172, 0.176, 1.0).into()), 
-                border: iced::Border {
-                    color: iced::Color::from_rgba(0.3, 0.3, 0.3, 1.0), 
-                    width: 1.0,
-                    radius: 0.0.into(),
-                },
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
                 ..Default::default()
             }
-        })
-        .padding([4, 8])
-        .width(Length::Fill);
+        });
 
-        menu_bar.into()
+        dialog.into()
     }
 
-    fn create_operations_menu(&self) -> Element<Message> {
-        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0); 
-        let menu_button_hover_color = iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0); 
+    // Dialog that shows the result of an operation that is not a machine.
+    fn create_text_result_dialog(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
         let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let (title, result) = match &self.text_result {
+            Some((title, result)) => (title.as_str(), result.as_str()),
+            None => ("", ""),
+        };
 
-        let menu_items = column![
-            button(text("Check Input"))
-                .on_press(Message::CheckInput)
-                .width(Length::Fill)
-                .style(move |_theme: &iced::Theme, status| {
-                    match status {
-                        button::Status::Hovered => button::Style {
-                            background: Some(menu_button_hover_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        },
-                        _ => button::Style {
-                            background: Some(menu_background_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        }
-                    }
-                })
-                .padding([4, 8]),
-            button(text("DFA to NFA"))
-                .on_press(Message::DfaToNfa)
-                .width(Length::Fill)
-                .style(move |_theme: &iced::Theme, status| {
-                    match status {
-                        button::Status::Hovered => button::Style {
-                            background: Some(menu_button_hover_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        },
-                        _ => button::Style {
-                            background: Some(menu_background_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        }
-                    }
-                })
-                .padding([4, 8]),
-            button(text("Minimize"))
-                .on_press(Message::Minimize)
-                .width(Length::Fill)
-                .style(move |_theme: &iced::Theme, status| {
-                    match status {
-                        button::Status::Hovered => button::Style {
-                            background: Some(menu_button_hover_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
-                            ..Default::default()
-                        },
-                        _ => button::Style {
-                            background: Some(menu_background_color.into()),
-                            text_color,
-                            border: iced::Border::default(),
+        let dialog = container(
+            container(
+                iced::widget::column![
+                    iced::widget::text(title)
+                        .size(17)
+                        .color(text_color),
+                    container(
+                        iced::widget::scrollable(
+                            iced::widget::text(result)
+                                .size(14)
+                                .font(iced::Font::MONOSPACE)
+                                .color(text_color)
+                        )
+                        .height(Length::Fill)
+                    )
+                    .padding(8)
+                    .max_height(400)
+                    .style(move |_theme: &iced::Theme| {
+                        container::Style {
+                            border: iced::Border {
+                                color: border_color,
+                                width: 1.0,
+                                radius: 4.0.into(),
+                            },
                             ..Default::default()
                         }
-                    }
-                })
-                .padding([4, 8]),
-        ]
-        .spacing(2)
-        .width(120);
-
-        container(menu_items)
+                    }),
+                    row![
+                        button("Copy")
+                            .on_press(Message::CopyTextResult)
+                            .padding([4, 8]),
+                        button("Close")
+                            .on_press(Message::CloseTextResult)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(500)
+            )
             .style(move |_theme: &iced::Theme| {
                 container::Style {
                     background: Some(menu_background_color.into()),
                     border: iced::Border {
-                        color: iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0),
+                        color: border_color,
                         width: 1.0,
                         radius: 4.0.into(),
                     },
                     ..Default::default()
                 }
             })
-            .padding(4)
-            .into()
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            }
+        });
+
+        dialog.into()
     }
 
     fn create_check_input_dialog(&self) -> Element<Message> {
@@ -1006,19 +2723,152 @@ impl App {
         dialog.into()
     }
 
-    fn create_check_result_popup(&self) -> Element<Message> {
+    /* Dialog to write the maximum length of the words tried by the ambiguity
+     * search of a finite automaton. */
+    fn create_ambiguity_dialog(&self) -> Element<Message> {
         let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
         let text_color = iced::Color::WHITE;
         let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
 
-        let result_text = if let Some(result) = self.get_active_tab().check_input_result {
-            if result {
-                "Input is accepted by the automaton :)"
-            } else {
-                "Input is rejected by the automaton :("
+        let dialog = container(
+            container(
+                iced::widget::column![
+                    iced::widget::text("NFA Ambiguity:")
+                        .size(17)
+                        .color(text_color),
+                    iced::widget::text_input("Maximum length, e.g. 6", &self.ambiguity_length)
+                        .on_input(Message::AmbiguityLengthChanged)
+                        .on_submit(Message::FindAmbiguity)
+                        .width(200)
+                        .style(|_theme: &iced::Theme, _status| {
+                            iced::widget::text_input::Style {
+                                background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                                border: iced::Border {
+                                    color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                                    width: 2.0,
+                                    radius: 4.0.into(),
+                                },
+                                icon: iced::Color::WHITE,
+                                placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                                value: iced::Color::WHITE,
+                                selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                            }
+                        }),
+                    row![
+                        button("Search")
+                            .on_press(Message::FindAmbiguity)
+                            .padding([4, 8]),
+                        button("Cancel")
+                            .on_press(Message::CancelAmbiguityDialog)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(250)
+            )
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
             }
-        } else {
-            "No result available"
+        });
+
+        dialog.into()
+    }
+
+    /* Dialog to write the path of the machine file to open or save, the
+     * machine of the active tab is the one saved. */
+    fn create_file_dialog(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+        let operation = self.file_dialog.unwrap_or(FileOperation::Open);
+
+        let dialog = container(
+            container(
+                iced::widget::column![
+                    iced::widget::text(operation.title())
+                        .size(17)
+                        .color(text_color),
+                    iced::widget::text_input(operation.placeholder(), &self.file_path)
+                        .on_input(Message::FilePathChanged)
+                        .on_submit(Message::SubmitFileDialog)
+                        .style(|_theme: &iced::Theme, _status| {
+                            iced::widget::text_input::Style {
+                                background: iced::Background::Color(iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0)),
+                                border: iced::Border {
+                                    color: iced::Color::from_rgba(0.0, 0.5, 1.0, 1.0),
+                                    width: 2.0,
+                                    radius: 4.0.into(),
+                                },
+                                icon: iced::Color::WHITE,
+                                placeholder: iced::Color::from_rgba(0.7, 0.7, 0.7, 1.0),
+                                value: iced::Color::WHITE,
+                                selection: iced::Color::from_rgba(0.0, 0.5, 1.0, 0.3),
+                            }
+                        }),
+                    row![
+                        button(operation.name())
+                            .on_press(Message::SubmitFileDialog)
+                            .padding([4, 8]),
+                        button("Cancel")
+                            .on_press(Message::CancelFileDialog)
+                            .padding([4, 8])
+                    ]
+                    .spacing(8)
+                ]
+                .spacing(8)
+                .padding(12)
+                .width(400)
+            )
+            .style(move |_theme: &iced::Theme| {
+                container::Style {
+                    background: Some(menu_background_color.into()),
+                    border: iced::Border {
+                        color: border_color,
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+        )
+        .center(iced::Length::Fill)
+        .style(|_theme: &iced::Theme| {
+            container::Style {
+                background: Some(iced::Color::from_rgba(0.0, 0.0, 0.0, 0.3).into()),
+                ..Default::default()
+            }
+        });
+
+        dialog.into()
+    }
+
+    fn create_check_result_popup(&self) -> Element<Message> {
+        let menu_background_color = iced::Color::from_rgba(0.15, 0.14, 0.15, 1.0);
+        let text_color = iced::Color::WHITE;
+        let border_color = iced::Color::from_rgba(0.4, 0.4, 0.4, 1.0);
+
+        let result_text = match self.get_active_tab().check_input_result {
+            Some(HaltingStatus::Accept) => "Input is accepted by the automaton :)",
+            Some(HaltingStatus::Reject) => "Input is rejected by the automaton :(",
+            Some(HaltingStatus::Timeout) => "The search gave up before deciding the input, it is not accepted within the limits",
+            None => "No result available",
         };
 
         let dialog = container(
@@ -1489,6 +3339,24 @@ impl App {
         })
         .padding(0);
 
+        let main_content: Element<Message> = if self.get_active_tab().turing_run.is_some() {
+            column![main_content, self.create_tape_panel()].into()
+        } else {
+            main_content.into()
+        };
+
+        let main_content: Element<Message> = if self.get_active_tab().simulation_trace.is_some() {
+            row![main_content, self.create_simulation_panel()].into()
+        } else {
+            main_content
+        };
+
+        let main_content: Element<Message> = if self.get_active_tab().item_sets.is_some() {
+            row![main_content, self.create_item_sets_panel()].into()
+        } else {
+            main_content
+        };
+
         let content_with_menu = column![
             menu_bar,
             tab_bar,
@@ -1529,31 +3397,75 @@ impl App {
             content_with_menu.into()
         };
 
+        if self.machine_menu_open {
+            let machine_menu = self.create_machine_menu();
+            final_content = stack![
+                final_content,
+                container(machine_menu)
+                    .padding(iced::Padding {
+                        top: 40.0,
+                        left: 8.0,
+                        right: 0.0,
+                        bottom: 0.0,
+                    })
+            ].into();
+        }
+
         if self.get_active_tab().editing_state.is_some() || self.get_active_tab().editing_transition.is_some() {
             let edit_dialog = self.create_edit_dialog();
             final_content = iced::widget::stack![final_content, edit_dialog].into();
         }
 
+        if self.grammar_dialog.is_some() {
+            let grammar_dialog = self.create_grammar_dialog();
+            final_content = iced::widget::stack![final_content, grammar_dialog].into();
+        }
+
+        if self.automaton_text_dialog_open {
+            let automaton_text_dialog = self.create_automaton_text_dialog();
+            final_content = iced::widget::stack![final_content, automaton_text_dialog].into();
+        }
+
+        if self.test_suite_open {
+            let test_suite_panel = self.create_test_suite_panel();
+            final_content = iced::widget::stack![final_content, test_suite_panel].into();
+        }
+
         if self.get_active_tab().check_input_dialog_open {
             let check_input_dialog = self.create_check_input_dialog();
             final_content = iced::widget::stack![final_content, check_input_dialog].into();
         }
 
+        if self.ambiguity_dialog_open {
+            let ambiguity_dialog = self.create_ambiguity_dialog();
+            final_content = iced::widget::stack![final_content, ambiguity_dialog].into();
+        }
+
         if self.get_active_tab().check_result_popup_open {
             let check_result_popup = self.create_check_result_popup();
             final_content = iced::widget::stack![final_content, check_result_popup].into();
         }
 
-        if self.error_message.is_some() {
-            let error_popup = self.create_error_popup();
-            final_content = iced::widget::stack![final_content, error_popup].into();
-        }
-
         if self.latex_export_dialog_open {
             let latex_dialog = self.create_latex_export_dialog();
             final_content = iced::widget::stack![final_content, latex_dialog].into();
         }
 
+        if self.cyk_table.is_some() {
+            let cyk_panel = self.create_cyk_panel();
+            final_content = iced::widget::stack![final_content, cyk_panel].into();
+        }
+
+        if self.text_result.is_some() {
+            let text_result_dialog = self.create_text_result_dialog();
+            final_content = iced::widget::stack![final_content, text_result_dialog].into();
+        }
+
+        if self.file_dialog.is_some() {
+            let file_dialog = self.create_file_dialog();
+            final_content = iced::widget::stack![final_content, file_dialog].into();
+        }
+
         // Always show the pending transition dialog on top if open
         if self.get_active_tab().pending_transition_dialog_open {
             let pending_dialog = self.create_edit_dialog();
@@ -1566,6 +3478,12 @@ impl App {
             final_content = iced::widget::stack![final_content, edit_labels_dialog].into();
         }
 
+        // The errors can come from the dialogs, so they are shown above them
+        if self.error_message.is_some() {
+            let error_popup = self.create_error_popup();
+            final_content = iced::widget::stack![final_content, error_popup].into();
+        }
+
         container(final_content)
             .style(|_theme: &iced::Theme| {
                 container::Style {
@@ -1581,3 +3499,23 @@ impl App {
     }
 }
 
+
+/* Path of the file where the session is autosaved and restored from, in the
+ * local data directory of the user, e.g. ~/.local/share/moccacino/session.json. */
+fn session_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|directory| directory.join("moccacino").join("session.json"))
+}
+
+// Stream of the autosave messages, sent from a thread that waits the interval between them.
+fn autosave_ticks() -> iced::futures::channel::mpsc::Receiver<Message> {
+    let (mut sender, receiver) = iced::futures::channel::mpsc::channel(1);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(AUTOSAVE_INTERVAL);
+        if let Err(error) = sender.try_send(Message::Autosave) {
+            if error.is_disconnected() {
+                break;
+            }
+        }
+    });
+    receiver
+}
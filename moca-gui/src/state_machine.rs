@@ -2,7 +2,7 @@ use iced::{alignment, mouse};
 use iced::widget::canvas::{
     self, Canvas, Event, Frame, Geometry, Path, Stroke, Text
 };
-use iced::{Element, Fill, Point, Rectangle, Renderer, Theme, Vector};
+use iced::{Element, Fill, Point, Rectangle, Renderer, Size, Theme, Vector};
 use std::collections::HashSet;
 
 pub trait VectorExt {
@@ -48,6 +48,7 @@ pub struct State {
     shift_pressed: bool,
     alt_pressed: bool,
     deletion_mode: bool,
+    highlighted_state: Option<usize>,
     pub next_id: usize, 
 }
 
@@ -59,6 +60,7 @@ impl Default for State {
             shift_pressed: false,
             alt_pressed: false,
             deletion_mode: false,
+            highlighted_state: None,
             next_id: 0,
         }
     }
@@ -121,6 +123,16 @@ impl State {
         self.deletion_mode
     }
 
+    /* Function to mark the state where a simulation is, or none. */
+    pub fn set_highlighted_state(&mut self, state_id: Option<usize>) {
+        self.highlighted_state = state_id;
+        self.cache.clear();
+    }
+
+    pub fn get_highlighted_state(&self) -> Option<usize> {
+        self.highlighted_state
+    }
+
     pub fn get_current_next_id(&self) -> usize {
         self.next_id
     }
@@ -789,7 +801,7 @@ impl canvas::Program<CanvasMessage> for StateMachine<'_> {
                 }
             }
 
-            StateNode::draw_all(self.states, frame, _theme, self.initial_state, self.final_states);
+            StateNode::draw_all(self.states, frame, _theme, self.initial_state, self.final_states, self.state.get_highlighted_state());
         });
 
         let mut geometries = vec![content];
@@ -830,6 +842,94 @@ impl canvas::Program<CanvasMessage> for StateMachine<'_> {
     }
 }
 
+/* Width of a cell of the tape of a Turing machine. */
+const TAPE_CELL_SIZE: f32 = 36.0;
+
+/* Height of the tape drawn beneath the canvas, the cells and the head. */
+const TAPE_HEIGHT: f32 = 70.0;
+
+/* The tape of a Turing machine drawn around the head, the cells outside of
+ * the written part show the blank symbol and the head is a triangle beneath
+ * the cell it reads. */
+pub struct Tape<'a> {
+    cells: &'a [String],
+    head: usize,
+    blank_symbol: &'a str,
+}
+
+impl<'a> Tape<'a> {
+    pub fn new(cells: &'a [String], head: usize, blank_symbol: &'a str) -> Self {
+        Tape { cells, head, blank_symbol }
+    }
+
+    pub fn view<Message: 'a>(self) -> Element<'a, Message> {
+        Canvas::new(self)
+            .width(Fill)
+            .height(TAPE_HEIGHT)
+            .into()
+    }
+}
+
+impl<Message> canvas::Program<Message> for Tape<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill(
+            &Path::rectangle(Point::ORIGIN, frame.size()),
+            iced::Color::from_rgb(0.1, 0.1, 0.1),
+        );
+
+        // The cell under the head is always in the middle.
+        let center_x = bounds.width / 2.0;
+        let top = 10.0;
+        let visible_cells = (bounds.width / TAPE_CELL_SIZE / 2.0).ceil() as isize + 1;
+        for offset in -visible_cells..=visible_cells {
+            let index = self.head as isize + offset;
+            let symbol = usize::try_from(index).ok()
+                .and_then(|index| self.cells.get(index))
+                .map_or(self.blank_symbol, |symbol| symbol.as_str());
+            let x = center_x + offset as f32 * TAPE_CELL_SIZE - TAPE_CELL_SIZE / 2.0;
+            let cell = Path::rectangle(Point::new(x, top), Size::new(TAPE_CELL_SIZE, TAPE_CELL_SIZE));
+            if offset == 0 {
+                frame.fill(&cell, iced::Color::from_rgb(0.9, 0.6, 0.2));
+            }
+            frame.stroke(
+                &cell,
+                Stroke::default()
+                    .with_width(1.5)
+                    .with_color(iced::Color::WHITE),
+            );
+            frame.fill_text(Text {
+                content: symbol.to_string(),
+                position: Point::new(x + TAPE_CELL_SIZE / 2.0, top + TAPE_CELL_SIZE / 2.0),
+                color: iced::Color::WHITE,
+                size: 16.0.into(),
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+        }
+
+        let tip_y = top + TAPE_CELL_SIZE + 4.0;
+        let mut path_builder = canvas::path::Builder::new();
+        path_builder.move_to(Point::new(center_x, tip_y));
+        path_builder.line_to(Point::new(center_x - 8.0, tip_y + 12.0));
+        path_builder.line_to(Point::new(center_x + 8.0, tip_y + 12.0));
+        path_builder.close();
+        frame.fill(&path_builder.build(), iced::Color::from_rgb(0.9, 0.6, 0.2));
+
+        vec![frame.into_geometry()]
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct StateNode {
     pub id: usize,
@@ -847,10 +947,15 @@ impl StateNode {
         StateNode { id: 0, position, radius, label } 
     }
 
-    fn draw(&self, frame: &mut Frame, _theme: &Theme, is_initial: bool, is_final: bool) {
+    fn draw(&self, frame: &mut Frame, _theme: &Theme, is_initial: bool, is_final: bool, is_highlighted: bool) {
+        let fill_color = if is_highlighted {
+            iced::Color::from_rgb(0.9, 0.6, 0.2)
+        } else {
+            iced::Color::from_rgb(0.2, 0.7, 0.4)
+        };
         frame.fill(
             &Path::circle(self.position, self.radius),
-            iced::Color::from_rgb(0.2, 0.7, 0.4), 
+            fill_color, 
         );
 
         frame.stroke(
@@ -912,12 +1017,14 @@ impl StateNode {
         frame: &mut Frame,
         _theme: &Theme,
         initial_state: Option<usize>,
-        final_states: &HashSet<usize>
+        final_states: &HashSet<usize>,
+        highlighted_state: Option<usize>
     ) {
         for node in nodes {
             let is_initial = initial_state == Some(node.id);
             let is_final = final_states.contains(&node.id);
-            node.draw(frame, _theme, is_initial, is_final);
+            let is_highlighted = highlighted_state == Some(node.id);
+            node.draw(frame, _theme, is_initial, is_final, is_highlighted);
         }
     }
 }
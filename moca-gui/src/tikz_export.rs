@@ -1,153 +1,78 @@
-// This module generates TikZ code for automata diagrams.
-// The generated code uses only the tikzpicture environment and automata library styles.
+// This module generates TikZ code for the tables shown by the editor, the
+// automata diagrams are written by moca_data::tikz.
 
-use crate::state_machine::{StateNode, Transition};
-use std::collections::HashSet;
+use moca_data::cyk::CykTable;
 
-/// Exports the automaton to TikZ/PGF code using tikzpicture and automata styles.
-/// - `states`: all state nodes (with positions and labels)
-/// - `transitions`: all transitions (with from/to, label, and points)
-/// - `initial_state`: optional id of the initial state
-/// - `final_states`: set of ids of final states
+/// Exports the table of the CYK algorithm to TikZ code, drawn as a triangle
+/// with the cells of the subwords of length 1 in the bottom row, over the word.
+/// - `table`: the filled table of the CYK algorithm
 ///
 /// Returns a String containing the TikZ code.
-pub fn export_to_tikz(
-    states: &[StateNode],
-    transitions: &[Transition],
-    initial_state: Option<usize>,
-    final_states: &HashSet<usize>,
-) -> String {
-    // Find bounds for normalization
-    let (min_x, min_y, max_x, max_y) = states.iter().fold(
-        (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
-        |(min_x, min_y, max_x, max_y), s| {
-            (
-                min_x.min(s.position.x),
-                min_y.min(s.position.y),
-                max_x.max(s.position.x),
-                max_y.max(s.position.y),
-            )
-        },
-    );
-    let width = (max_x - min_x).max(1.0);
-    let height = (max_y - min_y).max(1.0);
-    let scale = 5.0 / width.max(height); 
-
-    // Map state id to node name
-    let mut id_to_name = std::collections::HashMap::new();
-    for (i, s) in states.iter().enumerate() {
-        id_to_name.insert(s.id, format!("q{}", i));
-    }
+pub fn export_cyk_table_to_tikz(table: &CykTable) -> String {
+    let cell_width = 1.6;
+    let cell_height = 0.7;
+    let word = table.get_word();
 
     let mut tikz = String::new();
     tikz.push_str("% Paste this into your LaTeX document\n");
-    tikz.push_str("% Requires: \\usepackage{tikz} and \\usetikzlibrary{arrows.meta, automata, positioning}\n");
+    tikz.push_str("% Requires: \\usepackage{tikz}\n");
     tikz.push_str("\\begin{center}\n");
-    tikz.push_str("\\begin{tikzpicture}[shorten >=1pt, node distance=2cm, on grid, initial text=, auto]\n");
+    tikz.push_str("\\begin{tikzpicture}\n");
 
-    // Draw states using automata library shapes
-    for s in states {
-        let x = (s.position.x - min_x) * scale;
-        let y = (s.position.y - min_y) * scale;
-        let name = id_to_name.get(&s.id).unwrap();
-        let is_initial = initial_state == Some(s.id);
-        let is_final = final_states.contains(&s.id);
-        let mut style = String::from("state");
-        if is_initial {
-            style.push_str(", initial");
-        }
-        if is_final {
-            style.push_str(", accepting");
-        }
-        // Format label for math mode: convert q0 -> q_0, q12 -> q_{12}, etc.
-        let mut latex_label = s.label.to_string();
-        if let Some((_prefix, _digits)) = latex_label.split_once(|c: char| c.is_ascii_digit()) {
-            let idx = s.label.chars().position(|c| c.is_ascii_digit()).unwrap_or(0);
-            let (prefix, digits) = s.label.split_at(idx);
-            if !digits.is_empty() {
-                if digits.len() == 1 {
-                    latex_label = format!("{}_{}", prefix, digits);
-                } else {
-                    latex_label = format!("{}_{{{}}}", prefix, digits);
-                }
-            }
-        }
+    // Draw the word below the table
+    for (position, symbol) in word.iter().enumerate() {
         tikz.push_str(&format!(
-            "  \\node[{}] ({}) at ({:.2}, {:.2}) {{${}$}};\n",
-            style,
-            name,
-            x,
-            -y, 
-            latex_label
+            "  \\node at ({:.2}, {:.2}) {{${}$}};\n",
+            (position as f32 + 0.5) * cell_width,
+            -0.5 * cell_height,
+            latex_symbol(symbol)
         ));
     }
 
-    // Group transitions by (from, to) pairs to handle multiple labels
-    let mut transition_groups: std::collections::HashMap<(usize, usize), Vec<String>> = std::collections::HashMap::new();
-    
-    for t in transitions {
-        let key = (t.from_state_id, t.to_state_id);
-        let mut label = t.label.to_string();
-        if label.trim() == "ε" {
-            label = String::from("$\\varepsilon$");
-        }
-        transition_groups.entry(key).or_insert_with(Vec::new).push(label);
-    }
-
-    // Draw transitions with stacked labels
-    for ((from_id, to_id), labels) in &transition_groups {
-        let from = id_to_name.get(from_id).unwrap();
-        let to = id_to_name.get(to_id).unwrap();
-        
-        if from_id == to_id {
-            // Self-loop with stacked labels
-            if labels.len() == 1 {
-                tikz.push_str(&format!(
-                    "  \\path[->] ({}) edge[loop above] node{{{}}} ({});\n",
-                    from, labels[0], to
-                ));
-            } else {
-                // Multiple labels for loop - stack them vertically
-                tikz.push_str(&format!(
-                    "  \\path[->] ({}) edge[loop above] node[align=center]{{{}}} ({});\n",
-                    from, labels.join("\\\\"), to
-                ));
-            }
-        } else {
-            // Check for reverse edge for curve
-            let has_reverse = transition_groups.contains_key(&(*to_id, *from_id));
-            
-            if has_reverse {
-                if labels.len() == 1 {
-                    tikz.push_str(&format!(
-                        "  \\path[->] ({}) edge[bend left] node{{{}}} ({}) ;\n",
-                        from, labels[0], to
-                    ));
-                } else {
-                    // Multiple labels for curved edge - stack them vertically
-                    tikz.push_str(&format!(
-                        "  \\path[->] ({}) edge[bend left] node[align=center]{{{}}} ({}) ;\n",
-                        from, labels.join("\\\\"), to
-                    ));
-                }
+    // Draw the cells, each row is shifted half a cell to the right
+    for length in 1..=word.len() {
+        for start in 0..=word.len() - length {
+            let x = (start as f32 + (length - 1) as f32 / 2.0) * cell_width;
+            let y = (length - 1) as f32 * cell_height;
+            let cell = table.get_cell(start, length);
+            let label = if cell.is_empty() {
+                String::from("\\emptyset")
             } else {
-                if labels.len() == 1 {
-                    tikz.push_str(&format!(
-                        "  \\path[->] ({}) edge node{{{}}} ({}) ;\n",
-                        from, labels[0], to
-                    ));
-                } else {
-                    // Multiple labels for straight edge - stack them vertically
-                    tikz.push_str(&format!(
-                        "  \\path[->] ({}) edge node[align=center]{{{}}} ({}) ;\n",
-                        from, labels.join("\\\\"), to
-                    ));
-                }
-            }
+                let symbols: Vec<String> = cell.iter().map(|symbol| latex_symbol(symbol)).collect();
+                format!("\\{{{}\\}}", symbols.join(", "))
+            };
+            tikz.push_str(&format!(
+                "  \\draw ({:.2}, {:.2}) rectangle ({:.2}, {:.2});\n",
+                x, y, x + cell_width, y + cell_height
+            ));
+            tikz.push_str(&format!(
+                "  \\node at ({:.2}, {:.2}) {{${}$}};\n",
+                x + cell_width / 2.0,
+                y + cell_height / 2.0,
+                label
+            ));
         }
     }
 
     tikz.push_str("\\end{tikzpicture}\n");
     tikz.push_str("\\end{center}\n");
     tikz
-} 
+}
+
+/// Writes a grammar symbol for math mode, escaping the characters that are special in LaTeX.
+fn latex_symbol(symbol: &str) -> String {
+    let mut latex = String::new();
+    for c in symbol.chars() {
+        match c {
+            'ε' => latex.push_str("\\varepsilon "),
+            '<' => latex.push_str("\\langle "),
+            '>' => latex.push_str("\\rangle "),
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                latex.push('\\');
+                latex.push(c);
+            }
+            _ => latex.push(c),
+        }
+    }
+    latex.trim_end().to_string()
+}